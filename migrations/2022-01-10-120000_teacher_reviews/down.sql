@@ -0,0 +1,10 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE workshops
+    DROP COLUMN teacherweight;
+
+ALTER TABLE reviews
+    DROP COLUMN teacher;
+
+ALTER TABLE submissions
+    DROP COLUMN computedpoints,
+    DROP COLUMN justification;
@@ -0,0 +1,13 @@
+-- Your SQL goes here
+ALTER TABLE workshops
+    ADD teacherweight DOUBLE NOT NULL DEFAULT 1.0;
+
+ALTER TABLE reviews
+    ADD teacher BOOL NOT NULL DEFAULT FALSE;
+
+ALTER TABLE submissions
+    ADD computedpoints DOUBLE,
+    ADD justification  TEXT;
+
+UPDATE submissions
+SET computedpoints = meanpoints;
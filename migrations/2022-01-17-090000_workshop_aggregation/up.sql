@@ -0,0 +1,3 @@
+-- Your SQL goes here
+ALTER TABLE workshops
+    ADD aggregation enum ('mean', 'median', 'trimmedmean', 'weighted') NOT NULL DEFAULT 'mean';
@@ -0,0 +1,3 @@
+-- Your SQL goes here
+ALTER TABLE workshops
+    ADD gradescale enum ('austrian', 'german', 'swiss') NOT NULL DEFAULT 'austrian';
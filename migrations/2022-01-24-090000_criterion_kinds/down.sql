@@ -0,0 +1,9 @@
+-- This file should undo anything in `up.sql`
+DROP TABLE rubriclevels;
+
+DELETE FROM criterion WHERE kind IN ('range', 'rubric', 'text');
+ALTER TABLE criterion
+    DROP COLUMN minpoints,
+    DROP COLUMN maxpoints,
+    DROP COLUMN step,
+    MODIFY kind enum ('point', 'grade', 'percentage', 'truefalse') NOT NULL;
@@ -0,0 +1,16 @@
+-- Your SQL goes here
+ALTER TABLE criterion
+    MODIFY kind enum ('point', 'grade', 'percentage', 'truefalse', 'range', 'rubric', 'text') NOT NULL,
+    ADD minpoints DOUBLE,
+    ADD maxpoints DOUBLE,
+    ADD step DOUBLE;
+
+CREATE TABLE rubriclevels
+(
+    id        SERIAL PRIMARY KEY,
+    criterion BIGINT UNSIGNED NOT NULL,
+    title     VARCHAR(100)    NOT NULL,
+    content   TEXT            NOT NULL,
+    points    DOUBLE          NOT NULL,
+    FOREIGN KEY (criterion) REFERENCES criterion (id) ON DELETE CASCADE
+);
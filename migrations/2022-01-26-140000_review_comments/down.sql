@@ -0,0 +1,6 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE workshops
+    DROP COLUMN commentthreshold;
+
+ALTER TABLE reviewpoints
+    DROP COLUMN comment;
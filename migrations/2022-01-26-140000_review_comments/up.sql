@@ -0,0 +1,6 @@
+-- Your SQL goes here
+ALTER TABLE reviewpoints
+    ADD comment TEXT;
+
+ALTER TABLE workshops
+    ADD commentthreshold DOUBLE;
@@ -0,0 +1,3 @@
+-- Your SQL goes here
+ALTER TABLE workshops
+    ADD draftpolicy enum ('discard', 'acceptcomplete') NOT NULL DEFAULT 'discard';
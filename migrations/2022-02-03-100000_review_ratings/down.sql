@@ -0,0 +1,5 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE workshops
+    DROP COLUMN reviewingweight;
+
+DROP TABLE reviewratings;
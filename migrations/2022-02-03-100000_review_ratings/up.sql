@@ -0,0 +1,11 @@
+-- Your SQL goes here
+CREATE TABLE reviewratings
+(
+    review      BIGINT UNSIGNED NOT NULL PRIMARY KEY,
+    helpfulness INT             NOT NULL,
+    comment     TEXT,
+    FOREIGN KEY (review) REFERENCES reviews (id) ON DELETE CASCADE
+);
+
+ALTER TABLE workshops
+    ADD reviewingweight DOUBLE NOT NULL DEFAULT 0;
@@ -0,0 +1,8 @@
+-- This file should undo anything in `up.sql`
+DROP TABLE appeals;
+
+ALTER TABLE reviews
+    DROP COLUMN discarded;
+
+ALTER TABLE workshops
+    DROP COLUMN appealwindow;
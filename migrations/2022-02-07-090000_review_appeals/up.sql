@@ -0,0 +1,19 @@
+-- Your SQL goes here
+ALTER TABLE workshops
+    ADD appealwindow BIGINT NOT NULL DEFAULT 0;
+
+ALTER TABLE reviews
+    ADD discarded BOOL NOT NULL DEFAULT FALSE;
+
+CREATE TABLE appeals
+(
+    id       SERIAL PRIMARY KEY,
+    review   BIGINT UNSIGNED                                       NOT NULL UNIQUE,
+    student  BIGINT UNSIGNED,
+    reason   TEXT                                                  NOT NULL,
+    status   enum ('open', 'rejected', 'discarded', 'adjusted') NOT NULL DEFAULT 'open',
+    response TEXT,
+    date     DATETIME                                              NOT NULL,
+    FOREIGN KEY (review) REFERENCES reviews (id) ON DELETE CASCADE,
+    FOREIGN KEY (student) REFERENCES users (id) ON DELETE SET NULL
+);
@@ -0,0 +1,4 @@
+-- This file should undo anything in `up.sql`
+DELETE FROM submissions WHERE calibration = TRUE;
+ALTER TABLE submissions
+    DROP COLUMN calibration;
@@ -0,0 +1,3 @@
+-- Your SQL goes here
+ALTER TABLE submissions
+    ADD calibration BOOL NOT NULL DEFAULT FALSE;
@@ -0,0 +1,8 @@
+-- This file should undo anything in `up.sql`
+DELETE FROM reviews WHERE selfassessment = TRUE;
+
+ALTER TABLE reviews
+    DROP COLUMN selfassessment;
+
+ALTER TABLE workshops
+    DROP COLUMN selfassessment;
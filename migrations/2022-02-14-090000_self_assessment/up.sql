@@ -0,0 +1,6 @@
+-- Your SQL goes here
+ALTER TABLE workshops
+    ADD selfassessment BOOL NOT NULL DEFAULT FALSE;
+
+ALTER TABLE reviews
+    ADD selfassessment BOOL NOT NULL DEFAULT FALSE;
@@ -0,0 +1,6 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE workshops
+    DROP COLUMN submissionstart,
+    DROP COLUMN submissionend,
+    DROP COLUMN reviewend,
+    DROP COLUMN resultsdate;
@@ -0,0 +1,6 @@
+-- Your SQL goes here
+ALTER TABLE workshops
+    ADD submissionstart DATETIME,
+    ADD submissionend   DATETIME,
+    ADD reviewend       DATETIME,
+    ADD resultsdate     DATETIME;
@@ -0,0 +1,9 @@
+-- This file should undo anything in `up.sql`
+DROP TABLE submissionextensions;
+
+ALTER TABLE submissions
+    DROP COLUMN late;
+
+ALTER TABLE workshops
+    DROP COLUMN latepolicy,
+    DROP COLUMN latepenalty;
@@ -0,0 +1,17 @@
+-- Your SQL goes here
+ALTER TABLE workshops
+    ADD latepolicy  enum ('block', 'accept') NOT NULL DEFAULT 'block',
+    ADD latepenalty DOUBLE                   NOT NULL DEFAULT 0;
+
+ALTER TABLE submissions
+    ADD late BOOL NOT NULL DEFAULT FALSE;
+
+CREATE TABLE submissionextensions
+(
+    workshop BIGINT UNSIGNED NOT NULL,
+    student  BIGINT UNSIGNED NOT NULL,
+    end      DATETIME        NOT NULL,
+    PRIMARY KEY (workshop, student),
+    FOREIGN KEY (workshop) REFERENCES workshops (id) ON DELETE CASCADE,
+    FOREIGN KEY (student) REFERENCES users (id) ON DELETE CASCADE
+);
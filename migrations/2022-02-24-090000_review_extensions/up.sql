@@ -0,0 +1,22 @@
+-- Your SQL goes here
+CREATE TABLE reviewextensions
+(
+    review BIGINT UNSIGNED NOT NULL PRIMARY KEY,
+    end    DATETIME        NOT NULL,
+    FOREIGN KEY (review) REFERENCES reviews (id) ON DELETE CASCADE
+);
+
+CREATE TABLE extensionhistory
+(
+    id       SERIAL PRIMARY KEY,
+    workshop BIGINT UNSIGNED NOT NULL,
+    student  BIGINT UNSIGNED NOT NULL,
+    review   BIGINT UNSIGNED,
+    teacher  BIGINT UNSIGNED,
+    end      DATETIME,
+    date     DATETIME        NOT NULL,
+    FOREIGN KEY (workshop) REFERENCES workshops (id) ON DELETE CASCADE,
+    FOREIGN KEY (student) REFERENCES users (id) ON DELETE CASCADE,
+    FOREIGN KEY (review) REFERENCES reviews (id) ON DELETE CASCADE,
+    FOREIGN KEY (teacher) REFERENCES users (id) ON DELETE SET NULL
+);
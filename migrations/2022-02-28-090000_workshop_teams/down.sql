@@ -0,0 +1,7 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE submissions
+    DROP FOREIGN KEY submissions_team_fk,
+    DROP COLUMN team;
+
+DROP TABLE teammembers;
+DROP TABLE teams;
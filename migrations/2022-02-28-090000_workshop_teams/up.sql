@@ -0,0 +1,21 @@
+-- Your SQL goes here
+CREATE TABLE teams
+(
+    id       SERIAL PRIMARY KEY,
+    workshop BIGINT UNSIGNED NOT NULL,
+    title    VARCHAR(255)    NOT NULL,
+    FOREIGN KEY (workshop) REFERENCES workshops (id) ON DELETE CASCADE
+);
+
+CREATE TABLE teammembers
+(
+    team    BIGINT UNSIGNED NOT NULL,
+    student BIGINT UNSIGNED NOT NULL,
+    PRIMARY KEY (team, student),
+    FOREIGN KEY (team) REFERENCES teams (id) ON DELETE CASCADE,
+    FOREIGN KEY (student) REFERENCES users (id) ON DELETE CASCADE
+);
+
+ALTER TABLE submissions
+    ADD team BIGINT UNSIGNED,
+    ADD CONSTRAINT submissions_team_fk FOREIGN KEY (team) REFERENCES teams (id) ON DELETE SET NULL;
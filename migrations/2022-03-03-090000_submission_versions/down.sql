@@ -0,0 +1,7 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE reviews
+    DROP FOREIGN KEY reviews_version_fk,
+    DROP COLUMN version;
+
+DROP TABLE versionattachments;
+DROP TABLE submissionversions;
@@ -0,0 +1,35 @@
+-- Your SQL goes here
+CREATE TABLE submissionversions
+(
+    id         SERIAL PRIMARY KEY,
+    submission BIGINT UNSIGNED NOT NULL,
+    version    INT             NOT NULL,
+    title      VARCHAR(255)    NOT NULL,
+    comment    TEXT            NOT NULL,
+    date       DATETIME        NOT NULL,
+    UNIQUE (submission, version),
+    FOREIGN KEY (submission) REFERENCES submissions (id) ON DELETE CASCADE
+);
+
+CREATE TABLE versionattachments
+(
+    version    BIGINT UNSIGNED NOT NULL,
+    attachment BIGINT UNSIGNED NOT NULL,
+    PRIMARY KEY (version, attachment),
+    FOREIGN KEY (version) REFERENCES submissionversions (id) ON DELETE CASCADE,
+    FOREIGN KEY (attachment) REFERENCES attachments (id) ON DELETE CASCADE
+);
+
+ALTER TABLE reviews
+    ADD version BIGINT UNSIGNED,
+    ADD CONSTRAINT reviews_version_fk FOREIGN KEY (version) REFERENCES submissionversions (id) ON DELETE SET NULL;
+
+-- Existing submissions start with their current state as first version
+INSERT INTO submissionversions (submission, version, title, comment, date)
+SELECT id, 1, title, comment, date
+FROM submissions;
+
+INSERT INTO versionattachments (version, attachment)
+SELECT v.id, a.attachment
+FROM submissionversions v
+         INNER JOIN submissionattachments a ON a.submission = v.submission;
@@ -0,0 +1,10 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE submissions
+    DROP FOREIGN KEY submissions_previous_fk,
+    DROP COLUMN round,
+    DROP COLUMN previous;
+
+ALTER TABLE workshops
+    DROP COLUMN rounds,
+    DROP COLUMN samereviewers,
+    DROP COLUMN roundpolicy;
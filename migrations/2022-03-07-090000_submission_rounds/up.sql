@@ -0,0 +1,10 @@
+-- Your SQL goes here
+ALTER TABLE workshops
+    ADD rounds        INT                   NOT NULL DEFAULT 1,
+    ADD samereviewers BOOL                  NOT NULL DEFAULT FALSE,
+    ADD roundpolicy   enum ('last', 'best') NOT NULL DEFAULT 'last';
+
+ALTER TABLE submissions
+    ADD round    INT NOT NULL DEFAULT 1,
+    ADD previous BIGINT UNSIGNED,
+    ADD CONSTRAINT submissions_previous_fk FOREIGN KEY (previous) REFERENCES submissions (id) ON DELETE SET NULL;
@@ -0,0 +1,9 @@
+-- This file should undo anything in `up.sql`
+DROP TABLE emailqueue;
+DROP TABLE emailpreferences;
+
+ALTER TABLE reviews
+    DROP COLUMN reminded;
+
+ALTER TABLE users
+    DROP COLUMN email;
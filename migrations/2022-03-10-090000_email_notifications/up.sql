@@ -0,0 +1,33 @@
+-- Your SQL goes here
+ALTER TABLE users
+    ADD email VARCHAR(255);
+
+ALTER TABLE reviews
+    ADD reminded BOOL NOT NULL DEFAULT FALSE;
+
+CREATE TABLE emailpreferences
+(
+    user     BIGINT UNSIGNED PRIMARY KEY,
+    assigned BOOL NOT NULL DEFAULT TRUE,
+    deadline BOOL NOT NULL DEFAULT TRUE,
+    received BOOL NOT NULL DEFAULT TRUE,
+    workshop BOOL NOT NULL DEFAULT TRUE,
+    FOREIGN KEY (user) REFERENCES users (id) ON DELETE CASCADE
+);
+
+CREATE TABLE emailqueue
+(
+    id          SERIAL PRIMARY KEY,
+    recipient   BIGINT UNSIGNED                                       NOT NULL,
+    address     VARCHAR(255)                                          NOT NULL,
+    kind        enum ('assigned', 'deadline', 'received', 'workshop') NOT NULL,
+    subject     VARCHAR(255)                                          NOT NULL,
+    body        TEXT                                                  NOT NULL,
+    attempts    INT                                                   NOT NULL DEFAULT 0,
+    sent        BOOL                                                  NOT NULL DEFAULT FALSE,
+    failed      BOOL                                                  NOT NULL DEFAULT FALSE,
+    created     DATETIME                                              NOT NULL,
+    nextattempt DATETIME                                              NOT NULL,
+    lasterror   TEXT,
+    FOREIGN KEY (recipient) REFERENCES users (id) ON DELETE CASCADE
+);
@@ -0,0 +1,16 @@
+-- Your SQL goes here
+CREATE TABLE notifications
+(
+    id         SERIAL PRIMARY KEY,
+    user       BIGINT UNSIGNED                                   NOT NULL,
+    kind       enum ('assigned', 'graded', 'workshop', 'appeal') NOT NULL,
+    title      VARCHAR(255)                                      NOT NULL,
+    content    TEXT                                              NOT NULL,
+    workshop   BIGINT UNSIGNED,
+    submission BIGINT UNSIGNED,
+    seen       BOOL                                              NOT NULL DEFAULT FALSE,
+    date       DATETIME                                          NOT NULL,
+    FOREIGN KEY (user) REFERENCES users (id) ON DELETE CASCADE,
+    FOREIGN KEY (workshop) REFERENCES workshops (id) ON DELETE CASCADE,
+    FOREIGN KEY (submission) REFERENCES submissions (id) ON DELETE CASCADE
+);
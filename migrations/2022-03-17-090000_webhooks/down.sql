@@ -0,0 +1,4 @@
+-- This file should undo anything in `up.sql`
+DROP TABLE webhookdeliveries;
+DROP TABLE webhookevents;
+DROP TABLE webhooks;
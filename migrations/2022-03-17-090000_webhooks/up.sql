@@ -0,0 +1,38 @@
+-- Your SQL goes here
+CREATE TABLE webhooks
+(
+    id       SERIAL PRIMARY KEY,
+    owner    BIGINT UNSIGNED NOT NULL,
+    workshop BIGINT UNSIGNED,
+    url      VARCHAR(2048)   NOT NULL,
+    secret   VARCHAR(255)    NOT NULL,
+    active   BOOL            NOT NULL DEFAULT TRUE,
+    created  DATETIME        NOT NULL,
+    FOREIGN KEY (owner) REFERENCES users (id) ON DELETE CASCADE,
+    FOREIGN KEY (workshop) REFERENCES workshops (id) ON DELETE CASCADE
+);
+
+CREATE TABLE webhookevents
+(
+    webhook BIGINT UNSIGNED                                                                          NOT NULL,
+    event   enum ('submission.created', 'review.completed', 'submission.graded', 'workshop.updated') NOT NULL,
+    PRIMARY KEY (webhook, event),
+    FOREIGN KEY (webhook) REFERENCES webhooks (id) ON DELETE CASCADE
+);
+
+CREATE TABLE webhookdeliveries
+(
+    id          SERIAL PRIMARY KEY,
+    webhook     BIGINT UNSIGNED                                                                          NOT NULL,
+    event       enum ('submission.created', 'review.completed', 'submission.graded', 'workshop.updated') NOT NULL,
+    payload     TEXT                                                                                     NOT NULL,
+    attempts    INT                                                                                      NOT NULL DEFAULT 0,
+    delivered   BOOL                                                                                     NOT NULL DEFAULT FALSE,
+    failed      BOOL                                                                                     NOT NULL DEFAULT FALSE,
+    status      INT,
+    lasterror   TEXT,
+    created     DATETIME                                                                                 NOT NULL,
+    nextattempt DATETIME                                                                                 NOT NULL,
+    lastattempt DATETIME,
+    FOREIGN KEY (webhook) REFERENCES webhooks (id) ON DELETE CASCADE
+);
@@ -0,0 +1,8 @@
+-- Your SQL goes here
+CREATE TABLE calendartokens
+(
+    user    BIGINT UNSIGNED PRIMARY KEY,
+    token   VARCHAR(64) NOT NULL UNIQUE,
+    created DATETIME    NOT NULL,
+    FOREIGN KEY (user) REFERENCES users (id) ON DELETE CASCADE
+);
@@ -2,6 +2,11 @@
 
 use crypto::digest::Digest;
 use crypto::sha3::Sha3;
+use rand::distributions::Alphanumeric;
+use rand::Rng;
+
+/// Length of generated access tokens.
+const TOKEN_LENGTH: usize = 40;
 
 /// Hash password
 pub fn hash_password(password: &String) -> String {
@@ -9,3 +14,12 @@ pub fn hash_password(password: &String) -> String {
     hasher.input_str(password);
     hasher.result_str()
 }
+
+/// Generate random alphanumeric access token, e.g. for calendar feeds.
+pub fn generate_token() -> String {
+    rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(TOKEN_LENGTH)
+        .map(char::from)
+        .collect()
+}
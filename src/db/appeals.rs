@@ -0,0 +1,349 @@
+//! CRUD operations for appeals.
+
+use crate::db;
+use crate::db::error::{DbError, DbErrorKind};
+use crate::db::models::*;
+use crate::routes::models::RouteUpdatePoints;
+use crate::schema::appeals::dsl::{
+    appeals as appeals_t, date as a_date, id as a_id, review as a_review, status as a_status,
+};
+use crate::schema::reviewpoints::dsl::{
+    criterion as rp_criterion, review as rp_review, reviewpoints as reviewpoints_t,
+};
+use crate::schema::reviews::dsl::{
+    discarded as reviews_discarded, id as reviews_id, reviews as reviews_t, workshop as reviews_ws,
+};
+use crate::utils::error::AppError;
+use chrono::{Duration, Local};
+use diesel::prelude::*;
+
+/// Appeal a received review.
+/// Only possible for the submission owner within the appeal window after the review deadline.
+pub fn create(
+    conn: &MysqlConnection,
+    review_id: u64,
+    student_id: u64,
+    reason: String,
+) -> Result<Appeal, DbError> {
+    let review = db::reviews::get_by_id(conn, review_id);
+    if review.is_err() {
+        return Err(DbError::new(
+            DbErrorKind::NotFound,
+            format!("Review {} not found", review_id),
+        ));
+    }
+    let review = review.unwrap();
+    if review.error || review.teacher || review.selfassessment {
+        return Err(DbError::new(
+            DbErrorKind::Mismatch,
+            "Only submitted peer reviews can be appealed",
+        ));
+    }
+    let submission = db::submissions::get_by_id(conn, review.submission);
+    if submission.is_err() {
+        return Err(DbError::new(
+            DbErrorKind::ReadFailed,
+            format!("Submission for Review {} not found", review_id),
+        ));
+    }
+    let submission = submission.unwrap();
+    if !db::submissions::is_owner(conn, submission.id, student_id) {
+        return Err(DbError::new(
+            DbErrorKind::Mismatch,
+            "Review does not belong to own Submission",
+        ));
+    }
+    if !submission.reviewsdone {
+        return Err(DbError::new(
+            DbErrorKind::NotFinished,
+            "Reviews of Submission are not done",
+        ));
+    }
+    let workshop = db::workshops::get_by_submission_id(conn, review.submission);
+    if workshop.is_err() {
+        return Err(DbError::new(
+            DbErrorKind::ReadFailed,
+            format!("Workshop for Review {} not found", review_id),
+        ));
+    }
+    let workshop = workshop.unwrap();
+    let now = Local::now().naive_local();
+    if let Err(err) = db::workshops::check_results_released(&workshop, now) {
+        return Err(err);
+    }
+    // Extended reviews have a later deadline, the appeal window starts after it
+    let deadline = db::extensions::get_review_deadline(conn, &review);
+    if now > deadline + Duration::minutes(workshop.appealwindow) {
+        return Err(DbError::new(
+            DbErrorKind::PastDeadline,
+            "Appeal past appeal window",
+        ));
+    }
+    if get_by_review(conn, review_id).is_some() {
+        return Err(DbError::new(
+            DbErrorKind::Mismatch,
+            "Review is already appealed",
+        ));
+    }
+
+    let new_appeal = NewAppeal {
+        review: review_id,
+        student: Some(student_id),
+        reason,
+        status: AppealStatus::Open,
+        date: now,
+    };
+    let insert = diesel::insert_into(appeals_t)
+        .values(&new_appeal)
+        .execute(conn);
+    if insert.is_err() {
+        return Err(DbError::new(
+            DbErrorKind::CreateFailed,
+            "Appeal Insert failed",
+        ));
+    }
+    // Notify teachers of the workshop
+    let teachers = db::workshops::teachers_in_workshop(conn, workshop.id, true);
+    if let Ok(teachers) = teachers {
+        let teacher_ids: Vec<u64> = teachers.into_iter().map(|teacher| teacher.id).collect();
+        if let Err(err) = db::notifications::create(
+            conn,
+            &teacher_ids,
+            NotificationKind::Appeal,
+            "New appeal".to_string(),
+            format!(
+                "A review of \"{}\" in the workshop \"{}\" was appealed",
+                submission.title, workshop.title
+            ),
+            Some(workshop.id),
+            Some(submission.id),
+        ) {
+            err.print_stacktrace();
+        }
+    }
+    match get_by_review(conn, review_id) {
+        Some(appeal) => Ok(appeal),
+        None => Err(DbError::new(
+            DbErrorKind::ReadFailed,
+            "Could not get created Appeal",
+        )),
+    }
+}
+
+/// Resolve an open appeal, only teachers of the workshop can resolve it.
+/// Discarded reviews are not part of the points anymore, adjusted reviews get the given points.
+/// Points of the submission are calculated again afterwards.
+pub fn resolve(
+    conn: &MysqlConnection,
+    teacher_id: u64,
+    appeal_id: u64,
+    status: AppealStatus,
+    response: Option<String>,
+    points: Vec<RouteUpdatePoints>,
+) -> Result<(), DbError> {
+    let appeal = appeals_t.filter(a_id.eq(appeal_id)).first::<Appeal>(conn);
+    if appeal.is_err() {
+        return Err(DbError::new(
+            DbErrorKind::NotFound,
+            format!("Appeal {} not found", appeal_id),
+        ));
+    }
+    let mut appeal = appeal.unwrap();
+    let review = db::reviews::get_by_id(conn, appeal.review);
+    if review.is_err() {
+        return Err(DbError::new(
+            DbErrorKind::ReadFailed,
+            format!("Review for Appeal {} not found", appeal_id),
+        ));
+    }
+    let review = review.unwrap();
+    let workshop = db::workshops::get_by_submission_id(conn, review.submission);
+    if workshop.is_err() {
+        return Err(DbError::new(
+            DbErrorKind::ReadFailed,
+            format!("Workshop for Appeal {} not found", appeal_id),
+        ));
+    }
+    let workshop = workshop.unwrap();
+    if !db::workshops::teacher_in_workshop(conn, teacher_id, workshop.id) {
+        return Err(DbError::new(
+            DbErrorKind::NotFound,
+            format!("Teacher {} not in Workshop {}", teacher_id, workshop.id),
+        ));
+    }
+    if appeal.status != AppealStatus::Open {
+        return Err(DbError::new(
+            DbErrorKind::Mismatch,
+            "Appeal is already resolved",
+        ));
+    }
+    let criteria = db::submissions::get_criteria(conn, review.submission);
+    if criteria.is_err() {
+        return Err(DbError::new(
+            DbErrorKind::ReadFailed,
+            "Criteria for Review not found",
+        ));
+    }
+    let criteria = criteria.unwrap();
+    if let Err(err) = db::reviews::check_points(conn, &criteria, &points) {
+        return Err(err);
+    }
+
+    let mut t_error: Result<(), DbError> = Ok(());
+    let res = conn.transaction::<_, _, _>(|| {
+        match status {
+            AppealStatus::Discarded => {
+                let discard = diesel::update(reviews_t.filter(reviews_id.eq(review.id)))
+                    .set(reviews_discarded.eq(true))
+                    .execute(conn);
+                if discard.is_err() {
+                    return DbError::assign_and_rollback(
+                        &mut t_error,
+                        DbError::new(DbErrorKind::UpdateFailed, "Review Discard failed"),
+                    );
+                }
+            }
+            AppealStatus::Adjusted => {
+                let update_ids: Vec<u64> = points.iter().map(|p| p.id).collect();
+                let review_points =
+                    db::reviews::to_review_points(review.id, &criteria, &workshop, points);
+                let delete = diesel::delete(
+                    reviewpoints_t
+                        .filter(rp_review.eq(review.id).and(rp_criterion.eq_any(update_ids))),
+                )
+                .execute(conn);
+                if delete.is_err() {
+                    return DbError::assign_and_rollback(
+                        &mut t_error,
+                        DbError::new(DbErrorKind::DeleteFailed, "Review Points Delete failed"),
+                    );
+                }
+                let insert = diesel::insert_into(reviewpoints_t)
+                    .values(&review_points)
+                    .execute(conn);
+                if insert.is_err() {
+                    return DbError::assign_and_rollback(
+                        &mut t_error,
+                        DbError::new(DbErrorKind::CreateFailed, "Review Points Insert failed"),
+                    );
+                }
+            }
+            _ => {}
+        }
+
+        appeal.status = status;
+        appeal.response = response.filter(|r| r.trim().len() > 0);
+        let update = diesel::update(appeals_t.filter(a_id.eq(appeal_id)))
+            .set(&appeal)
+            .execute(conn);
+        if update.is_err() {
+            return DbError::assign_and_rollback(
+                &mut t_error,
+                DbError::new(DbErrorKind::UpdateFailed, "Appeal Update failed"),
+            );
+        }
+        Ok(())
+    });
+    if res.is_err() {
+        return Err(t_error.err().unwrap_or(DbError::new(
+            DbErrorKind::TransactionFailed,
+            "Unknown error",
+        )));
+    }
+
+    // Notify student that appealed
+    if let Some(student_id) = appeal.student {
+        if let Err(err) = db::notifications::create(
+            conn,
+            &[student_id],
+            NotificationKind::Appeal,
+            "Appeal resolved".to_string(),
+            format!(
+                "Your appeal in the workshop \"{}\" was resolved",
+                workshop.title
+            ),
+            Some(workshop.id),
+            Some(review.submission),
+        ) {
+            err.print_stacktrace();
+        }
+    }
+
+    db::submissions::recalculate_points(conn, review.submission)
+}
+
+/// Get appeals of a workshop.
+/// Only open appeals are returned if `all` is false.
+/// Only teachers of the workshop can view its appeals.
+pub fn get_workshop_appeals(
+    conn: &MysqlConnection,
+    teacher_id: u64,
+    workshop_id: u64,
+    all: bool,
+) -> Result<Vec<WorkshopAppeal>, DbError> {
+    if !db::workshops::teacher_in_workshop(conn, teacher_id, workshop_id) {
+        return Err(DbError::new(
+            DbErrorKind::NotFound,
+            format!("Teacher {} not in Workshop {}", teacher_id, workshop_id),
+        ));
+    }
+    let mut query = appeals_t
+        .inner_join(reviews_t.on(reviews_id.eq(a_review)))
+        .filter(reviews_ws.eq(workshop_id))
+        .select(crate::schema::appeals::all_columns)
+        .order(a_date)
+        .into_boxed();
+    if !all {
+        query = query.filter(a_status.eq(AppealStatus::Open));
+    }
+    let appeals = query.get_results::<Appeal>(conn);
+    if appeals.is_err() {
+        return Err(DbError::new(
+            DbErrorKind::ReadFailed,
+            format!("No Appeals for Workshop {} found", workshop_id),
+        ));
+    }
+
+    let mut workshop_appeals: Vec<WorkshopAppeal> = Vec::new();
+    for appeal in appeals.unwrap() {
+        let review = db::reviews::get_by_id(conn, appeal.review);
+        if review.is_err() {
+            return Err(DbError::new(
+                DbErrorKind::ReadFailed,
+                format!("Review for Appeal {} not found", appeal.id),
+            ));
+        }
+        let submission = db::submissions::get_by_id(conn, review.unwrap().submission);
+        if submission.is_err() {
+            return Err(DbError::new(
+                DbErrorKind::ReadFailed,
+                format!("Submission for Appeal {} not found", appeal.id),
+            ));
+        }
+        let submission = submission.unwrap();
+        let (firstname, lastname) = match appeal.student {
+            Some(student) => match db::users::get_by_id(conn, student) {
+                Ok(user) => (Some(user.firstname), Some(user.lastname)),
+                Err(_) => (None, None),
+            },
+            None => (None, None),
+        };
+        workshop_appeals.push(WorkshopAppeal {
+            review: appeal.review,
+            submission: submission.id,
+            title: submission.title,
+            firstname,
+            lastname,
+            appeal,
+        });
+    }
+    Ok(workshop_appeals)
+}
+
+/// Get appeal of a review.
+pub fn get_by_review(conn: &MysqlConnection, review_id: u64) -> Option<Appeal> {
+    appeals_t
+        .filter(a_review.eq(review_id))
+        .first::<Appeal>(conn)
+        .ok()
+}
@@ -1,7 +1,5 @@
 //! CRUD operations for attachments.
 
-use crate::db;
-use crate::db::error::{DbError, DbErrorKind};
 use crate::db::models::*;
 use crate::schema::attachments::dsl::{
     attachments as attachments_t, id as att_id, owner as att_owner, title as att_title,
@@ -116,29 +114,6 @@ pub fn get_by_submission_id(
     get_by_submission_id_internal(conn, submission_id)
 }
 
-/// Get all attachments in simplified from from a submission
-/// and lock the submission.
-pub fn get_by_submission_id_and_lock_submission(
-    conn: &MysqlConnection,
-    submission_id: u64,
-) -> Result<Vec<SimpleAttachment>, DbError> {
-    let lock = db::submissions::lock(conn, submission_id);
-    if lock.is_err() {
-        return Err(DbError::new(
-            DbErrorKind::UpdateFailed,
-            "Submission Lock failed",
-        ));
-    }
-    let submission = get_by_submission_id_internal(conn, submission_id);
-    match submission {
-        Ok(submission) => Ok(submission),
-        Err(_) => Err(DbError::new(
-            DbErrorKind::ReadFailed,
-            format!("Attachments for submission {} not found", submission_id),
-        )),
-    }
-}
-
 pub fn get_by_workshop_id(
     conn: &MysqlConnection,
     workshop_id: u64,
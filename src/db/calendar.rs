@@ -0,0 +1,177 @@
+//! Operations for personal calendar feeds.
+//! Feeds are accessed without login through a secret token of the user.
+
+use crate::auth::crypto::generate_token;
+use crate::db;
+use crate::db::error::{DbError, DbErrorKind};
+use crate::db::models::*;
+use crate::schema::calendartokens::dsl::{
+    calendartokens as calendartokens_t, token as ct_token, user as ct_user,
+};
+use crate::schema::submissions::dsl::{
+    calibration as sub_calibration, reviewsdone as sub_reviews_done, student as sub_student,
+    submissions as submissions_t, team as sub_team,
+};
+use crate::schema::teammembers::dsl::{
+    student as tm_student, team as tm_team, teammembers as teammembers_t,
+};
+use crate::utils::calendar::CalendarEvent;
+use chrono::Local;
+use diesel::prelude::*;
+use diesel::result::Error;
+
+/// Get calendar token of an user, a new token is created on first access.
+pub fn get_token(conn: &MysqlConnection, user_id: u64) -> Result<String, DbError> {
+    let token = calendartokens_t
+        .filter(ct_user.eq(user_id))
+        .select(ct_token)
+        .first::<String>(conn);
+    match token {
+        Ok(token) => Ok(token),
+        Err(Error::NotFound) => reset_token(conn, user_id),
+        Err(_) => Err(DbError::new(
+            DbErrorKind::ReadFailed,
+            format!("Could not get Calendar Token of User {}", user_id),
+        )),
+    }
+}
+
+/// Replace calendar token of an user, the old feed url stops working.
+pub fn reset_token(conn: &MysqlConnection, user_id: u64) -> Result<String, DbError> {
+    let calendar_token = CalendarToken {
+        user: user_id,
+        token: generate_token(),
+        created: Local::now().naive_local(),
+    };
+    let replace = diesel::replace_into(calendartokens_t)
+        .values(&calendar_token)
+        .execute(conn);
+    if replace.is_err() {
+        return Err(DbError::new(
+            DbErrorKind::CreateFailed,
+            "Calendar Token Insert failed",
+        ));
+    }
+    Ok(calendar_token.token)
+}
+
+/// Get user of a calendar token.
+pub fn get_user_by_token(conn: &MysqlConnection, token: &str) -> Result<User, Error> {
+    let user_id = calendartokens_t
+        .filter(ct_token.eq(token))
+        .select(ct_user)
+        .first::<u64>(conn);
+    match user_id {
+        Ok(user_id) => db::users::get_by_id(conn, user_id),
+        Err(err) => Err(err),
+    }
+}
+
+/// Get deadlines of an user as calendar events.
+/// Contains the deadlines of assigned reviews, the review windows of own submissions,
+/// the submission deadlines of workshops without submission and the end of all workshops.
+pub fn get_events(conn: &MysqlConnection, user: &User) -> Result<Vec<CalendarEvent>, DbError> {
+    let user_id = user.id;
+    let workshops = db::workshops::get_by_user(conn, user_id);
+    let mut events = Vec::new();
+
+    // Reviews assigned to the user
+    let reviews = db::todos::get_reviews(conn, user_id);
+    if let Err(err) = reviews {
+        return Err(err);
+    }
+    for review in reviews.unwrap().into_iter().filter(|review| !review.done) {
+        events.push(CalendarEvent {
+            uid: format!("review-{}@iprp", review.id),
+            summary: format!("Review deadline: {}", review.title),
+            description: format!("Review of \"{}\" in {}", review.title, review.workshop_name),
+            start: review.deadline,
+            end: None,
+        });
+    }
+
+    // Review windows of own submissions
+    let submissions = get_submissions_in_review(conn, user_id);
+    if submissions.is_err() {
+        return Err(DbError::new(
+            DbErrorKind::ReadFailed,
+            format!("Could not query Submissions of User {}", user_id),
+        ));
+    }
+    for submission in submissions.unwrap() {
+        let workshop_title = workshops
+            .iter()
+            .find(|workshop| workshop.id == submission.workshop)
+            .map(|workshop| workshop.title.clone())
+            .unwrap_or_default();
+        events.push(CalendarEvent {
+            uid: format!("submission-{}@iprp", submission.id),
+            summary: format!("Reviews of {}", submission.title),
+            description: format!(
+                "Review window of \"{}\" in {}, points are shown once all reviews are done",
+                submission.title, workshop_title
+            ),
+            start: submission.date,
+            end: Some(
+                db::extensions::get_latest_review_extension(conn, submission.id)
+                    .map_or(submission.deadline, |end| end.max(submission.deadline)),
+            ),
+        });
+    }
+
+    // Workshops without submission, teachers do not submit
+    let missing_submissions = if user.role == Role::Student {
+        db::todos::get_missing_submissions(conn, user_id)
+    } else {
+        Ok(Vec::new())
+    };
+    if let Err(err) = missing_submissions {
+        return Err(err);
+    }
+    for missing in missing_submissions.unwrap() {
+        if let Some(workshop) = workshops.iter().find(|workshop| workshop.id == missing.id) {
+            let deadline = db::extensions::get_submission_extension(conn, workshop.id, user_id)
+                .unwrap_or(workshop.submissionend.unwrap_or(workshop.end));
+            events.push(CalendarEvent {
+                uid: format!("workshop-{}-submission@iprp", workshop.id),
+                summary: format!("Submission deadline: {}", workshop.title),
+                description: format!("Submission for {} is due", workshop.title),
+                start: deadline,
+                end: None,
+            });
+        }
+    }
+
+    // End of workshops
+    for workshop in workshops.iter() {
+        events.push(CalendarEvent {
+            uid: format!("workshop-{}@iprp", workshop.id),
+            summary: format!("Workshop ends: {}", workshop.title),
+            description: format!("End of {}", workshop.title),
+            start: workshop.end,
+            end: None,
+        });
+    }
+
+    events.sort_by(|a, b| a.start.cmp(&b.start));
+    Ok(events)
+}
+
+// Get own & team submissions of a student whose reviews are not done yet.
+fn get_submissions_in_review(
+    conn: &MysqlConnection,
+    student_id: u64,
+) -> Result<Vec<Submission>, Error> {
+    let teams = teammembers_t
+        .filter(tm_student.eq(student_id))
+        .select(tm_team.nullable());
+    submissions_t
+        .filter(
+            sub_student
+                .eq(student_id)
+                .or(sub_team.eq_any(teams))
+                .and(sub_reviews_done.eq(false))
+                .and(sub_calibration.eq(false)),
+        )
+        .get_results(conn)
+}
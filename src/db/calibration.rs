@@ -0,0 +1,471 @@
+//! CRUD operations for calibration submissions.
+//! Students review calibration submissions before their peer reviews unlock,
+//! their deviation from the reference review of the teacher is used as accuracy.
+
+use crate::db;
+use crate::db::error::{DbError, DbErrorKind};
+use crate::db::models::*;
+use crate::grading::calibration;
+use crate::schema::criterion::dsl::{criterion as criterion_t, id as c_id, title as c_title};
+use crate::schema::reviews::dsl::{
+    done as reviews_done, id as reviews_id, reviewer, reviews as reviews_t,
+    submission as reviews_sub, teacher as reviews_teacher,
+};
+use crate::schema::submissions::dsl::{
+    calibration as sub_calibration, date as sub_date, id as sub_id, submissions as submissions_t,
+    workshop as sub_ws,
+};
+use crate::schema::users::dsl::{
+    firstname as u_firstname, id as u_id, lastname as u_lastname, users as users_t,
+};
+use crate::schema::workshoplist::dsl::{
+    role as wsl_role, user as wsl_user, workshop as wsl_ws, workshoplist as workshoplist_t,
+};
+use crate::schema::workshops::dsl::{id as ws_id, workshops as workshops_t};
+use chrono::Local;
+use diesel::prelude::*;
+use diesel::result::Error;
+use std::collections::{BTreeMap, HashMap};
+
+/// Create a calibration submission for a workshop.
+/// The teacher gets the reference review, every student of the workshop a calibration review.
+/// Calibration reviews can be done until the end of the workshop.
+pub fn create(
+    conn: &MysqlConnection,
+    title: String,
+    comment: String,
+    attachments: Vec<u64>,
+    teacher_id: u64,
+    workshop_id: u64,
+) -> Result<(Submission, Review), DbError> {
+    if !db::workshops::teacher_in_workshop(conn, teacher_id, workshop_id) {
+        return Err(DbError::new(
+            DbErrorKind::NotFound,
+            format!("Teacher {} not in Workshop {}", teacher_id, workshop_id),
+        ));
+    }
+    let workshop: Result<Workshop, _> = workshops_t.filter(ws_id.eq(workshop_id)).first(conn);
+    if workshop.is_err() {
+        return Err(DbError::new(
+            DbErrorKind::NotFound,
+            format!("Workshop {} not found", workshop_id),
+        ));
+    }
+    let workshop = workshop.unwrap();
+    let date = Local::now().naive_local();
+    if date > workshop.end {
+        return Err(DbError::new(
+            DbErrorKind::PastDeadline,
+            "Calibration Submissions cannot be created after the Workshop end",
+        ));
+    }
+
+    let new_submission = NewSubmission {
+        title,
+        comment,
+        student: teacher_id,
+        workshop: workshop_id,
+        date,
+        deadline: workshop.end,
+        locked: true,
+        reviewsdone: false,
+        error: false,
+        calibration: true,
+        late: false,
+        team: None,
+        round: 1,
+        previous: None,
+    };
+
+    let mut t_error: Result<(), DbError> = Ok(());
+    let res = conn.transaction::<(Submission, Review), Error, _>(|| {
+        // Insert submission
+        let submission_insert = diesel::insert_into(submissions_t)
+            .values(&new_submission)
+            .execute(conn);
+        if submission_insert.is_err() {
+            return DbError::assign_and_rollback(
+                &mut t_error,
+                DbError::new(DbErrorKind::CreateFailed, "Submission Insert failed"),
+            );
+        }
+        let submission: Submission = submissions_t.order(sub_id.desc()).first(conn).unwrap();
+
+        if let Err(err) =
+            db::submissions::relate_attachments(conn, submission.id, &[teacher_id], attachments)
+        {
+            return DbError::assign_and_rollback(&mut t_error, err);
+        }
+        if let Err(err) = db::submissions::relate_criteria(conn, submission.id, workshop_id) {
+            return DbError::assign_and_rollback(&mut t_error, err);
+        }
+        if let Err(err) = db::versions::add(conn, submission.id, date) {
+            return DbError::assign_and_rollback(&mut t_error, err);
+        }
+
+        // Reference review of the teacher
+        let reference = NewReview {
+            feedback: "".to_string(),
+            reviewer: Some(teacher_id),
+            submission: submission.id,
+            workshop: workshop_id,
+            deadline: submission.deadline,
+            done: false,
+            locked: false,
+            error: false,
+            teacher: true,
+            discarded: false,
+            selfassessment: false,
+        };
+        let review_insert = diesel::insert_into(reviews_t)
+            .values(&reference)
+            .execute(conn);
+        if review_insert.is_err() {
+            return DbError::assign_and_rollback(
+                &mut t_error,
+                DbError::new(DbErrorKind::CreateFailed, "Review Insert failed"),
+            );
+        }
+        let reference: Review = reviews_t.order(reviews_id.desc()).first(conn).unwrap();
+
+        // Calibration reviews of the students
+        let students = workshoplist_t
+            .filter(wsl_ws.eq(workshop_id).and(wsl_role.eq(Role::Student)))
+            .select(wsl_user)
+            .get_results::<u64>(conn);
+        if students.is_err() {
+            return DbError::assign_and_rollback(
+                &mut t_error,
+                DbError::new(DbErrorKind::ReadFailed, "Could not get Students"),
+            );
+        }
+        let reviews: Vec<NewReview> = students
+            .unwrap()
+            .into_iter()
+            .map(|student_id| calibration_review(&submission, student_id))
+            .collect();
+        let review_insert = diesel::insert_into(reviews_t)
+            .values(&reviews)
+            .execute(conn);
+        if review_insert.is_err() {
+            return DbError::assign_and_rollback(
+                &mut t_error,
+                DbError::new(DbErrorKind::CreateFailed, "Review Insert failed"),
+            );
+        }
+
+        Ok((submission, reference))
+    });
+
+    match res {
+        Ok(res) => Ok(res),
+        Err(_) => Err(t_error.err().unwrap_or(DbError::new(
+            DbErrorKind::TransactionFailed,
+            "Unknown error",
+        ))),
+    }
+}
+
+// Calibration review of a student.
+fn calibration_review(submission: &Submission, student_id: u64) -> NewReview {
+    NewReview {
+        feedback: "".to_string(),
+        reviewer: Some(student_id),
+        submission: submission.id,
+        workshop: submission.workshop,
+        deadline: submission.deadline,
+        done: false,
+        locked: false,
+        error: false,
+        teacher: false,
+        discarded: false,
+        selfassessment: false,
+    }
+}
+
+/// Assign missing calibration reviews to a student.
+/// Needed for students who were added to the workshop after the calibration submissions.
+pub fn assign(conn: &MysqlConnection, workshop_id: u64, student_id: u64) -> Result<(), DbError> {
+    let submissions = get_submissions(conn, workshop_id);
+    if submissions.is_err() {
+        return Err(DbError::new(
+            DbErrorKind::ReadFailed,
+            format!(
+                "Calibration Submissions of Workshop {} not found",
+                workshop_id
+            ),
+        ));
+    }
+    let reviewed = get_reviewed(conn, workshop_id, student_id, false);
+    if reviewed.is_err() {
+        return Err(DbError::new(
+            DbErrorKind::ReadFailed,
+            "Calibration Reviews not found",
+        ));
+    }
+    let reviewed = reviewed.unwrap();
+    let now = Local::now().naive_local();
+    let reviews: Vec<NewReview> = submissions
+        .unwrap()
+        .iter()
+        .filter(|submission| !reviewed.contains(&submission.id) && submission.deadline > now)
+        .map(|submission| calibration_review(submission, student_id))
+        .collect();
+    if reviews.len() == 0 {
+        return Ok(());
+    }
+    let review_insert = diesel::insert_into(reviews_t)
+        .values(&reviews)
+        .execute(conn);
+    if review_insert.is_err() {
+        return Err(DbError::new(
+            DbErrorKind::CreateFailed,
+            "Calibration Review Insert failed",
+        ));
+    }
+    Ok(())
+}
+
+/// Check if a student has submitted all calibration reviews of a workshop.
+/// Workshops without calibration submissions need no calibration.
+pub fn is_done(conn: &MysqlConnection, workshop_id: u64, student_id: u64) -> bool {
+    let submissions = get_submissions(conn, workshop_id);
+    let reviewed = get_reviewed(conn, workshop_id, student_id, true);
+    match (submissions, reviewed) {
+        (Ok(submissions), Ok(reviewed)) => submissions
+            .iter()
+            .all(|submission| reviewed.contains(&submission.id)),
+        _ => false,
+    }
+}
+
+/// Get calibration submissions of a workshop with their reference reviews.
+/// Only teachers of the workshop can view its calibration submissions.
+pub fn get_workshop_calibrations(
+    conn: &MysqlConnection,
+    teacher_id: u64,
+    workshop_id: u64,
+) -> Result<Vec<CalibrationSubmission>, DbError> {
+    if !db::workshops::teacher_in_workshop(conn, teacher_id, workshop_id) {
+        return Err(DbError::new(
+            DbErrorKind::NotFound,
+            format!("Teacher {} not in Workshop {}", teacher_id, workshop_id),
+        ));
+    }
+    let submissions = get_submissions(conn, workshop_id);
+    if submissions.is_err() {
+        return Err(DbError::new(
+            DbErrorKind::ReadFailed,
+            format!(
+                "Calibration Submissions of Workshop {} not found",
+                workshop_id
+            ),
+        ));
+    }
+    Ok(submissions
+        .unwrap()
+        .into_iter()
+        .map(|submission| {
+            let reference = reviews_t
+                .filter(reviews_sub.eq(submission.id).and(reviews_teacher.eq(true)))
+                .first::<Review>(conn)
+                .ok();
+            CalibrationSubmission {
+                id: submission.id,
+                title: submission.title,
+                date: submission.date,
+                reference_done: reference.as_ref().map_or(false, |review| review.done),
+                reference: reference.map(|review| review.id),
+            }
+        })
+        .collect())
+}
+
+/// Get the accuracy of all students of a workshop that did calibration reviews.
+pub fn get_accuracies(
+    conn: &MysqlConnection,
+    workshop_id: u64,
+    grade_scale: &GradeScale,
+) -> Result<HashMap<u64, f64>, DbError> {
+    let deviations = get_deviations(conn, workshop_id, grade_scale);
+    if let Err(err) = deviations {
+        return Err(err);
+    }
+    Ok(deviations
+        .unwrap()
+        .into_iter()
+        .map(|(student_id, deviations)| {
+            let deviations: Vec<f64> = deviations
+                .iter()
+                .flat_map(|deviations| deviations.values().copied())
+                .collect();
+            (student_id, calibration::accuracy(&deviations))
+        })
+        .collect())
+}
+
+/// Get the accuracy and the deviation per criterion of every student of a workshop.
+/// Only teachers of the workshop can view the accuracies.
+pub fn get_reviewer_accuracies(
+    conn: &MysqlConnection,
+    teacher_id: u64,
+    workshop_id: u64,
+) -> Result<Vec<ReviewerAccuracy>, DbError> {
+    if !db::workshops::teacher_in_workshop(conn, teacher_id, workshop_id) {
+        return Err(DbError::new(
+            DbErrorKind::NotFound,
+            format!("Teacher {} not in Workshop {}", teacher_id, workshop_id),
+        ));
+    }
+    let workshop: Result<Workshop, _> = workshops_t.filter(ws_id.eq(workshop_id)).first(conn);
+    if workshop.is_err() {
+        return Err(DbError::new(
+            DbErrorKind::NotFound,
+            format!("Workshop {} not found", workshop_id),
+        ));
+    }
+    let workshop = workshop.unwrap();
+    let deviations = get_deviations(conn, workshop_id, &workshop.gradescale);
+    if let Err(err) = deviations {
+        return Err(err);
+    }
+    let mut deviations = deviations.unwrap();
+    let students = workshoplist_t
+        .inner_join(users_t.on(u_id.eq(wsl_user)))
+        .filter(wsl_ws.eq(workshop_id).and(wsl_role.eq(Role::Student)))
+        .select((u_id, u_firstname, u_lastname))
+        .order(u_id)
+        .get_results::<(u64, String, String)>(conn);
+    if students.is_err() {
+        return Err(DbError::new(
+            DbErrorKind::ReadFailed,
+            format!("No Students for Workshop {} found", workshop_id),
+        ));
+    }
+    let criterion_ids: Vec<u64> = deviations
+        .values()
+        .flatten()
+        .flat_map(|deviations| deviations.keys().copied())
+        .collect();
+    let criteria = criterion_t
+        .filter(c_id.eq_any(criterion_ids))
+        .select((c_id, c_title))
+        .get_results::<(u64, String)>(conn);
+    if criteria.is_err() {
+        return Err(DbError::new(DbErrorKind::ReadFailed, "Criteria not found"));
+    }
+    let titles: HashMap<u64, String> = criteria.unwrap().into_iter().collect();
+
+    Ok(students
+        .unwrap()
+        .into_iter()
+        .map(|(student_id, firstname, lastname)| {
+            let student_deviations = deviations.remove(&student_id).unwrap_or_default();
+            // Mean deviation per criterion
+            let mut per_criterion: BTreeMap<u64, Vec<f64>> = BTreeMap::new();
+            for review_deviations in &student_deviations {
+                for (criterion, deviation) in review_deviations {
+                    per_criterion
+                        .entry(*criterion)
+                        .or_default()
+                        .push(*deviation);
+                }
+            }
+            let all_deviations: Vec<f64> = per_criterion.values().flatten().copied().collect();
+            ReviewerAccuracy {
+                id: student_id,
+                firstname,
+                lastname,
+                done: student_deviations.len(),
+                accuracy: if student_deviations.len() > 0 {
+                    Some(calibration::accuracy(&all_deviations))
+                } else {
+                    None
+                },
+                criteria: per_criterion
+                    .into_iter()
+                    .map(|(criterion, deviations)| CriterionDeviation {
+                        id: criterion,
+                        title: titles.get(&criterion).cloned().unwrap_or_default(),
+                        deviation: deviations.iter().sum::<f64>() / deviations.len() as f64,
+                    })
+                    .collect(),
+            }
+        })
+        .collect())
+}
+
+// Get deviations from the reference reviews for every student.
+// Contains the deviation per criterion for each submitted calibration review.
+fn get_deviations(
+    conn: &MysqlConnection,
+    workshop_id: u64,
+    grade_scale: &GradeScale,
+) -> Result<HashMap<u64, Vec<HashMap<u64, f64>>>, DbError> {
+    let submissions = get_submissions(conn, workshop_id);
+    if submissions.is_err() {
+        return Err(DbError::new(
+            DbErrorKind::ReadFailed,
+            format!(
+                "Calibration Submissions of Workshop {} not found",
+                workshop_id
+            ),
+        ));
+    }
+    let mut deviations: HashMap<u64, Vec<HashMap<u64, f64>>> = HashMap::new();
+    for submission in submissions.unwrap() {
+        let reviews = db::reviews::get_submitted_review_points(conn, submission.id);
+        if let Err(err) = reviews {
+            return Err(err);
+        }
+        let reviews = reviews.unwrap();
+        // Calibration submissions without finished reference review are skipped
+        let reference = reviews.iter().find(|review| review.teacher);
+        if reference.is_none() {
+            continue;
+        }
+        let reference = calibration::criterion_fractions(&reference.unwrap().points, grade_scale);
+        for review in reviews.iter().filter(|review| !review.teacher) {
+            if let Some(student_id) = review.reviewer {
+                let fractions = calibration::criterion_fractions(&review.points, grade_scale);
+                deviations
+                    .entry(student_id)
+                    .or_default()
+                    .push(calibration::deviations(&reference, &fractions));
+            }
+        }
+    }
+    Ok(deviations)
+}
+
+// Get calibration submissions of a workshop.
+fn get_submissions(conn: &MysqlConnection, workshop_id: u64) -> Result<Vec<Submission>, Error> {
+    submissions_t
+        .filter(sub_ws.eq(workshop_id).and(sub_calibration.eq(true)))
+        .order(sub_date)
+        .get_results(conn)
+}
+
+// Get ids of calibration submissions the student reviews.
+// If `done` is true only submitted reviews are considered.
+fn get_reviewed(
+    conn: &MysqlConnection,
+    workshop_id: u64,
+    student_id: u64,
+    done: bool,
+) -> Result<Vec<u64>, Error> {
+    let mut query = reviews_t
+        .inner_join(submissions_t.on(sub_id.eq(reviews_sub)))
+        .filter(
+            sub_ws
+                .eq(workshop_id)
+                .and(sub_calibration.eq(true))
+                .and(reviewer.eq(student_id)),
+        )
+        .select(sub_id)
+        .into_boxed();
+    if done {
+        query = query.filter(reviews_done.eq(true));
+    }
+    query.get_results(conn)
+}
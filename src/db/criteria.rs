@@ -0,0 +1,63 @@
+//! CRUD operations for criteria.
+
+use crate::db::models::*;
+use crate::schema::rubriclevels::dsl::{
+    criterion as rl_criterion, points as rl_points, rubriclevels as rubriclevels_t,
+};
+use diesel::prelude::*;
+use diesel::result::Error;
+
+/// Insert rubric levels of newly created criteria.
+/// `criterion_ids` must be in the same order as the criteria.
+pub(crate) fn insert_levels(
+    conn: &MysqlConnection,
+    criterion_ids: &[u64],
+    levels: Vec<Vec<NewRubricLevel>>,
+) -> Result<(), Error> {
+    let levels: Vec<NewRubricLevel> = criterion_ids
+        .iter()
+        .zip(levels.into_iter())
+        .flat_map(|(criterion_id, levels)| {
+            levels.into_iter().map(move |level| NewRubricLevel {
+                criterion: *criterion_id,
+                ..level
+            })
+        })
+        .collect();
+    if levels.len() == 0 {
+        return Ok(());
+    }
+    diesel::insert_into(rubriclevels_t)
+        .values(&levels)
+        .execute(conn)
+        .map(|_| ())
+}
+
+/// Get rubric levels of a criterion ordered by points.
+pub fn get_levels(conn: &MysqlConnection, criterion_id: u64) -> Result<Vec<RubricLevel>, Error> {
+    rubriclevels_t
+        .filter(rl_criterion.eq(criterion_id))
+        .order(rl_points.asc())
+        .get_results(conn)
+}
+
+/// Add rubric levels to criteria.
+pub fn get_full(
+    conn: &MysqlConnection,
+    criteria: Vec<Criterion>,
+) -> Result<Vec<FullCriterion>, Error> {
+    let mut full_criteria: Vec<FullCriterion> = Vec::new();
+    for criterion in criteria {
+        let levels = if criterion.kind == Kind::Rubric {
+            let levels = get_levels(conn, criterion.id);
+            if let Err(err) = levels {
+                return Err(err);
+            }
+            levels.unwrap()
+        } else {
+            Vec::new()
+        };
+        full_criteria.push(FullCriterion { criterion, levels });
+    }
+    Ok(full_criteria)
+}
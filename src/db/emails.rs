@@ -0,0 +1,305 @@
+//! Operations for email notifications.
+//! Emails are only queued for users with an address that did not opt out of their kind.
+
+use crate::db;
+use crate::db::error::{DbError, DbErrorKind};
+use crate::db::models::*;
+use crate::mail::templates::{self, Email};
+use crate::schema::emailpreferences::dsl::{emailpreferences as preferences_t, user as pref_user};
+use crate::schema::emailqueue::dsl::{
+    attempts as eq_attempts, emailqueue as emailqueue_t, failed as eq_failed, id as eq_id,
+    lasterror as eq_lasterror, nextattempt as eq_nextattempt, sent as eq_sent,
+};
+use crate::schema::reviews::dsl::{
+    deadline as reviews_deadline, done as reviews_done, id as reviews_id, locked as reviews_locked,
+    reminded as reviews_reminded, reviewer as reviews_reviewer, reviews as reviews_t,
+    teacher as reviews_teacher,
+};
+use crate::schema::users::dsl::{email as u_email, id as u_id, users as users_t};
+use crate::utils::queue;
+use chrono::{Duration, Local};
+use diesel::prelude::*;
+use diesel::result::Error;
+
+/// Get email preferences of an user.
+pub fn get_preference(conn: &MysqlConnection, user_id: u64) -> EmailPreference {
+    preferences_t
+        .filter(pref_user.eq(user_id))
+        .first(conn)
+        .unwrap_or(EmailPreference::new(user_id))
+}
+
+/// Update email address & preferences of an user.
+pub fn update_preference(
+    conn: &MysqlConnection,
+    user_id: u64,
+    email: Option<String>,
+    preference: EmailPreference,
+) -> Result<(), DbError> {
+    let mut t_error: Result<(), DbError> = Ok(());
+    let update = conn.transaction::<(), _, _>(|| {
+        let email_update = diesel::update(users_t.filter(u_id.eq(user_id)))
+            .set(u_email.eq(email))
+            .execute(conn);
+        if email_update.is_err() {
+            return DbError::assign_and_rollback(
+                &mut t_error,
+                DbError::new(DbErrorKind::UpdateFailed, "Email Update failed"),
+            );
+        }
+        let preference_update = diesel::replace_into(preferences_t)
+            .values(&preference)
+            .execute(conn);
+        if preference_update.is_err() {
+            return DbError::assign_and_rollback(
+                &mut t_error,
+                DbError::new(DbErrorKind::UpdateFailed, "Email Preference Update failed"),
+            );
+        }
+        Ok(())
+    });
+    match update {
+        Ok(_) => Ok(()),
+        Err(_) => Err(t_error.err().unwrap_or(DbError::new(
+            DbErrorKind::TransactionFailed,
+            "Unknown error",
+        ))),
+    }
+}
+
+/// Queue email for an user.
+/// Returns false when the user has no address or opted out.
+pub fn enqueue(conn: &MysqlConnection, user: &User, email: Email) -> Result<bool, DbError> {
+    let address = match &user.email {
+        Some(address) => address.clone(),
+        None => return Ok(false),
+    };
+    if !get_preference(conn, user.id).allows(&email.kind) {
+        return Ok(false);
+    }
+    let now = Local::now().naive_local();
+    let new_email = NewQueuedEmail {
+        recipient: user.id,
+        address,
+        kind: email.kind,
+        subject: email.subject,
+        body: email.body,
+        created: now,
+        nextattempt: now,
+    };
+    let insert = diesel::insert_into(emailqueue_t)
+        .values(&new_email)
+        .execute(conn);
+    if insert.is_err() {
+        return Err(DbError::new(
+            DbErrorKind::CreateFailed,
+            format!("Email Insert for User {} failed", user.id),
+        ));
+    }
+    Ok(true)
+}
+
+/// Queue emails for newly assigned reviewers.
+pub fn notify_review_assigned(
+    conn: &MysqlConnection,
+    reviewer_ids: &[u64],
+    workshop: &Workshop,
+    deadline: &chrono::NaiveDateTime,
+) -> Result<(), DbError> {
+    let reviewers = users_t
+        .filter(u_id.eq_any(reviewer_ids))
+        .get_results::<User>(conn);
+    if reviewers.is_err() {
+        return Err(DbError::new(
+            DbErrorKind::ReadFailed,
+            "Could not get Reviewers",
+        ));
+    }
+    for reviewer in reviewers.unwrap() {
+        let email = templates::review_assigned(&reviewer.firstname, &workshop.title, deadline);
+        if let Err(err) = enqueue(conn, &reviewer, email) {
+            return Err(err);
+        }
+    }
+    Ok(())
+}
+
+/// Queue emails for the owners of a submission that received a review.
+pub fn notify_review_received(
+    conn: &MysqlConnection,
+    review: &Review,
+    workshop: &Workshop,
+) -> Result<(), DbError> {
+    let submission = db::submissions::get_by_id(conn, review.submission);
+    if submission.is_err() {
+        return Err(DbError::new(
+            DbErrorKind::NotFound,
+            format!("Submission {} not found", review.submission),
+        ));
+    }
+    let submission = submission.unwrap();
+    let owner_ids = db::submissions::get_owner_ids(conn, &submission);
+    if owner_ids.is_err() {
+        return Err(DbError::new(
+            DbErrorKind::ReadFailed,
+            format!("Owners of Submission {} not found", submission.id),
+        ));
+    }
+    let owners = users_t
+        .filter(u_id.eq_any(owner_ids.unwrap()))
+        .get_results::<User>(conn);
+    if owners.is_err() {
+        return Err(DbError::new(
+            DbErrorKind::ReadFailed,
+            format!("Owners of Submission {} not found", submission.id),
+        ));
+    }
+    for owner in owners.unwrap() {
+        let email =
+            templates::review_received(&owner.firstname, &workshop.title, &submission.title);
+        if let Err(err) = enqueue(conn, &owner, email) {
+            return Err(err);
+        }
+    }
+    Ok(())
+}
+
+/// Queue reminders for reviews that end within the next 24 hours.
+/// Extended reviews are reminded before the end of their extension.
+/// Every review is only reminded once per deadline.
+pub fn queue_deadline_reminders(conn: &MysqlConnection) -> Result<usize, DbError> {
+    let now = Local::now().naive_local();
+    // Extensions only move the deadline back, the stored deadline is the earliest end
+    let reviews = reviews_t
+        .filter(
+            reviews_done
+                .eq(false)
+                .and(reviews_locked.eq(false))
+                .and(reviews_teacher.eq(false))
+                .and(reviews_reminded.eq(false))
+                .and(reviews_reviewer.is_not_null())
+                .and(reviews_deadline.le(now + Duration::hours(24))),
+        )
+        .get_results::<Review>(conn);
+    if reviews.is_err() {
+        return Err(DbError::new(
+            DbErrorKind::ReadFailed,
+            "Could not query Reviews to remind",
+        ));
+    }
+
+    let mut reminded = 0;
+    for review in reviews.unwrap() {
+        let deadline = db::extensions::get_review_deadline(conn, &review);
+        if deadline <= now || deadline > now + Duration::hours(24) {
+            continue;
+        }
+        // Claim review first so that other instances do not remind it again
+        let update = diesel::update(
+            reviews_t.filter(reviews_id.eq(review.id).and(reviews_reminded.eq(false))),
+        )
+        .set(reviews_reminded.eq(true))
+        .execute(conn);
+        match update {
+            Ok(0) => continue,
+            Ok(_) => {}
+            Err(_) => {
+                return Err(DbError::new(
+                    DbErrorKind::UpdateFailed,
+                    "Review Update failed",
+                ))
+            }
+        }
+        let reviewer = db::users::get_by_id(conn, review.reviewer.unwrap());
+        let workshop = db::workshops::get_by_id(conn, review.workshop);
+        let submission = db::submissions::get_by_id(conn, review.submission);
+        if reviewer.is_err() || workshop.is_err() || submission.is_err() {
+            return Err(DbError::new(
+                DbErrorKind::ReadFailed,
+                format!("Could not get details of Review {}", review.id),
+            ));
+        }
+        let reviewer = reviewer.unwrap();
+        let email = templates::review_deadline(
+            &reviewer.firstname,
+            &workshop.unwrap().title,
+            &submission.unwrap().title,
+            &deadline,
+        );
+        match enqueue(conn, &reviewer, email) {
+            Ok(true) => reminded += 1,
+            Ok(false) => {}
+            Err(err) => return Err(err),
+        }
+    }
+    Ok(reminded)
+}
+
+/// Get ids of queued emails that are due to be sent.
+pub fn get_pending_ids(conn: &MysqlConnection) -> Result<Vec<u64>, Error> {
+    let now = Local::now().naive_local();
+    emailqueue_t
+        .filter(
+            eq_sent
+                .eq(false)
+                .and(eq_failed.eq(false))
+                .and(eq_nextattempt.le(now)),
+        )
+        .order(eq_id)
+        .select(eq_id)
+        .get_results(conn)
+}
+
+/// Lock a queued email that is still pending.
+/// Needs to be called inside a transaction.
+pub fn lock_pending(conn: &MysqlConnection, email_id: u64) -> Result<QueuedEmail, Error> {
+    emailqueue_t
+        .filter(
+            eq_id
+                .eq(email_id)
+                .and(eq_sent.eq(false))
+                .and(eq_failed.eq(false)),
+        )
+        .for_update()
+        .first(conn)
+}
+
+/// Mark queued email as sent.
+pub fn mark_sent(conn: &MysqlConnection, email: &QueuedEmail) -> Result<(), DbError> {
+    let update = diesel::update(emailqueue_t.filter(eq_id.eq(email.id)))
+        .set((eq_sent.eq(true), eq_attempts.eq(email.attempts + 1)))
+        .execute(conn);
+    if update.is_err() {
+        return Err(DbError::new(
+            DbErrorKind::UpdateFailed,
+            "Email Update failed",
+        ));
+    }
+    Ok(())
+}
+
+/// Mark failed attempt of a queued email.
+/// The next attempt is delayed by the backoff of the queue, after `max_attempts` the email is given up.
+pub fn mark_failed(
+    conn: &MysqlConnection,
+    email: &QueuedEmail,
+    error: String,
+    max_attempts: i32,
+) -> Result<(), DbError> {
+    let attempts = email.attempts + 1;
+    let update = diesel::update(emailqueue_t.filter(eq_id.eq(email.id)))
+        .set((
+            eq_attempts.eq(attempts),
+            eq_failed.eq(attempts >= max_attempts),
+            eq_nextattempt.eq(Local::now().naive_local() + queue::backoff(attempts)),
+            eq_lasterror.eq(Some(error)),
+        ))
+        .execute(conn);
+    if update.is_err() {
+        return Err(DbError::new(
+            DbErrorKind::UpdateFailed,
+            "Email Update failed",
+        ));
+    }
+    Ok(())
+}
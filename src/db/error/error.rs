@@ -9,6 +9,8 @@ use std::fmt::{Debug, Formatter};
 pub enum DbErrorKind {
     NotFound,
     PastDeadline,
+    NotStarted,
+    NotFinished,
     Mismatch,
     CreateFailed,
     ReadFailed,
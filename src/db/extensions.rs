@@ -0,0 +1,483 @@
+//! CRUD operations for deadline extensions.
+//! Teachers can extend the submission phase of a workshop or the deadline of a review
+//! for single students, every granted or revoked extension is kept in the history.
+
+use crate::db;
+use crate::db::error::{DbError, DbErrorKind};
+use crate::db::models::*;
+use crate::schema::extensionhistory::dsl::{
+    date as eh_date, extensionhistory as extensionhistory_t, student as eh_student,
+    workshop as eh_workshop,
+};
+use crate::schema::reviewextensions::dsl::{
+    end as re_end, review as re_review, reviewextensions as reviewextensions_t,
+};
+use crate::schema::reviews::dsl::{
+    id as reviews_id, reminded as reviews_reminded, reviewer as reviews_reviewer,
+    reviews as reviews_t, submission as reviews_sub, workshop as reviews_ws,
+};
+use crate::schema::submissionextensions::dsl::{
+    end as se_end, student as se_student, submissionextensions as submissionextensions_t,
+    workshop as se_workshop,
+};
+use crate::schema::users::dsl::{
+    firstname as u_firstname, id as u_id, lastname as u_lastname, users as users_t,
+};
+use chrono::Local;
+use diesel::dsl::max;
+use diesel::prelude::*;
+use diesel::result::Error;
+
+/// Grant a student an extension of the submission phase.
+/// An existing extension of the student is replaced.
+pub fn grant_submission_extension(
+    conn: &MysqlConnection,
+    teacher_id: u64,
+    workshop_id: u64,
+    student_id: u64,
+    end: chrono::NaiveDateTime,
+) -> Result<(), DbError> {
+    if !db::workshops::teacher_in_workshop(conn, teacher_id, workshop_id) {
+        return Err(DbError::new(
+            DbErrorKind::NotFound,
+            format!("Teacher {} not in Workshop {}", teacher_id, workshop_id),
+        ));
+    }
+    if !db::workshops::student_in_workshop(conn, student_id, workshop_id) {
+        return Err(DbError::new(
+            DbErrorKind::NotFound,
+            format!("Student {} not in Workshop {}", student_id, workshop_id),
+        ));
+    }
+
+    let extension = SubmissionExtension {
+        workshop: workshop_id,
+        student: student_id,
+        end,
+    };
+    let mut t_error: Result<(), DbError> = Ok(());
+    let res = conn.transaction::<(), Error, _>(|| {
+        let insert = diesel::replace_into(submissionextensions_t)
+            .values(&extension)
+            .execute(conn);
+        if insert.is_err() {
+            return DbError::assign_and_rollback(
+                &mut t_error,
+                DbError::new(
+                    DbErrorKind::CreateFailed,
+                    "Could not save Submission Extension",
+                ),
+            );
+        }
+        if let Err(err) = add_history(conn, workshop_id, student_id, None, teacher_id, Some(end)) {
+            return DbError::assign_and_rollback(&mut t_error, err);
+        }
+        Ok(())
+    });
+
+    match res {
+        Ok(_) => Ok(()),
+        Err(_) => Err(t_error.err().unwrap_or(DbError::new(
+            DbErrorKind::TransactionFailed,
+            "Unknown error",
+        ))),
+    }
+}
+
+/// Revoke the submission extension of a student.
+pub fn revoke_submission_extension(
+    conn: &MysqlConnection,
+    teacher_id: u64,
+    workshop_id: u64,
+    student_id: u64,
+) -> Result<(), DbError> {
+    if !db::workshops::teacher_in_workshop(conn, teacher_id, workshop_id) {
+        return Err(DbError::new(
+            DbErrorKind::NotFound,
+            format!("Teacher {} not in Workshop {}", teacher_id, workshop_id),
+        ));
+    }
+
+    let mut t_error: Result<(), DbError> = Ok(());
+    let res = conn.transaction::<(), Error, _>(|| {
+        let delete = diesel::delete(
+            submissionextensions_t
+                .filter(se_workshop.eq(workshop_id).and(se_student.eq(student_id))),
+        )
+        .execute(conn);
+        match delete {
+            Ok(0) => {
+                return DbError::assign_and_rollback(
+                    &mut t_error,
+                    DbError::new(
+                        DbErrorKind::NotFound,
+                        format!("No Submission Extension for Student {} found", student_id),
+                    ),
+                )
+            }
+            Ok(_) => {}
+            Err(_) => {
+                return DbError::assign_and_rollback(
+                    &mut t_error,
+                    DbError::new(
+                        DbErrorKind::DeleteFailed,
+                        "Submission Extension Delete failed",
+                    ),
+                )
+            }
+        }
+        if let Err(err) = add_history(conn, workshop_id, student_id, None, teacher_id, None) {
+            return DbError::assign_and_rollback(&mut t_error, err);
+        }
+        Ok(())
+    });
+
+    match res {
+        Ok(_) => Ok(()),
+        Err(_) => Err(t_error.err().unwrap_or(DbError::new(
+            DbErrorKind::TransactionFailed,
+            "Unknown error",
+        ))),
+    }
+}
+
+/// Get the end of the extended submission phase of a student.
+pub fn get_submission_extension(
+    conn: &MysqlConnection,
+    workshop_id: u64,
+    student_id: u64,
+) -> Option<chrono::NaiveDateTime> {
+    submissionextensions_t
+        .filter(se_workshop.eq(workshop_id).and(se_student.eq(student_id)))
+        .select(se_end)
+        .first(conn)
+        .ok()
+}
+
+/// Get all submission extensions of a workshop.
+/// Only teachers of the workshop can see the extensions.
+pub fn get_submission_extensions(
+    conn: &MysqlConnection,
+    teacher_id: u64,
+    workshop_id: u64,
+) -> Result<Vec<StudentExtension>, DbError> {
+    if !db::workshops::teacher_in_workshop(conn, teacher_id, workshop_id) {
+        return Err(DbError::new(
+            DbErrorKind::NotFound,
+            format!("Teacher {} not in Workshop {}", teacher_id, workshop_id),
+        ));
+    }
+    let extensions = submissionextensions_t
+        .inner_join(users_t.on(u_id.eq(se_student)))
+        .filter(se_workshop.eq(workshop_id))
+        .select((se_student, u_firstname, u_lastname, se_end))
+        .order(se_end)
+        .get_results::<(u64, String, String, chrono::NaiveDateTime)>(conn);
+    match extensions {
+        Ok(extensions) => Ok(extensions
+            .into_iter()
+            .map(|(id, firstname, lastname, end)| StudentExtension {
+                id,
+                firstname,
+                lastname,
+                review: None,
+                end,
+            })
+            .collect()),
+        Err(_) => Err(DbError::new(
+            DbErrorKind::ReadFailed,
+            format!(
+                "No Submission Extensions for Workshop {} found",
+                workshop_id
+            ),
+        )),
+    }
+}
+
+/// Extend the deadline of a review for its reviewer.
+/// An existing extension of the review is replaced.
+pub fn grant_review_extension(
+    conn: &MysqlConnection,
+    teacher_id: u64,
+    review_id: u64,
+    end: chrono::NaiveDateTime,
+) -> Result<(), DbError> {
+    let review = db::reviews::get_by_id(conn, review_id);
+    if review.is_err() {
+        return Err(DbError::new(
+            DbErrorKind::NotFound,
+            format!("Review {} not found", review_id),
+        ));
+    }
+    let review = review.unwrap();
+    if !db::workshops::teacher_in_workshop(conn, teacher_id, review.workshop) {
+        return Err(DbError::new(
+            DbErrorKind::NotFound,
+            format!("Teacher {} not in Workshop {}", teacher_id, review.workshop),
+        ));
+    }
+    let student_id = match review.reviewer {
+        Some(student_id) if !review.teacher => student_id,
+        _ => {
+            return Err(DbError::new(
+                DbErrorKind::Mismatch,
+                "Only Reviews of Students can be extended",
+            ))
+        }
+    };
+    // Closed reviews are already part of the points
+    if review.locked {
+        return Err(DbError::new(
+            DbErrorKind::PastDeadline,
+            "Closed Reviews cannot be extended",
+        ));
+    }
+
+    let extension = ReviewExtension {
+        review: review_id,
+        end,
+    };
+    let mut t_error: Result<(), DbError> = Ok(());
+    let res = conn.transaction::<(), Error, _>(|| {
+        let insert = diesel::replace_into(reviewextensions_t)
+            .values(&extension)
+            .execute(conn);
+        if insert.is_err() {
+            return DbError::assign_and_rollback(
+                &mut t_error,
+                DbError::new(DbErrorKind::CreateFailed, "Could not save Review Extension"),
+            );
+        }
+        // Reviewer is reminded again before the extended deadline
+        let reminder_update = diesel::update(reviews_t.filter(reviews_id.eq(review_id)))
+            .set(reviews_reminded.eq(false))
+            .execute(conn);
+        if reminder_update.is_err() {
+            return DbError::assign_and_rollback(
+                &mut t_error,
+                DbError::new(DbErrorKind::UpdateFailed, "Review Update failed"),
+            );
+        }
+        if let Err(err) = add_history(
+            conn,
+            review.workshop,
+            student_id,
+            Some(review_id),
+            teacher_id,
+            Some(end),
+        ) {
+            return DbError::assign_and_rollback(&mut t_error, err);
+        }
+        Ok(())
+    });
+
+    match res {
+        Ok(_) => Ok(()),
+        Err(_) => Err(t_error.err().unwrap_or(DbError::new(
+            DbErrorKind::TransactionFailed,
+            "Unknown error",
+        ))),
+    }
+}
+
+/// Revoke the extension of a review.
+pub fn revoke_review_extension(
+    conn: &MysqlConnection,
+    teacher_id: u64,
+    review_id: u64,
+) -> Result<(), DbError> {
+    let review = db::reviews::get_by_id(conn, review_id);
+    if review.is_err() {
+        return Err(DbError::new(
+            DbErrorKind::NotFound,
+            format!("Review {} not found", review_id),
+        ));
+    }
+    let review = review.unwrap();
+    if !db::workshops::teacher_in_workshop(conn, teacher_id, review.workshop) {
+        return Err(DbError::new(
+            DbErrorKind::NotFound,
+            format!("Teacher {} not in Workshop {}", teacher_id, review.workshop),
+        ));
+    }
+
+    let mut t_error: Result<(), DbError> = Ok(());
+    let res = conn.transaction::<(), Error, _>(|| {
+        let delete =
+            diesel::delete(reviewextensions_t.filter(re_review.eq(review_id))).execute(conn);
+        match delete {
+            Ok(0) => {
+                return DbError::assign_and_rollback(
+                    &mut t_error,
+                    DbError::new(
+                        DbErrorKind::NotFound,
+                        format!("No Extension for Review {} found", review_id),
+                    ),
+                )
+            }
+            Ok(_) => {}
+            Err(_) => {
+                return DbError::assign_and_rollback(
+                    &mut t_error,
+                    DbError::new(DbErrorKind::DeleteFailed, "Review Extension Delete failed"),
+                )
+            }
+        }
+        if let Some(student_id) = review.reviewer {
+            if let Err(err) = add_history(
+                conn,
+                review.workshop,
+                student_id,
+                Some(review_id),
+                teacher_id,
+                None,
+            ) {
+                return DbError::assign_and_rollback(&mut t_error, err);
+            }
+        }
+        Ok(())
+    });
+
+    match res {
+        Ok(_) => Ok(()),
+        Err(_) => Err(t_error.err().unwrap_or(DbError::new(
+            DbErrorKind::TransactionFailed,
+            "Unknown error",
+        ))),
+    }
+}
+
+/// Get the end of the extended deadline of a review.
+pub fn get_review_extension(
+    conn: &MysqlConnection,
+    review_id: u64,
+) -> Option<chrono::NaiveDateTime> {
+    reviewextensions_t
+        .filter(re_review.eq(review_id))
+        .select(re_end)
+        .first(conn)
+        .ok()
+}
+
+/// Get the deadline of a review, an extension replaces the stored deadline.
+pub fn get_review_deadline(conn: &MysqlConnection, review: &Review) -> chrono::NaiveDateTime {
+    get_review_extension(conn, review.id).unwrap_or(review.deadline)
+}
+
+/// Get the latest end of the review extensions of a submission.
+/// The submission is only graded after all extended reviews are done.
+pub fn get_latest_review_extension(
+    conn: &MysqlConnection,
+    submission_id: u64,
+) -> Option<chrono::NaiveDateTime> {
+    reviewextensions_t
+        .inner_join(reviews_t.on(reviews_id.eq(re_review)))
+        .filter(reviews_sub.eq(submission_id))
+        .select(max(re_end))
+        .first::<Option<chrono::NaiveDateTime>>(conn)
+        .ok()
+        .flatten()
+}
+
+/// Get all review extensions of a workshop.
+/// Only teachers of the workshop can see the extensions.
+pub fn get_review_extensions(
+    conn: &MysqlConnection,
+    teacher_id: u64,
+    workshop_id: u64,
+) -> Result<Vec<StudentExtension>, DbError> {
+    if !db::workshops::teacher_in_workshop(conn, teacher_id, workshop_id) {
+        return Err(DbError::new(
+            DbErrorKind::NotFound,
+            format!("Teacher {} not in Workshop {}", teacher_id, workshop_id),
+        ));
+    }
+    let extensions = reviewextensions_t
+        .inner_join(reviews_t.on(reviews_id.eq(re_review)))
+        .inner_join(users_t.on(u_id.nullable().eq(reviews_reviewer)))
+        .filter(reviews_ws.eq(workshop_id))
+        .select((u_id, u_firstname, u_lastname, re_review, re_end))
+        .order(re_end)
+        .get_results::<(u64, String, String, u64, chrono::NaiveDateTime)>(conn);
+    match extensions {
+        Ok(extensions) => Ok(extensions
+            .into_iter()
+            .map(|(id, firstname, lastname, review, end)| StudentExtension {
+                id,
+                firstname,
+                lastname,
+                review: Some(review),
+                end,
+            })
+            .collect()),
+        Err(_) => Err(DbError::new(
+            DbErrorKind::ReadFailed,
+            format!("No Review Extensions for Workshop {} found", workshop_id),
+        )),
+    }
+}
+
+/// Get the history of granted & revoked extensions of a workshop, latest first.
+pub fn get_history(
+    conn: &MysqlConnection,
+    workshop_id: u64,
+) -> Result<Vec<ExtensionHistoryEntry>, DbError> {
+    let history = extensionhistory_t
+        .inner_join(users_t.on(u_id.eq(eh_student)))
+        .filter(eh_workshop.eq(workshop_id))
+        .select((
+            crate::schema::extensionhistory::all_columns,
+            u_firstname,
+            u_lastname,
+        ))
+        .order(eh_date.desc())
+        .get_results::<(ExtensionHistory, String, String)>(conn);
+    match history {
+        Ok(history) => Ok(history
+            .into_iter()
+            .map(|(entry, firstname, lastname)| ExtensionHistoryEntry {
+                id: entry.id,
+                student_id: entry.student,
+                firstname,
+                lastname,
+                review: entry.review,
+                teacher_id: entry.teacher,
+                end: entry.end,
+                date: entry.date,
+            })
+            .collect()),
+        Err(_) => Err(DbError::new(
+            DbErrorKind::ReadFailed,
+            format!("No Extension History for Workshop {} found", workshop_id),
+        )),
+    }
+}
+
+// Record a granted or revoked extension, revoked extensions have no end.
+fn add_history(
+    conn: &MysqlConnection,
+    workshop_id: u64,
+    student_id: u64,
+    review_id: Option<u64>,
+    teacher_id: u64,
+    end: Option<chrono::NaiveDateTime>,
+) -> Result<(), DbError> {
+    let entry = NewExtensionHistory {
+        workshop: workshop_id,
+        student: student_id,
+        review: review_id,
+        teacher: Some(teacher_id),
+        end,
+        date: Local::now().naive_local(),
+    };
+    let insert = diesel::insert_into(extensionhistory_t)
+        .values(&entry)
+        .execute(conn);
+    if insert.is_err() {
+        return Err(DbError::new(
+            DbErrorKind::CreateFailed,
+            "Extension History Insert failed",
+        ));
+    }
+    Ok(())
+}
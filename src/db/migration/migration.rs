@@ -41,7 +41,17 @@ truncate submissioncriteria;
 truncate submissionattachments;
 truncate reviews;
 truncate reviewpoints;
+truncate reviewratings;
+truncate appeals;
+truncate rubriclevels;
 truncate workshopattachments;
+truncate emailpreferences;
+truncate emailqueue;
+truncate notifications;
+truncate webhooks;
+truncate webhookevents;
+truncate webhookdeliveries;
+truncate calendartokens;
 SET FOREIGN_KEY_CHECKS = 1;
                     "#,
                 );
@@ -58,7 +68,7 @@ SET FOREIGN_KEY_CHECKS = 1;
             // See: https://tableplus.com/blog/2018/11/how-to-insert-if-not-exist-mysql.html
             let res = conn.batch_execute(
                 r#"          
-INSERT IGNORE INTO users values(default, "admin", "admin", "admin", "fb001dfcffd1c899f3297871406242f097aecf1a5342ccf3ebcd116146188e4b", "teacher", null);
+INSERT IGNORE INTO users values(default, "admin", "admin", "admin", "fb001dfcffd1c899f3297871406242f097aecf1a5342ccf3ebcd116146188e4b", "teacher", null, null);
                 "#,
             );
             match res {
@@ -83,15 +93,15 @@ INSERT IGNORE INTO users values(default, "admin", "admin", "admin", "fb001dfcffd
             if db_mock {
                 let res = conn.batch_execute(
                     &format!(r#"
-INSERT INTO users values(default, "t1", "John", "Doe", "1d6442ddcfd9db1ff81df77cbefcd5afcc8c7ca952ab3101ede17a84b866d3f3", "teacher", null);
-INSERT INTO users values(default, "t2", "John", "Doe II", "1d6442ddcfd9db1ff81df77cbefcd5afcc8c7ca952ab3101ede17a84b866d3f3", "teacher", null);
-INSERT INTO users values(default, "s1", "Max", "Mustermann", "1d6442ddcfd9db1ff81df77cbefcd5afcc8c7ca952ab3101ede17a84b866d3f3", "student", "4A");
-INSERT INTO users values(default, "s2", "Luke", "Skywalker", "1d6442ddcfd9db1ff81df77cbefcd5afcc8c7ca952ab3101ede17a84b866d3f3", "student", "4A");
-INSERT INTO users values(default, "s3", "Gordon", "Freeman", "1d6442ddcfd9db1ff81df77cbefcd5afcc8c7ca952ab3101ede17a84b866d3f3", "student", "4A");
-INSERT INTO users values(default, "s4", "Mario", "Mario", "1d6442ddcfd9db1ff81df77cbefcd5afcc8c7ca952ab3101ede17a84b866d3f3", "student", "4A");
-INSERT INTO `workshops` VALUES (1,'WS','Hey!','2023-07-31 16:26:00',1,{});
+INSERT INTO users values(default, "t1", "John", "Doe", "1d6442ddcfd9db1ff81df77cbefcd5afcc8c7ca952ab3101ede17a84b866d3f3", "teacher", null, null);
+INSERT INTO users values(default, "t2", "John", "Doe II", "1d6442ddcfd9db1ff81df77cbefcd5afcc8c7ca952ab3101ede17a84b866d3f3", "teacher", null, null);
+INSERT INTO users values(default, "s1", "Max", "Mustermann", "1d6442ddcfd9db1ff81df77cbefcd5afcc8c7ca952ab3101ede17a84b866d3f3", "student", "4A", null);
+INSERT INTO users values(default, "s2", "Luke", "Skywalker", "1d6442ddcfd9db1ff81df77cbefcd5afcc8c7ca952ab3101ede17a84b866d3f3", "student", "4A", null);
+INSERT INTO users values(default, "s3", "Gordon", "Freeman", "1d6442ddcfd9db1ff81df77cbefcd5afcc8c7ca952ab3101ede17a84b866d3f3", "student", "4A", null);
+INSERT INTO users values(default, "s4", "Mario", "Mario", "1d6442ddcfd9db1ff81df77cbefcd5afcc8c7ca952ab3101ede17a84b866d3f3", "student", "4A", null);
+INSERT INTO `workshops` (id, title, content, end, anonymous, reviewtimespan) VALUES (1,'WS','Hey!','2023-07-31 16:26:00',1,{});
 INSERT INTO `workshoplist` VALUES (1,1,'teacher'),(1,2,'teacher'),(1,4,'student'),(1,5,'student'),(1,6,'student'),(1,7,'student');
-INSERT INTO `criterion` VALUES (1,'Criterion','True/False',10,'truefalse'),(2,'Other Criterion','True/False',10,'truefalse');
+INSERT INTO `criterion` (id, title, content, weight, kind) VALUES (1,'Criterion','True/False',10,'truefalse'),(2,'Other Criterion','True/False',10,'truefalse');
 INSERT INTO `criteria` VALUES (1,1),(1,2);
     "#, review_timespan),
                 );
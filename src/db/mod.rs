@@ -3,9 +3,20 @@ mod migration;
 pub mod models;
 pub use migration::*;
 
+pub mod appeals;
 pub mod attachments;
+pub mod calendar;
+pub mod calibration;
+pub mod criteria;
+pub mod emails;
+pub mod extensions;
+pub mod notifications;
 pub mod reviews;
+pub mod scheduler;
 pub mod submissions;
+pub mod teams;
 pub mod todos;
 pub mod users;
+pub mod versions;
+pub mod webhooks;
 pub mod workshops;
@@ -1,11 +1,41 @@
 use crate::models::*;
+use crate::utils::diff::DiffLine;
+
+// Criteria
+/// Criterion with its rubric levels.
+#[derive(Serialize)]
+pub struct FullCriterion {
+    #[serde(flatten)]
+    pub criterion: Criterion,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub levels: Vec<RubricLevel>,
+}
+
+/// Criterion of a workshop that should be created with its rubric levels.
+/// The criterion of the levels is assigned on creation.
+pub struct NewWorkshopCriterion {
+    pub criterion: NewCriterion,
+    pub levels: Vec<NewRubricLevel>,
+}
 
 // Reviews
+/// Simplified representation of a review.
+#[derive(Serialize)]
+pub struct SimpleReview {
+    pub id: u64,
+    pub teacher: bool,
+    pub reviewer: Option<u64>,
+    pub submission: u64,
+    pub points: Vec<SimpleReviewPoints>,
+}
+
 /// Simplified representation of review points.
 #[derive(Serialize)]
 pub struct SimpleReviewPoints {
+    pub criterion: u64,
     pub weight: f64,
     pub kind: Kind,
+    pub custom_range: Option<(f64, f64)>,
     pub points: f64,
 }
 
@@ -21,7 +51,20 @@ pub struct FullReview {
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(rename = "notSubmitted")]
     pub not_submitted: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub draft: Option<bool>,
+    pub teacher: bool,
     pub points: Vec<FullReviewPoints>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rating: Option<ReviewRating>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub appeal: Option<Appeal>,
+    pub discarded: bool,
+    #[serde(rename = "selfAssessment")]
+    pub self_assessment: bool,
+    // Version of the submission that was reviewed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<i32>,
 }
 
 /// Detailed representation of review points.
@@ -34,7 +77,21 @@ pub struct FullReviewPoints {
     pub weight: f64,
     #[serde(rename = "type")]
     pub kind: Kind,
-    pub points: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub points: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub comment: Option<String>,
+}
+
+/// Helpfulness of the reviews written by a reviewer.
+#[derive(Serialize)]
+pub struct ReviewerHelpfulness {
+    pub id: u64,
+    pub firstname: String,
+    pub lastname: String,
+    pub ratings: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub helpfulness: Option<f64>,
 }
 
 /// Representation of a missing review
@@ -52,6 +109,9 @@ pub struct MissingReview {
 pub struct WorkshopReview {
     pub id: u64,
     pub done: bool,
+    pub calibration: bool,
+    #[serde(rename(serialize = "selfAssessment"))]
+    pub self_assessment: bool,
     pub deadline: chrono::NaiveDateTime,
     pub title: String,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -60,6 +120,160 @@ pub struct WorkshopReview {
     pub lastname: Option<String>,
 }
 
+// Appeals
+/// Workshop representation of an appeal for the moderation queue.
+#[derive(Serialize)]
+pub struct WorkshopAppeal {
+    #[serde(flatten)]
+    pub appeal: Appeal,
+    pub review: u64,
+    pub submission: u64,
+    pub title: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub firstname: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lastname: Option<String>,
+}
+
+// Calibration
+/// Calibration submission of a workshop with its reference review.
+#[derive(Serialize)]
+pub struct CalibrationSubmission {
+    pub id: u64,
+    pub title: String,
+    pub date: chrono::NaiveDateTime,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reference: Option<u64>,
+    #[serde(rename(serialize = "referenceDone"))]
+    pub reference_done: bool,
+}
+
+/// Deviation of a reviewer from the reference reviews for a criterion.
+#[derive(Serialize)]
+pub struct CriterionDeviation {
+    pub id: u64,
+    pub title: String,
+    pub deviation: f64,
+}
+
+/// Accuracy of a reviewer based on calibration reviews.
+#[derive(Serialize)]
+pub struct ReviewerAccuracy {
+    pub id: u64,
+    pub firstname: String,
+    pub lastname: String,
+    pub done: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub accuracy: Option<f64>,
+    pub criteria: Vec<CriterionDeviation>,
+}
+
+// Self-assessments
+/// Deviation of a self-assessment from the peer reviews for a criterion.
+/// Positive deviations mean that the submitter rated the own work higher.
+#[derive(Serialize)]
+pub struct SelfCriterionDeviation {
+    pub id: u64,
+    pub title: String,
+    #[serde(rename(serialize = "self"))]
+    pub own: f64,
+    pub peers: f64,
+    pub deviation: f64,
+}
+
+/// Deviation of a self-assessment from the peer reviews of the submission.
+#[derive(Serialize)]
+pub struct SelfAssessmentDeviation {
+    pub id: u64,
+    pub submission: u64,
+    pub title: String,
+    pub firstname: String,
+    pub lastname: String,
+    pub reviews: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deviation: Option<f64>,
+    pub criteria: Vec<SelfCriterionDeviation>,
+}
+
+// Statistics
+/// Spread of normalized review points (`0.0..=1.0`).
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Spread {
+    pub mean: f64,
+    #[serde(rename(serialize = "stdDev"))]
+    pub std_dev: f64,
+    pub min: f64,
+    pub max: f64,
+    pub range: f64,
+}
+
+/// Spread of the review points of a criterion.
+#[derive(Serialize)]
+pub struct CriterionStatistics {
+    pub id: u64,
+    pub title: String,
+    #[serde(flatten)]
+    pub spread: Spread,
+}
+
+/// Normalized points of a peer review.
+#[derive(Serialize)]
+pub struct ReviewStatistics {
+    pub id: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub firstname: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lastname: Option<String>,
+    pub fraction: f64,
+    pub outlier: bool,
+}
+
+/// Agreement of the peer reviews of a submission.
+#[derive(Serialize)]
+pub struct SubmissionStatistics {
+    pub id: u64,
+    pub title: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub spread: Option<Spread>,
+    pub reviews: Vec<ReviewStatistics>,
+    pub criteria: Vec<CriterionStatistics>,
+}
+
+/// Grades of a student in a workshop, a row of the grade export.
+/// Points are taken from the latest submission of the student.
+pub struct GradeRow {
+    pub student: u64,
+    pub firstname: String,
+    pub lastname: String,
+    pub group: Option<String>,
+    pub submission: Option<String>,
+    pub points: Option<f64>,
+    pub max_points: Option<f64>,
+    pub percentage: Option<f64>,
+    // Mean points per criterion, same order as the criteria of the export
+    pub criteria: Vec<Option<f64>>,
+    pub reviews_written: i64,
+    pub reviews_missed: i64,
+    pub reviews_done: bool,
+    pub error: bool,
+}
+
+/// Grades of all students in a workshop.
+pub struct GradeExport {
+    pub title: String,
+    pub criteria: Vec<String>,
+    pub rows: Vec<GradeRow>,
+}
+
+/// Agreement of the peer reviews of a workshop.
+#[derive(Serialize)]
+pub struct WorkshopStatistics {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub agreement: Option<f64>,
+    pub outliers: usize,
+    pub submissions: Vec<SubmissionStatistics>,
+}
+
 // Submissions
 /// Representation of a submission for owner.
 #[derive(Serialize)]
@@ -67,17 +281,42 @@ pub struct OwnSubmission {
     pub title: String,
     pub comment: String,
     pub attachments: Vec<SimpleAttachment>,
+    // Needed for self-assessments
+    pub criteria: Vec<FullCriterion>,
+    // Latest version of the submission
+    pub version: i32,
+    // Team submissions are owned by all team members
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub team: Option<WorkshopTeam>,
     pub locked: bool,
     pub date: chrono::NaiveDateTime,
+    pub late: bool,
+    pub round: i32,
+    // Submission of the previous round
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub previous: Option<u64>,
+    #[serde(rename(serialize = "roundPoints"))]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub round_points: Option<f64>,
     #[serde(rename(serialize = "reviewsDone"))]
     pub reviews_done: bool,
     #[serde(rename(serialize = "noReviews"))]
     pub no_reviews: bool,
+    #[serde(rename(serialize = "resultsReleased"))]
+    pub results_released: bool,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub points: Option<f64>,
     #[serde(rename(serialize = "maxPoints"))]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub max_points: Option<f64>,
+    #[serde(rename(serialize = "computedPoints"))]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub computed_points: Option<f64>,
+    #[serde(rename(serialize = "finalPoints"))]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub final_points: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub justification: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub firstname: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -94,7 +333,9 @@ pub struct OtherSubmission {
     pub title: String,
     pub comment: String,
     pub attachments: Vec<SimpleAttachment>,
-    pub criteria: Vec<Criterion>,
+    pub criteria: Vec<FullCriterion>,
+    // Version the review is pinned to
+    pub version: i32,
 }
 
 /// Workshop representation of a submission.
@@ -103,6 +344,13 @@ pub struct WorkshopSubmission {
     pub id: u64,
     pub title: String,
     pub date: chrono::NaiveDateTime,
+    pub late: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub team: Option<u64>,
+    pub round: i32,
+    #[serde(rename(serialize = "roundPoints"))]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub round_points: Option<f64>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub locked: Option<bool>,
     #[serde(rename(serialize = "studentid"))]
@@ -112,11 +360,84 @@ pub struct WorkshopSubmission {
     pub reviews_done: bool,
     #[serde(rename(serialize = "noReviews"))]
     pub no_reviews: bool,
+    #[serde(rename(serialize = "resultsReleased"))]
+    pub results_released: bool,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub points: Option<f64>,
     #[serde(rename(serialize = "maxPoints"))]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub max_points: Option<f64>,
+    #[serde(rename(serialize = "computedPoints"))]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub computed_points: Option<f64>,
+    #[serde(rename(serialize = "finalPoints"))]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub final_points: Option<f64>,
+}
+
+// Teams
+/// Workshop representation of a team.
+#[derive(Serialize)]
+pub struct WorkshopTeam {
+    pub id: u64,
+    pub title: String,
+    pub members: Vec<SimpleUser>,
+}
+
+// Versions
+/// Version of a submission with its attachments.
+#[derive(Serialize)]
+pub struct FullSubmissionVersion {
+    pub version: i32,
+    pub title: String,
+    pub comment: String,
+    pub date: chrono::NaiveDateTime,
+    pub attachments: Vec<SimpleAttachment>,
+}
+
+/// Changes between two versions of a submission.
+#[derive(Serialize)]
+pub struct SubmissionVersionDiff {
+    pub from: i32,
+    pub to: i32,
+    pub title: Vec<DiffLine>,
+    pub comment: Vec<DiffLine>,
+    #[serde(rename(serialize = "addedAttachments"))]
+    pub added_attachments: Vec<SimpleAttachment>,
+    #[serde(rename(serialize = "removedAttachments"))]
+    pub removed_attachments: Vec<SimpleAttachment>,
+}
+
+// Extensions
+/// Deadline extension of a student.
+#[derive(Serialize)]
+pub struct StudentExtension {
+    pub id: u64,
+    pub firstname: String,
+    pub lastname: String,
+    // Only set for review extensions
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub review: Option<u64>,
+    pub end: chrono::NaiveDateTime,
+}
+
+/// Granted or revoked extension for the history of a workshop.
+#[derive(Serialize)]
+pub struct ExtensionHistoryEntry {
+    pub id: u64,
+    #[serde(rename(serialize = "studentid"))]
+    pub student_id: u64,
+    pub firstname: String,
+    pub lastname: String,
+    // Submission extensions have no review
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub review: Option<u64>,
+    #[serde(rename(serialize = "teacherid"))]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub teacher_id: Option<u64>,
+    // Revoked extensions have no end
+    pub end: Option<chrono::NaiveDateTime>,
+    pub date: chrono::NaiveDateTime,
 }
 
 // Todos
@@ -164,6 +485,31 @@ pub struct SimpleUser {
 }
 
 // Workshops
+/// Settings of a workshop that are given on creation & update.
+pub struct WorkshopSettings {
+    pub title: String,
+    pub content: String,
+    pub end: chrono::NaiveDateTime,
+    pub review_timespan: i64,
+    pub teacher_weight: f64,
+    pub aggregation: Aggregation,
+    pub grade_scale: GradeScale,
+    pub comment_threshold: Option<f64>,
+    pub draft_policy: DraftPolicy,
+    pub reviewing_weight: f64,
+    pub appeal_window: i64,
+    pub self_assessment: bool,
+    pub submission_start: Option<chrono::NaiveDateTime>,
+    pub submission_end: Option<chrono::NaiveDateTime>,
+    pub review_end: Option<chrono::NaiveDateTime>,
+    pub results_date: Option<chrono::NaiveDateTime>,
+    pub late_policy: LatePolicy,
+    pub late_penalty: f64,
+    pub rounds: i32,
+    pub same_reviewers: bool,
+    pub round_policy: RoundPolicy,
+}
+
 /// Workshop representation of an user.
 #[derive(Serialize)]
 pub struct WorkshopUser {
@@ -184,10 +530,46 @@ pub struct TeacherWorkshop {
     pub end: chrono::NaiveDateTime,
     #[serde(rename(serialize = "reviewTimespan"))]
     pub review_timespan: i64,
+    #[serde(rename(serialize = "teacherWeight"))]
+    pub teacher_weight: f64,
+    pub aggregation: Aggregation,
+    #[serde(rename(serialize = "gradeScale"))]
+    pub grade_scale: GradeScale,
+    #[serde(rename(serialize = "commentThreshold"))]
+    pub comment_threshold: Option<f64>,
+    #[serde(rename(serialize = "draftPolicy"))]
+    pub draft_policy: DraftPolicy,
+    #[serde(rename(serialize = "reviewingWeight"))]
+    pub reviewing_weight: f64,
+    #[serde(rename(serialize = "appealWindow"))]
+    pub appeal_window: i64,
+    #[serde(rename(serialize = "selfAssessment"))]
+    pub self_assessment: bool,
+    #[serde(rename(serialize = "submissionStart"))]
+    pub submission_start: Option<chrono::NaiveDateTime>,
+    #[serde(rename(serialize = "submissionEnd"))]
+    pub submission_end: Option<chrono::NaiveDateTime>,
+    #[serde(rename(serialize = "reviewEnd"))]
+    pub review_end: Option<chrono::NaiveDateTime>,
+    #[serde(rename(serialize = "resultsDate"))]
+    pub results_date: Option<chrono::NaiveDateTime>,
+    pub phase: Phase,
+    #[serde(rename(serialize = "latePolicy"))]
+    pub late_policy: LatePolicy,
+    #[serde(rename(serialize = "latePenalty"))]
+    pub late_penalty: f64,
+    pub rounds: i32,
+    #[serde(rename(serialize = "sameReviewers"))]
+    pub same_reviewers: bool,
+    #[serde(rename(serialize = "roundPolicy"))]
+    pub round_policy: RoundPolicy,
+    // Granted & revoked extensions, latest first
+    pub extensions: Vec<ExtensionHistoryEntry>,
     pub anonymous: bool,
+    pub teams: Vec<WorkshopTeam>,
     pub students: Vec<WorkshopUser>,
     pub teachers: Vec<WorkshopUser>,
-    pub criteria: Vec<Criterion>,
+    pub criteria: Vec<FullCriterion>,
     pub attachments: Vec<SimpleAttachment>,
 }
 
@@ -197,7 +579,41 @@ pub struct StudentWorkshop {
     pub title: String,
     pub content: String,
     pub end: chrono::NaiveDateTime,
+    #[serde(rename(serialize = "gradeScale"))]
+    pub grade_scale: GradeScale,
+    #[serde(rename(serialize = "commentThreshold"))]
+    pub comment_threshold: Option<f64>,
+    #[serde(rename(serialize = "appealWindow"))]
+    pub appeal_window: i64,
+    #[serde(rename(serialize = "selfAssessment"))]
+    pub self_assessment: bool,
+    #[serde(rename(serialize = "submissionStart"))]
+    pub submission_start: Option<chrono::NaiveDateTime>,
+    #[serde(rename(serialize = "submissionEnd"))]
+    pub submission_end: Option<chrono::NaiveDateTime>,
+    #[serde(rename(serialize = "reviewEnd"))]
+    pub review_end: Option<chrono::NaiveDateTime>,
+    #[serde(rename(serialize = "resultsDate"))]
+    pub results_date: Option<chrono::NaiveDateTime>,
+    pub phase: Phase,
+    #[serde(rename(serialize = "latePolicy"))]
+    pub late_policy: LatePolicy,
+    #[serde(rename(serialize = "latePenalty"))]
+    pub late_penalty: f64,
+    pub rounds: i32,
+    #[serde(rename(serialize = "sameReviewers"))]
+    pub same_reviewers: bool,
+    #[serde(rename(serialize = "roundPolicy"))]
+    pub round_policy: RoundPolicy,
+    // End of the extended submission phase of the student
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub extension: Option<chrono::NaiveDateTime>,
+    // Peer reviews are unlocked after all calibration reviews are done
+    #[serde(rename(serialize = "calibrationDone"))]
+    pub calibration_done: bool,
     pub anonymous: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub team: Option<WorkshopTeam>,
     pub students: Vec<WorkshopUser>,
     pub teachers: Vec<WorkshopUser>,
     pub submissions: Vec<WorkshopSubmission>,
@@ -0,0 +1,138 @@
+//! CRUD operations for in-app notifications.
+
+use crate::db;
+use crate::db::error::{DbError, DbErrorKind};
+use crate::db::models::*;
+use crate::schema::notifications::dsl::{
+    date as n_date, id as n_id, notifications as notifications_t, seen as n_seen, user as n_user,
+};
+use chrono::Local;
+use diesel::dsl::count_star;
+use diesel::prelude::*;
+use diesel::result::Error;
+
+/// Create the same notification for multiple users.
+pub fn create(
+    conn: &MysqlConnection,
+    users: &[u64],
+    kind: NotificationKind,
+    title: String,
+    content: String,
+    workshop: Option<u64>,
+    submission: Option<u64>,
+) -> Result<(), DbError> {
+    if users.is_empty() {
+        return Ok(());
+    }
+    let date = Local::now().naive_local();
+    let new_notifications: Vec<NewNotification> = users
+        .iter()
+        .map(|user| NewNotification {
+            user: *user,
+            kind: kind.clone(),
+            title: title.clone(),
+            content: content.clone(),
+            workshop,
+            submission,
+            date,
+        })
+        .collect();
+    let insert = diesel::insert_into(notifications_t)
+        .values(&new_notifications)
+        .execute(conn);
+    if insert.is_err() {
+        return Err(DbError::new(
+            DbErrorKind::CreateFailed,
+            "Notification Insert failed",
+        ));
+    }
+    Ok(())
+}
+
+/// Notify the owners of a submission.
+pub fn notify_submission_owners(
+    conn: &MysqlConnection,
+    submission: &Submission,
+    kind: NotificationKind,
+    title: String,
+    content: String,
+) -> Result<(), DbError> {
+    let owners = db::submissions::get_owner_ids(conn, submission);
+    if owners.is_err() {
+        return Err(DbError::new(
+            DbErrorKind::ReadFailed,
+            format!("Owners of Submission {} not found", submission.id),
+        ));
+    }
+    create(
+        conn,
+        &owners.unwrap(),
+        kind,
+        title,
+        content,
+        Some(submission.workshop),
+        Some(submission.id),
+    )
+}
+
+/// Get notifications of an user, latest first.
+/// Only unread notifications are returned if `unread` is true.
+pub fn get_by_user(
+    conn: &MysqlConnection,
+    user_id: u64,
+    unread: bool,
+) -> Result<Vec<Notification>, Error> {
+    let mut query = notifications_t
+        .filter(n_user.eq(user_id))
+        .order((n_date.desc(), n_id.desc()))
+        .into_boxed();
+    if unread {
+        query = query.filter(n_seen.eq(false));
+    }
+    query.get_results(conn)
+}
+
+/// Count unread notifications of an user.
+pub fn count_unread(conn: &MysqlConnection, user_id: u64) -> Result<i64, Error> {
+    notifications_t
+        .filter(n_user.eq(user_id).and(n_seen.eq(false)))
+        .select(count_star())
+        .first(conn)
+}
+
+/// Mark notification of an user as read.
+pub fn mark_read(
+    conn: &MysqlConnection,
+    notification_id: u64,
+    user_id: u64,
+) -> Result<(), DbError> {
+    let update =
+        diesel::update(notifications_t.filter(n_id.eq(notification_id).and(n_user.eq(user_id))))
+            .set(n_seen.eq(true))
+            .execute(conn);
+    match update {
+        Ok(0) => Err(DbError::new(
+            DbErrorKind::NotFound,
+            format!("Notification {} not found", notification_id),
+        )),
+        Ok(_) => Ok(()),
+        Err(_) => Err(DbError::new(
+            DbErrorKind::UpdateFailed,
+            "Notification Update failed",
+        )),
+    }
+}
+
+/// Mark all notifications of an user as read.
+pub fn mark_all_read(conn: &MysqlConnection, user_id: u64) -> Result<(), DbError> {
+    let update = diesel::update(notifications_t.filter(n_user.eq(user_id).and(n_seen.eq(false))))
+        .set(n_seen.eq(true))
+        .execute(conn);
+    if update.is_err() {
+        return Err(DbError::new(
+            DbErrorKind::UpdateFailed,
+            "Notification Update failed",
+        ));
+    }
+    Ok(())
+}
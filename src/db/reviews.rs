@@ -3,22 +3,35 @@
 use crate::db;
 use crate::db::error::{DbError, DbErrorKind};
 use crate::db::models::*;
-use crate::routes::models::RouteUpdateReview;
+use crate::events::{self, Event, EventKind};
+use crate::grading;
+use crate::grading::aggregation::{self, ReviewerScore};
+use crate::grading::calibration;
+use crate::grading::normalization;
+use crate::grading::reviewing;
+use crate::grading::selfassessment;
+use crate::grading::statistics;
+use crate::routes::models::{RouteUpdatePoints, RouteUpdateReview};
 use crate::schema::criterion::dsl::{
-    content as c_content, criterion as criterion_t, id as c_id, kind as c_kind, title as c_title,
-    weight as c_weight,
+    content as c_content, criterion as criterion_t, id as c_id, kind as c_kind,
+    maxpoints as c_maxpoints, minpoints as c_minpoints, title as c_title, weight as c_weight,
 };
 use crate::schema::reviewpoints::dsl::{
-    criterion as rp_criterion, points as rp_points, review as rp_review,
+    comment as rp_comment, criterion as rp_criterion, points as rp_points, review as rp_review,
     reviewpoints as reviewpoints_t,
 };
+use crate::schema::reviewratings::dsl::{
+    helpfulness as rr_helpfulness, review as rr_review, reviewratings as reviewratings_t,
+};
 use crate::schema::reviews::dsl::{
-    deadline as reviews_deadline, done as reviews_done, error as reviews_error, id as reviews_id,
-    reviewer, reviews as reviews_t, submission as reviews_sub,
+    deadline as reviews_deadline, discarded as reviews_discarded, done as reviews_done,
+    error as reviews_error, id as reviews_id, reviewer, reviews as reviews_t,
+    selfassessment as reviews_self, submission as reviews_sub, teacher as reviews_teacher,
+    workshop as reviews_ws,
 };
 use crate::schema::submissions::dsl::{
-    id as sub_id, student as sub_student, submissions as submissions_t, title as sub_title,
-    workshop as sub_ws,
+    calibration as sub_calibration, id as sub_id, reviewsdone as sub_reviews_done,
+    student as sub_student, submissions as submissions_t, title as sub_title, workshop as sub_ws,
 };
 use crate::schema::users::dsl::{
     firstname as u_firstname, id as u_id, lastname as u_lastname, users as users_t,
@@ -27,12 +40,14 @@ use crate::schema::workshoplist::dsl::{
     role as wsl_role, user as wsl_user, workshop as wsl_ws, workshoplist as workshoplist_t,
 };
 use crate::schema::workshops::dsl::{id as ws_id, workshops as workshops_t};
+use crate::utils::error::AppError;
 use chrono::Local;
 use diesel::dsl::{exists, not};
 use diesel::prelude::*;
 use diesel::result::Error;
 use diesel::select;
 use diesel::sql_types::BigInt;
+use std::collections::{BTreeMap, HashMap};
 use std::convert::TryInto;
 
 /// Assign reviews from a given submission.
@@ -54,14 +69,36 @@ pub fn assign(
     */
     // Nullable eq: https://docs.diesel.rs/diesel/expression_methods/trait.NullableExpressionMethods.html
     // Problems with count: https://github.com/diesel-rs/diesel/issues/1781
+    // Self-assessments do not count as assigned reviews
+    // Team members do not review the submissions of their own team
+    let excluded = match db::teams::get_student_team(conn, workshop_id, submission_student_id) {
+        Some(team) => match db::teams::get_member_ids(conn, team.id) {
+            Ok(mut members) => {
+                members.push(submission_student_id);
+                members
+            }
+            Err(_) => {
+                return Err(DbError::new(
+                    DbErrorKind::ReadFailed,
+                    format!("Members of Team {} not found", team.id),
+                ))
+            }
+        },
+        None => vec![submission_student_id],
+    };
     let count_reviewer = diesel::dsl::sql::<diesel::sql_types::Unsigned<BigInt>>("count(reviewer)");
     let reviews = workshoplist_t
-        .left_outer_join(reviews_t.on(reviewer.nullable().eq(wsl_user.nullable())))
+        .left_outer_join(
+            reviews_t.on(reviewer
+                .nullable()
+                .eq(wsl_user.nullable())
+                .and(reviews_self.eq(false))),
+        )
         .filter(
             wsl_ws.eq(workshop_id).and(
                 wsl_role
                     .eq(Role::Student)
-                    .and(wsl_user.ne(submission_student_id)),
+                    .and(not(wsl_user.eq_any(excluded))),
             ),
         )
         .group_by(wsl_user)
@@ -75,22 +112,116 @@ pub fn assign(
             "Could not get reviewers",
         ));
     }
-    let reviews: Vec<(u64, u64)> = reviews.unwrap();
-    let review_count = reviews.len();
-    //println!("Reviews: {:?}", reviews);
+    let reviewers: Vec<u64> = reviews
+        .unwrap()
+        .into_iter()
+        .map(|review| review.0)
+        .collect();
+    if let Err(err) = insert_reviews(conn, submission_id, workshop_id, deadline, reviewers) {
+        return Err(err);
+    }
+    //let reviews: Vec<Review> = reviews.unwrap();
 
-    // Assign reviews to them
-    let reviews: Vec<NewReview> = reviews
+    // Reviews & submission are closed by the scheduler once the deadline passed
+    // See `db::scheduler`
+    /*
+    select r.id, r.done, r.deadline, s.id, s.student, s.workshop
+        from reviews r
+        inner join submissions s on r.submission=s.id
+        where s.student=4;
+
+    select r.id, r.done, r.deadline, s.id, s.student, w.title
+        from reviews r
+        inner join submissions s on r.submission=s.id
+        inner join workshops w on s.workshop=w.id
+        where s.student=4;
+
+    select r.id, r.done, r.deadline, s.id, s.student, w.title
+        from reviews r
+        inner join submissions s on r.submission=s.id
+        inner join workshops w on s.workshop=w.id
+        where s.student=4;
+
+    select r.id, r.done, r.deadline, s.id, u.firstname, u.lastname, w.title
+        from reviews r
+        inner join submissions s on r.submission=s.id
+        inner join workshops w on s.workshop=w.id
+        inner join users u on s.student=u.id
+        where s.student=4;
+     */
+
+    Ok(())
+}
+
+/// Assign the peer reviewers of the previous round to a resubmission.
+/// Falls back to the usual assignment if none of them is still part of the workshop.
+pub fn assign_previous_reviewers(
+    conn: &MysqlConnection,
+    submission_id: u64,
+    previous_id: u64,
+    submission_student_id: u64,
+    workshop_id: u64,
+    deadline: chrono::NaiveDateTime,
+) -> Result<(), DbError> {
+    let reviewers = reviews_t
+        .filter(
+            reviews_sub
+                .eq(previous_id)
+                .and(reviews_teacher.eq(false))
+                .and(reviews_self.eq(false)),
+        )
+        .select(reviewer)
+        .get_results::<Option<u64>>(conn);
+    if reviewers.is_err() {
+        return Err(DbError::new(
+            DbErrorKind::ReadFailed,
+            format!("Reviewers of Submission {} not found", previous_id),
+        ));
+    }
+    let reviewers: Vec<u64> = reviewers
+        .unwrap()
         .into_iter()
-        .map(|review| NewReview {
+        .flatten()
+        .filter(|&reviewer_id| db::workshops::student_in_workshop(conn, reviewer_id, workshop_id))
+        .collect();
+    if reviewers.is_empty() {
+        return assign(
+            conn,
+            submission_id,
+            submission_student_id,
+            workshop_id,
+            deadline,
+        );
+    }
+    insert_reviews(conn, submission_id, workshop_id, deadline, reviewers)
+}
+
+// Insert reviews of the given reviewers and the self-assessment of the submission owner.
+fn insert_reviews(
+    conn: &MysqlConnection,
+    submission_id: u64,
+    workshop_id: u64,
+    deadline: chrono::NaiveDateTime,
+    reviewers: Vec<u64>,
+) -> Result<(), DbError> {
+    let review_count = reviewers.len();
+
+    // Assign reviews to them
+    let reviews: Vec<NewReview> = reviewers
+        .iter()
+        .cloned()
+        .map(|reviewer_id| NewReview {
             feedback: "".to_string(),
-            reviewer: Some(review.0),
+            reviewer: Some(reviewer_id),
             submission: submission_id,
             workshop: workshop_id,
             deadline,
             done: false,
             locked: false,
             error: false,
+            teacher: false,
+            discarded: false,
+            selfassessment: false,
         })
         .collect();
     let review_insert = diesel::insert_into(reviews_t)
@@ -113,106 +244,155 @@ pub fn assign(
             "Could not get reviews",
         ));
     }
-    //let reviews: Vec<Review> = reviews.unwrap();
 
-    /*// Create events that close reviews
-    // See: https://docs.rs/chrono/0.4.0/chrono/naive/struct.NaiveDateTime.html#example-14
-    // And: https://dev.mysql.com/doc/refman/8.0/en/create-event.html
-    // And: https://stackoverflow.com/a/8763381/12347616
-
-    // Convert local time to UTC
-    // Why? MySQL Events use internally UTC time to determine when to trigger them...
-    // See: https://dba.stackexchange.com/a/255569
-    // And: https://stackoverflow.com/a/65830964/12347616
-    let deadline = Local.from_local_datetime(&deadline).unwrap();
-    let deadline = deadline.with_timezone(&Utc);
-    let timestamp = deadline.format("%Y-%m-%d %H:%M:%S").to_string();
-    println!("{}", timestamp);
-
-    for review in reviews {
-        let res = conn.batch_execute(&*format!(
-            r#"
-        DROP EVENT IF EXISTS close_review_{id};
-        CREATE EVENT close_review_{id}
-        ON SCHEDULE AT '{timestamp}'
-        DO
-          UPDATE reviews
-          SET done = 1, locked = 1,
-          error = CASE
-            WHEN exists(select * from reviewpoints where review={id}) THEN 0
-            ELSE 1
-          END
-          WHERE id = {id};
-    "#,
-            id = review.id,
-            timestamp = timestamp
-        ));
-        if res.is_err() {
-            println!("{}", res.err().unwrap());
+    let workshop: Result<Workshop, _> = workshops_t.filter(ws_id.eq(workshop_id)).first(conn);
+    if workshop.is_err() {
+        return Err(DbError::new(
+            DbErrorKind::ReadFailed,
+            format!("Workshop {} not found", workshop_id),
+        ));
+    }
+    let workshop = workshop.unwrap();
+    // Notify reviewers, notifications are optional so errors do not fail the assignment
+    if let Err(err) = db::emails::notify_review_assigned(conn, &reviewers, &workshop, &deadline) {
+        err.print_stacktrace();
+    }
+    if let Err(err) = db::notifications::create(
+        conn,
+        &reviewers,
+        NotificationKind::Assigned,
+        "New review".to_string(),
+        format!(
+            "A submission in the workshop \"{}\" is waiting for your review",
+            workshop.title
+        ),
+        Some(workshop_id),
+        Some(submission_id),
+    ) {
+        err.print_stacktrace();
+    }
+    // Assign self-assessments to the submission owners
+    if workshop.selfassessment {
+        let submission = db::submissions::get_by_id(conn, submission_id);
+        if submission.is_err() {
             return Err(DbError::new(
-                DbErrorKind::EventCreateFailed,
-                "Event Insert failed",
+                DbErrorKind::NotFound,
+                format!("Submission {} not found", submission_id),
+            ));
+        }
+        let owner_ids = db::submissions::get_owner_ids(conn, &submission.unwrap());
+        if owner_ids.is_err() {
+            return Err(DbError::new(
+                DbErrorKind::ReadFailed,
+                format!("Owners of Submission {} not found", submission_id),
+            ));
+        }
+        // Every team member assesses the team submission
+        let self_assessments: Vec<NewReview> = owner_ids
+            .unwrap()
+            .into_iter()
+            .map(|owner_id| NewReview {
+                feedback: "".to_string(),
+                reviewer: Some(owner_id),
+                submission: submission_id,
+                workshop: workshop_id,
+                deadline,
+                done: false,
+                locked: false,
+                error: false,
+                teacher: false,
+                discarded: false,
+                selfassessment: true,
+            })
+            .collect();
+        let self_assessment_insert = diesel::insert_into(reviews_t)
+            .values(&self_assessments)
+            .execute(conn);
+        if self_assessment_insert.is_err() {
+            return Err(DbError::new(
+                DbErrorKind::CreateFailed,
+                "Self-Assessment Insert failed",
             ));
         }
     }
+    Ok(())
+}
 
-    // Create event that closes submission
-    let deadline = deadline + Duration::seconds(5);
-    let timestamp = deadline.format("%Y-%m-%d %H:%M:%S").to_string();
-    let res = conn.batch_execute(&*format!(
-        r#"
-        DROP EVENT IF EXISTS close_submission_{id};
-        CREATE EVENT close_submission_{id}
-        ON SCHEDULE AT '{timestamp}'
-        DO
-          UPDATE submissions
-          SET reviewsdone = 1, locked = 1,
-          error = CASE
-            WHEN exists(select * from reviews where submission={id} and error=0) THEN 0
-            ELSE 1
-          END
-          WHERE id = {id};
-    "#,
-        id = submission_id,
-        timestamp = timestamp
-    ));
-    if res.is_err() {
-        println!("{}", res.err().unwrap());
-        return Err(DbError::new(
-            DbErrorKind::EventCreateFailed,
-            "Event Insert failed",
-        ));
-    }*/
-    /*
-    select r.id, r.done, r.deadline, s.id, s.student, s.workshop
-        from reviews r
-        inner join submissions s on r.submission=s.id
-        where s.student=4;
-
-    select r.id, r.done, r.deadline, s.id, s.student, w.title
-        from reviews r
-        inner join submissions s on r.submission=s.id
-        inner join workshops w on s.workshop=w.id
-        where s.student=4;
+/// Create a review for a teacher of the submission's workshop.
+/// If the teacher already reviews the submission the existing review is returned.
+pub fn create_teacher_review(
+    conn: &MysqlConnection,
+    submission_id: u64,
+    teacher_id: u64,
+) -> Result<Review, DbError> {
+    let submission = db::submissions::get_by_id(conn, submission_id);
+    if submission.is_err() {
+        return Err(DbError::new(
+            DbErrorKind::NotFound,
+            format!("Submission {} not found", submission_id),
+        ));
+    }
+    let submission = submission.unwrap();
+    if !db::workshops::teacher_in_workshop(conn, teacher_id, submission.workshop) {
+        return Err(DbError::new(
+            DbErrorKind::NotFound,
+            format!(
+                "Teacher {} not in Workshop {}",
+                teacher_id, submission.workshop
+            ),
+        ));
+    }
 
-    select r.id, r.done, r.deadline, s.id, s.student, w.title
-        from reviews r
-        inner join submissions s on r.submission=s.id
-        inner join workshops w on s.workshop=w.id
-        where s.student=4;
+    // Return already assigned review
+    let review: Result<Review, _> = reviews_t
+        .filter(reviewer.eq(teacher_id).and(reviews_sub.eq(submission_id)))
+        .first(conn);
+    if let Ok(review) = review {
+        return Ok(review);
+    }
 
-    select r.id, r.done, r.deadline, s.id, u.firstname, u.lastname, w.title
-        from reviews r
-        inner join submissions s on r.submission=s.id
-        inner join workshops w on s.workshop=w.id
-        inner join users u on s.student=u.id
-        where s.student=4;
-     */
+    if Local::now().naive_local() > submission.deadline {
+        return Err(DbError::new(
+            DbErrorKind::PastDeadline,
+            "Reviews cannot be created past deadline",
+        ));
+    }
 
-    Ok(())
+    let new_review = NewReview {
+        feedback: "".to_string(),
+        reviewer: Some(teacher_id),
+        submission: submission_id,
+        workshop: submission.workshop,
+        deadline: submission.deadline,
+        done: false,
+        locked: false,
+        error: false,
+        teacher: true,
+        discarded: false,
+        selfassessment: false,
+    };
+    let review_insert = diesel::insert_into(reviews_t)
+        .values(&new_review)
+        .execute(conn);
+    if review_insert.is_err() {
+        return Err(DbError::new(
+            DbErrorKind::CreateFailed,
+            "Review Insert failed",
+        ));
+    }
+    let review = reviews_t.order(reviews_id.desc()).first::<Review>(conn);
+    match review {
+        Ok(review) => Ok(review),
+        Err(_) => Err(DbError::new(
+            DbErrorKind::ReadFailed,
+            "Could not get Review",
+        )),
+    }
 }
 
-/// Update review.
+/// Update review draft.
+/// Points can be given partially and replace already given points of the same criteria.
+/// The review is only marked as done when it is submitted.
 /// Can be performed multiple times until review is locked on deadline.
 pub fn update(
     conn: &MysqlConnection,
@@ -229,17 +409,29 @@ pub fn update(
         return Err(DbError::new(DbErrorKind::ReadFailed, "No matching Review"));
     }
     let mut review = review.unwrap();
-    if Local::now().naive_local() > review.deadline {
+    if Local::now().naive_local() > db::extensions::get_review_deadline(conn, &review) {
         // Update past deadline
         return Err(DbError::new(
             DbErrorKind::PastDeadline,
             "Update past deadline",
         ));
     }
+    if let Err(err) = check_unlocked(conn, &review) {
+        return Err(err);
+    }
+    // Get grade scale of workshop
+    let workshop: Result<Workshop, _> = workshops_t.filter(ws_id.eq(review.workshop)).first(conn);
+    if workshop.is_err() {
+        return Err(DbError::new(
+            DbErrorKind::ReadFailed,
+            format!("Workshop for Review {} not found", review_id),
+        ));
+    }
+    let workshop = workshop.unwrap();
     // Update review
     let mut t_error: Result<(), DbError> = Ok(());
     let res = conn.transaction::<_, _, _>(|| {
-        // Check if all point criteria were given for update
+        // Check if points belong to criteria of review
         let criteria = db::submissions::get_criteria(conn, review.submission);
         if criteria.is_err() {
             return DbError::assign_and_rollback(
@@ -248,26 +440,27 @@ pub fn update(
             );
         }
         let criteria = criteria.unwrap();
-        let update_ids: Vec<u64> = update_review
-            .points
-            .iter()
-            .map(|update_points| update_points.id)
-            .collect();
-        for criterion in &criteria {
-            if !update_ids.contains(&criterion.id) {
-                return DbError::assign_and_rollback(
-                    &mut t_error,
-                    DbError::new(
-                        DbErrorKind::Mismatch,
-                        format!("Criterion Id {} is missing in Update", &criterion.id),
-                    ),
-                );
+        if let Err(err) = check_points(conn, &criteria, &update_review.points) {
+            return DbError::assign_and_rollback(&mut t_error, err);
+        }
+
+        // Reviews refer to the version of the submission that was reviewed first
+        if review.version.is_none() {
+            match db::versions::get_latest(conn, review.submission) {
+                Ok(version) => review.version = Some(version.id),
+                Err(_) => {
+                    return DbError::assign_and_rollback(
+                        &mut t_error,
+                        DbError::new(DbErrorKind::ReadFailed, "Submission Version not found"),
+                    )
+                }
             }
         }
 
         // Update review
         review.feedback = update_review.feedback;
-        review.done = true;
+        // Changed reviews need to be submitted again
+        review.done = false;
         let review_update = diesel::update(reviews_t.filter(reviews_id.eq(review.id)))
             .set(&review)
             .execute(conn);
@@ -280,32 +473,18 @@ pub fn update(
 
         // Update review points
         // First `update_review` needs to be changed into a insertable form
-        let review_points: Vec<ReviewPoints> = update_review
+        let update_ids: Vec<u64> = update_review
             .points
-            .into_iter()
-            .map(|update_points| {
-                // Validate & Correct points
-                let criterion = criteria
-                    .iter()
-                    .filter(|c| c.id == update_points.id)
-                    .next()
-                    .unwrap();
-                let max_points = criterion.kind.max_points();
-                let points = if update_points.points > max_points {
-                    max_points
-                } else {
-                    update_points.points
-                };
-                ReviewPoints {
-                    review: review_id,
-                    criterion: update_points.id,
-                    points,
-                }
-            })
+            .iter()
+            .map(|update_points| update_points.id)
             .collect();
+        let review_points = to_review_points(review_id, &criteria, &workshop, update_review.points);
 
-        // Drop already given review points
-        let delete = diesel::delete(reviewpoints_t.filter(rp_review.eq(review_id))).execute(conn);
+        // Drop already given review points of updated criteria
+        let delete = diesel::delete(
+            reviewpoints_t.filter(rp_review.eq(review_id).and(rp_criterion.eq_any(update_ids))),
+        )
+        .execute(conn);
         if delete.is_err() {
             // println!("E {}", delete.err().unwrap());
             return DbError::assign_and_rollback(
@@ -328,16 +507,351 @@ pub fn update(
         Ok(())
     });
 
-    if res.is_ok() {
-        Ok(())
-    } else {
-        // Transaction error should have custom DbError, use it
-        // If not, return general error message
-        Err(t_error.err().unwrap_or(DbError::new(
-            DbErrorKind::TransactionFailed,
-            "Unknown error",
-        )))
+    if res.is_ok() {
+        Ok(())
+    } else {
+        // Transaction error should have custom DbError, use it
+        // If not, return general error message
+        Err(t_error.err().unwrap_or(DbError::new(
+            DbErrorKind::TransactionFailed,
+            "Unknown error",
+        )))
+    }
+}
+
+// Check if a peer review is unlocked.
+// Students need to submit all calibration reviews of the workshop first.
+fn check_unlocked(conn: &MysqlConnection, review: &Review) -> Result<(), DbError> {
+    if review.teacher || review.selfassessment {
+        return Ok(());
+    }
+    let submission = db::submissions::get_by_id(conn, review.submission);
+    if submission.is_err() {
+        return Err(DbError::new(
+            DbErrorKind::ReadFailed,
+            format!("Submission for Review {} not found", review.id),
+        ));
+    }
+    if submission.unwrap().calibration {
+        return Ok(());
+    }
+    match review.reviewer {
+        Some(student_id) if !db::calibration::is_done(conn, review.workshop, student_id) => {
+            Err(DbError::new(
+                DbErrorKind::NotFinished,
+                "Calibration Reviews need to be submitted first",
+            ))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Check if given points belong to the criteria of a review.
+/// Rubric criteria only accept the points of their levels.
+pub(crate) fn check_points(
+    conn: &MysqlConnection,
+    criteria: &[Criterion],
+    points: &[RouteUpdatePoints],
+) -> Result<(), DbError> {
+    for update_points in points {
+        if !criteria.iter().any(|c| c.id == update_points.id) {
+            return Err(DbError::new(
+                DbErrorKind::Mismatch,
+                format!(
+                    "Criterion Id {} does not belong to Review",
+                    update_points.id
+                ),
+            ));
+        }
+    }
+    let mut rubric_levels: HashMap<u64, Vec<RubricLevel>> = HashMap::new();
+    for criterion in criteria.iter().filter(|c| c.kind == Kind::Rubric) {
+        let levels = db::criteria::get_levels(conn, criterion.id);
+        if levels.is_err() {
+            return Err(DbError::new(
+                DbErrorKind::ReadFailed,
+                "Rubric Levels for Review not found",
+            ));
+        }
+        rubric_levels.insert(criterion.id, levels.unwrap());
+    }
+    for update_points in points {
+        if let Some(levels) = rubric_levels.get(&update_points.id) {
+            if !levels
+                .iter()
+                .any(|level| (level.points - update_points.points).abs() < f64::EPSILON)
+            {
+                return Err(DbError::new(
+                    DbErrorKind::Mismatch,
+                    format!(
+                        "Points of Criterion {} do not match a Rubric Level",
+                        update_points.id
+                    ),
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Change given points into an insertable form.
+/// Points are corrected to the range of their criterion, see [`check_points`] for validation.
+pub(crate) fn to_review_points(
+    review_id: u64,
+    criteria: &[Criterion],
+    workshop: &Workshop,
+    points: Vec<RouteUpdatePoints>,
+) -> Vec<ReviewPoints> {
+    points
+        .into_iter()
+        .map(|update_points| {
+            let criterion = criteria
+                .iter()
+                .filter(|c| c.id == update_points.id)
+                .next()
+                .unwrap();
+            let points = match criterion.kind {
+                // Text criteria only hold a comment
+                Kind::Text => None,
+                Kind::Range => {
+                    let points = normalization::clamp(
+                        &criterion.kind,
+                        &workshop.gradescale,
+                        criterion.custom_range(),
+                        update_points.points,
+                    );
+                    match (criterion.custom_range(), criterion.step) {
+                        (Some((min, max)), Some(step)) => {
+                            Some(normalization::round_to_step(min, max, step, points))
+                        }
+                        _ => Some(points),
+                    }
+                }
+                _ => Some(normalization::clamp(
+                    &criterion.kind,
+                    &workshop.gradescale,
+                    criterion.custom_range(),
+                    update_points.points,
+                )),
+            };
+            ReviewPoints {
+                review: review_id,
+                criterion: update_points.id,
+                points,
+                comment: update_points
+                    .comment
+                    .filter(|comment| comment.trim().len() > 0),
+            }
+        })
+        .collect()
+}
+
+/// Submit review draft.
+/// All criteria need points and mandatory comments so that the review is marked as done.
+pub fn submit(conn: &MysqlConnection, review_id: u64, user_id: u64) -> Result<(), DbError> {
+    // Get review
+    let review: Result<Review, _> = reviews_t
+        .filter(reviews_id.eq(review_id).and(reviewer.eq(user_id)))
+        .first(conn);
+    if review.is_err() {
+        // No matching review
+        return Err(DbError::new(DbErrorKind::ReadFailed, "No matching Review"));
+    }
+    let review = review.unwrap();
+    if Local::now().naive_local() > db::extensions::get_review_deadline(conn, &review) {
+        return Err(DbError::new(
+            DbErrorKind::PastDeadline,
+            "Submit past deadline",
+        ));
+    }
+    if let Err(err) = check_unlocked(conn, &review) {
+        return Err(err);
+    }
+    let workshop: Result<Workshop, _> = workshops_t.filter(ws_id.eq(review.workshop)).first(conn);
+    if workshop.is_err() {
+        return Err(DbError::new(
+            DbErrorKind::ReadFailed,
+            format!("Workshop for Review {} not found", review_id),
+        ));
+    }
+    let workshop = workshop.unwrap();
+    let criteria = db::submissions::get_criteria(conn, review.submission);
+    if criteria.is_err() {
+        return Err(DbError::new(
+            DbErrorKind::ReadFailed,
+            "Criteria for Review not found",
+        ));
+    }
+    let points = reviewpoints_t
+        .filter(rp_review.eq(review_id))
+        .get_results::<ReviewPoints>(conn);
+    if points.is_err() {
+        return Err(DbError::new(
+            DbErrorKind::ReadFailed,
+            "Review Points not found",
+        ));
+    }
+    let complete = check_complete(&criteria.unwrap(), &points.unwrap(), &workshop);
+    if let Err(err) = complete {
+        return Err(err);
+    }
+
+    let update = diesel::update(reviews_t.filter(reviews_id.eq(review_id)))
+        .set(reviews_done.eq(true))
+        .execute(conn);
+    if update.is_err() {
+        return Err(DbError::new(
+            DbErrorKind::UpdateFailed,
+            "Review Update failed",
+        ));
+    }
+    if !review.selfassessment {
+        if let Err(err) = db::emails::notify_review_received(conn, &review, &workshop) {
+            err.print_stacktrace();
+        }
+        if let Err(err) = db::webhooks::review_completed(conn, &review) {
+            err.print_stacktrace();
+        }
+        if let Ok(submission) = db::submissions::get_by_id(conn, review.submission) {
+            if let Ok(owners) = db::submissions::get_owner_ids(conn, &submission) {
+                events::publish(
+                    &owners,
+                    Event {
+                        kind: EventKind::ReviewSubmitted,
+                        workshop: Some(review.workshop),
+                        submission: Some(review.submission),
+                        review: Some(review.id),
+                    },
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+// Check if review points are complete.
+// Every scored criterion needs points and comments are needed below the comment threshold.
+fn check_complete(
+    criteria: &[Criterion],
+    points: &[ReviewPoints],
+    workshop: &Workshop,
+) -> Result<(), DbError> {
+    for criterion in criteria.iter().filter(|c| c.kind.is_scored()) {
+        let criterion_points = points
+            .iter()
+            .find(|p| p.criterion == criterion.id && p.points.is_some());
+        if criterion_points.is_none() {
+            return Err(DbError::new(
+                DbErrorKind::Mismatch,
+                format!("Criterion Id {} is missing in Review", &criterion.id),
+            ));
+        }
+        let criterion_points = criterion_points.unwrap();
+        // Comments can be mandatory for criteria with few points
+        if let Some(threshold) = workshop.commentthreshold {
+            let fraction = normalization::normalize(
+                &criterion.kind,
+                &workshop.gradescale,
+                criterion.custom_range(),
+                criterion_points.points.unwrap(),
+            );
+            if fraction < threshold && criterion_points.comment.is_none() {
+                return Err(DbError::new(
+                    DbErrorKind::Mismatch,
+                    format!("Comment for Criterion {} is required", criterion.id),
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Rate the helpfulness of a received review.
+/// Only the submission owner can rate and only after all reviews are done.
+/// An existing rating of the review is replaced.
+pub fn rate(
+    conn: &MysqlConnection,
+    review_id: u64,
+    student_id: u64,
+    helpfulness: i32,
+    comment: Option<String>,
+) -> Result<(), DbError> {
+    let review: Result<Review, _> = reviews_t.filter(reviews_id.eq(review_id)).first(conn);
+    if review.is_err() {
+        return Err(DbError::new(
+            DbErrorKind::NotFound,
+            format!("Review {} not found", review_id),
+        ));
+    }
+    let review = review.unwrap();
+    let submission = db::submissions::get_by_id(conn, review.submission);
+    if submission.is_err() {
+        return Err(DbError::new(
+            DbErrorKind::ReadFailed,
+            format!("Submission for Review {} not found", review_id),
+        ));
+    }
+    let submission = submission.unwrap();
+    if !db::submissions::is_owner(conn, submission.id, student_id) {
+        return Err(DbError::new(
+            DbErrorKind::Mismatch,
+            "Review does not belong to own Submission",
+        ));
+    }
+    if !submission.reviewsdone {
+        return Err(DbError::new(
+            DbErrorKind::NotFinished,
+            "Reviews of Submission are not done",
+        ));
+    }
+    if review.error {
+        return Err(DbError::new(
+            DbErrorKind::Mismatch,
+            "Review was not submitted",
+        ));
+    }
+    if review.selfassessment {
+        return Err(DbError::new(
+            DbErrorKind::Mismatch,
+            "Self-Assessments cannot be rated",
+        ));
+    }
+    let workshop = db::workshops::get_by_id(conn, submission.workshop);
+    if workshop.is_err() {
+        return Err(DbError::new(
+            DbErrorKind::ReadFailed,
+            format!("Workshop for Review {} not found", review_id),
+        ));
+    }
+    let released =
+        db::workshops::check_results_released(&workshop.unwrap(), Local::now().naive_local());
+    if let Err(err) = released {
+        return Err(err);
+    }
+
+    let rating = ReviewRating {
+        review: review_id,
+        helpfulness,
+        comment: comment.filter(|c| c.trim().len() > 0),
+    };
+    let insert = diesel::replace_into(reviewratings_t)
+        .values(&rating)
+        .execute(conn);
+    if insert.is_err() {
+        return Err(DbError::new(
+            DbErrorKind::CreateFailed,
+            "Could not save Review Rating",
+        ));
     }
+    Ok(())
+}
+
+// Get the helpfulness rating of a review.
+fn get_rating(conn: &MysqlConnection, review_id: u64) -> Option<ReviewRating> {
+    reviewratings_t
+        .filter(rr_review.eq(review_id))
+        .first::<ReviewRating>(conn)
+        .ok()
 }
 
 pub(crate) fn close_reviews(conn: &MysqlConnection, submission_id: u64) -> Result<(), DbError> {
@@ -354,26 +868,65 @@ pub(crate) fn close_reviews(conn: &MysqlConnection, submission_id: u64) -> Resul
     // Check if reviews are present
     let reviews: Vec<Review> = reviews.unwrap();
     if reviews.len() > 0 {
+        // Drafts are handled by the draft policy of the workshop
+        let workshop = db::workshops::get_by_submission_id(conn, submission_id);
+        if workshop.is_err() {
+            return Err(DbError::new(
+                DbErrorKind::ReadFailed,
+                format!("Workshop for Submission {} not found", submission_id),
+            ));
+        }
+        let workshop = workshop.unwrap();
+        let criteria = db::submissions::get_criteria(conn, submission_id);
+        if criteria.is_err() {
+            return Err(DbError::new(
+                DbErrorKind::ReadFailed,
+                format!("Criteria for Submission {} not found", submission_id),
+            ));
+        }
+        let criteria = criteria.unwrap();
         let mut t_error: Result<(), DbError> = Ok(());
         let res = conn.transaction::<_, _, _>(|| {
             // Iterate over found reviews
             for mut review in reviews {
+                let submitted = review.done;
                 // Mark them as finished
                 review.done = true;
                 review.locked = true;
                 // Check if review was done
                 // If not, mark as error case
                 // --------------------------
-                // not(exists(select * from reviewpoints where review={id}))
-                let error = select(not(exists(reviewpoints_t.filter(rp_review.eq(review.id)))))
-                    .get_result(conn);
-                if error.is_err() {
-                    return DbError::assign_and_rollback(
-                        &mut t_error,
-                        DbError::new(DbErrorKind::ReadFailed, "Could not get Review state"),
-                    );
-                }
-                review.error = error.unwrap();
+                review.error = if submitted {
+                    // not(exists(select * from reviewpoints where review={id}))
+                    let error = select(not(exists(reviewpoints_t.filter(rp_review.eq(review.id)))))
+                        .get_result(conn);
+                    if error.is_err() {
+                        return DbError::assign_and_rollback(
+                            &mut t_error,
+                            DbError::new(DbErrorKind::ReadFailed, "Could not get Review state"),
+                        );
+                    }
+                    error.unwrap()
+                } else {
+                    match workshop.draftpolicy {
+                        DraftPolicy::Discard => true,
+                        DraftPolicy::Acceptcomplete => {
+                            let points = reviewpoints_t
+                                .filter(rp_review.eq(review.id))
+                                .get_results::<ReviewPoints>(conn);
+                            if points.is_err() {
+                                return DbError::assign_and_rollback(
+                                    &mut t_error,
+                                    DbError::new(
+                                        DbErrorKind::ReadFailed,
+                                        "Could not get Review Points",
+                                    ),
+                                );
+                            }
+                            check_complete(&criteria, &points.unwrap(), &workshop).is_err()
+                        }
+                    }
+                };
                 // Update review
                 let review_update = diesel::update(reviews_t.filter(reviews_id.eq(review.id)))
                     .set(&review)
@@ -404,42 +957,497 @@ pub(crate) fn close_reviews(conn: &MysqlConnection, submission_id: u64) -> Resul
 pub fn get_simple_review_points(
     conn: &MysqlConnection,
     submission_id: u64,
-) -> Result<Vec<Vec<SimpleReviewPoints>>, DbError> {
+) -> Result<Vec<SimpleReview>, DbError> {
+    // Discarded reviews and self-assessments are not part of the points
     let reviews = reviews_t
-        .filter(reviews_sub.eq(submission_id).and(reviews_error.eq(false)))
-        .select(reviews_id)
-        .get_results::<u64>(conn);
+        .filter(
+            reviews_sub
+                .eq(submission_id)
+                .and(reviews_error.eq(false).and(reviews_discarded.eq(false)))
+                .and(reviews_self.eq(false)),
+        )
+        .select((reviews_id, reviews_teacher, reviewer, reviews_sub))
+        .get_results::<(u64, bool, Option<u64>, u64)>(conn);
+    if reviews.is_err() {
+        return Err(DbError::new(
+            DbErrorKind::ReadFailed,
+            format!("No Reviews for Submission {} found", submission_id),
+        ));
+    }
+    get_simple_review_points_internal(conn, reviews.unwrap())
+}
+
+/// Get simplified review points of all submitted reviews from a submission.
+/// Unlike [`get_simple_review_points`] drafts are not included.
+pub fn get_submitted_review_points(
+    conn: &MysqlConnection,
+    submission_id: u64,
+) -> Result<Vec<SimpleReview>, DbError> {
+    let reviews = reviews_t
+        .filter(
+            reviews_sub
+                .eq(submission_id)
+                .and(reviews_done.eq(true).and(reviews_error.eq(false))),
+        )
+        .select((reviews_id, reviews_teacher, reviewer, reviews_sub))
+        .get_results::<(u64, bool, Option<u64>, u64)>(conn);
     if reviews.is_err() {
         return Err(DbError::new(
             DbErrorKind::ReadFailed,
             format!("No Reviews for Submission {} found", submission_id),
         ));
     }
-    let reviews: Vec<u64> = reviews.unwrap();
+    get_simple_review_points_internal(conn, reviews.unwrap())
+}
+
+/// Get the reliability of all student reviewers from a workshop.
+/// The reliability is based on how much a reviewer agrees with the other reviewers
+/// of finished submissions.
+pub fn get_reviewer_reliabilities(
+    conn: &MysqlConnection,
+    workshop_id: u64,
+    grade_scale: &GradeScale,
+) -> Result<HashMap<u64, f64>, DbError> {
+    // Calibration reviews are compared with the reference review instead
+    let reviews = reviews_t
+        .inner_join(submissions_t.on(sub_id.eq(reviews_sub)))
+        .filter(
+            reviews_ws.eq(workshop_id).and(
+                reviews_done
+                    .eq(true)
+                    .and(reviews_error.eq(false).and(reviews_teacher.eq(false)))
+                    .and(reviews_discarded.eq(false))
+                    .and(reviews_self.eq(false))
+                    .and(sub_calibration.eq(false)),
+            ),
+        )
+        .select((reviews_id, reviews_teacher, reviewer, reviews_sub))
+        .get_results::<(u64, bool, Option<u64>, u64)>(conn);
+    if reviews.is_err() {
+        return Err(DbError::new(
+            DbErrorKind::ReadFailed,
+            format!("No Reviews for Workshop {} found", workshop_id),
+        ));
+    }
+    let reviews = get_simple_review_points_internal(conn, reviews.unwrap());
+    if let Err(err) = reviews {
+        return Err(err);
+    }
+    let scores: Vec<ReviewerScore> = reviews
+        .unwrap()
+        .into_iter()
+        .filter_map(|review| {
+            let max_points = grading::max_points(&review.points);
+            match review.reviewer {
+                Some(reviewer_id) if max_points > 0.0 => Some(ReviewerScore {
+                    submission: review.submission,
+                    reviewer: reviewer_id,
+                    fraction: grading::review_points(&review.points, grade_scale) / max_points,
+                }),
+                _ => None,
+            }
+        })
+        .collect();
+    Ok(aggregation::reliabilities(&scores))
+}
+
+/// Get the helpfulness of the reviews written by every student of a workshop.
+/// Only teachers of the workshop can view the helpfulness.
+pub fn get_reviewer_helpfulness(
+    conn: &MysqlConnection,
+    teacher_id: u64,
+    workshop_id: u64,
+) -> Result<Vec<ReviewerHelpfulness>, DbError> {
+    if !db::workshops::teacher_in_workshop(conn, teacher_id, workshop_id) {
+        return Err(DbError::new(
+            DbErrorKind::NotFound,
+            format!("Teacher {} not in Workshop {}", teacher_id, workshop_id),
+        ));
+    }
+    let students = workshoplist_t
+        .inner_join(users_t.on(u_id.eq(wsl_user)))
+        .filter(wsl_ws.eq(workshop_id).and(wsl_role.eq(Role::Student)))
+        .select((u_id, u_firstname, u_lastname))
+        .order(u_id)
+        .get_results::<(u64, String, String)>(conn);
+    if students.is_err() {
+        return Err(DbError::new(
+            DbErrorKind::ReadFailed,
+            format!("No Students for Workshop {} found", workshop_id),
+        ));
+    }
+    let ratings = reviewratings_t
+        .inner_join(reviews_t.on(reviews_id.eq(rr_review)))
+        .filter(reviews_ws.eq(workshop_id).and(reviews_teacher.eq(false)))
+        .select((reviewer, rr_helpfulness))
+        .get_results::<(Option<u64>, i32)>(conn);
+    if ratings.is_err() {
+        return Err(DbError::new(
+            DbErrorKind::ReadFailed,
+            format!("No Review Ratings for Workshop {} found", workshop_id),
+        ));
+    }
+    let mut by_reviewer: HashMap<u64, Vec<i32>> = HashMap::new();
+    for (reviewer_id, helpfulness) in ratings.unwrap() {
+        if let Some(reviewer_id) = reviewer_id {
+            by_reviewer
+                .entry(reviewer_id)
+                .or_default()
+                .push(helpfulness);
+        }
+    }
+    Ok(students
+        .unwrap()
+        .into_iter()
+        .map(|(student_id, firstname, lastname)| {
+            let ratings = by_reviewer.remove(&student_id).unwrap_or_default();
+            ReviewerHelpfulness {
+                id: student_id,
+                firstname,
+                lastname,
+                ratings: ratings.len(),
+                helpfulness: reviewing::helpfulness_fraction(&ratings),
+            }
+        })
+        .collect())
+}
+
+/// Get the helpfulness (`0.0..=1.0`) of the reviews a student wrote in a workshop.
+/// Returns `None` if none of the reviews are rated.
+pub fn get_student_helpfulness(
+    conn: &MysqlConnection,
+    workshop_id: u64,
+    student_id: u64,
+) -> Option<f64> {
+    let ratings = reviewratings_t
+        .inner_join(reviews_t.on(reviews_id.eq(rr_review)))
+        .filter(reviews_ws.eq(workshop_id).and(reviewer.eq(student_id)))
+        .select(rr_helpfulness)
+        .get_results::<i32>(conn);
+    match ratings {
+        Ok(ratings) => reviewing::helpfulness_fraction(&ratings),
+        Err(_) => None,
+    }
+}
+
+/// Count the written & missed peer reviews of a student in a workshop.
+/// Reviews of calibration submissions and self-assessments are not counted.
+pub fn get_review_counts(
+    conn: &MysqlConnection,
+    workshop_id: u64,
+    student_id: u64,
+) -> Result<(i64, i64), DbError> {
+    let reviews = reviews_t
+        .inner_join(submissions_t.on(sub_id.eq(reviews_sub)))
+        .filter(
+            reviews_ws.eq(workshop_id).and(
+                reviewer
+                    .eq(student_id)
+                    .and(reviews_teacher.eq(false))
+                    .and(reviews_self.eq(false))
+                    .and(sub_calibration.eq(false)),
+            ),
+        )
+        .select((reviews_done, reviews_error))
+        .get_results::<(bool, bool)>(conn);
+    if reviews.is_err() {
+        return Err(DbError::new(
+            DbErrorKind::ReadFailed,
+            format!("No Reviews of Student {} found", student_id),
+        ));
+    }
+    let reviews = reviews.unwrap();
+    let written = reviews
+        .iter()
+        .filter(|(done, error)| *done && !*error)
+        .count() as i64;
+    let missed = reviews.iter().filter(|(_, error)| *error).count() as i64;
+    Ok((written, missed))
+}
+
+/// Get the deviation of every submitted self-assessment of a workshop from the peer reviews.
+/// Only graded submissions are compared, teacher reviews are not part of the peer points.
+/// Only teachers of the workshop can view the deviations.
+pub fn get_self_assessment_deviations(
+    conn: &MysqlConnection,
+    teacher_id: u64,
+    workshop_id: u64,
+) -> Result<Vec<SelfAssessmentDeviation>, DbError> {
+    if !db::workshops::teacher_in_workshop(conn, teacher_id, workshop_id) {
+        return Err(DbError::new(
+            DbErrorKind::NotFound,
+            format!("Teacher {} not in Workshop {}", teacher_id, workshop_id),
+        ));
+    }
+    let workshop: Result<Workshop, _> = workshops_t.filter(ws_id.eq(workshop_id)).first(conn);
+    if workshop.is_err() {
+        return Err(DbError::new(
+            DbErrorKind::NotFound,
+            format!("Workshop {} not found", workshop_id),
+        ));
+    }
+    let workshop = workshop.unwrap();
+    let self_assessments = reviews_t
+        .inner_join(submissions_t.on(sub_id.eq(reviews_sub)))
+        .inner_join(users_t.on(u_id.nullable().eq(reviewer)))
+        .filter(
+            reviews_ws.eq(workshop_id).and(
+                reviews_self
+                    .eq(true)
+                    .and(reviews_done.eq(true).and(reviews_error.eq(false)))
+                    .and(sub_reviews_done.eq(true)),
+            ),
+        )
+        .select((
+            reviews_id,
+            reviews_sub,
+            sub_title,
+            u_id,
+            u_firstname,
+            u_lastname,
+        ))
+        .order(reviews_sub)
+        .get_results::<(u64, u64, String, u64, String, String)>(conn);
+    if self_assessments.is_err() {
+        return Err(DbError::new(
+            DbErrorKind::ReadFailed,
+            format!("No Self-Assessments for Workshop {} found", workshop_id),
+        ));
+    }
+    let mut deviations: Vec<SelfAssessmentDeviation> = Vec::new();
+    for (review_id, submission_id, title, student_id, firstname, lastname) in
+        self_assessments.unwrap()
+    {
+        let own = get_simple_review_points_internal(
+            conn,
+            vec![(review_id, false, Some(student_id), submission_id)],
+        );
+        if let Err(err) = own {
+            return Err(err);
+        }
+        let own = own
+            .unwrap()
+            .pop()
+            .map(|review| calibration::criterion_fractions(&review.points, &workshop.gradescale))
+            .unwrap_or_default();
+        let peers = get_simple_review_points(conn, submission_id);
+        if let Err(err) = peers {
+            return Err(err);
+        }
+        let peers: Vec<HashMap<u64, f64>> = peers
+            .unwrap()
+            .into_iter()
+            .filter(|review| !review.teacher)
+            .map(|review| calibration::criterion_fractions(&review.points, &workshop.gradescale))
+            .collect();
+        let peer_fractions = selfassessment::peer_fractions(&peers);
+        let criterion_deviations = selfassessment::deviations(&own, &peer_fractions);
+        let criteria = criterion_t
+            .filter(c_id.eq_any(criterion_deviations.keys().copied().collect::<Vec<u64>>()))
+            .select((c_id, c_title))
+            .get_results::<(u64, String)>(conn);
+        if criteria.is_err() {
+            return Err(DbError::new(
+                DbErrorKind::ReadFailed,
+                format!("Criteria for Submission {} not found", submission_id),
+            ));
+        }
+        let titles: HashMap<u64, String> = criteria.unwrap().into_iter().collect();
+        let mut criteria: Vec<SelfCriterionDeviation> = criterion_deviations
+            .iter()
+            .map(|(criterion, deviation)| SelfCriterionDeviation {
+                id: *criterion,
+                title: titles.get(criterion).cloned().unwrap_or_default(),
+                own: own[criterion],
+                peers: peer_fractions[criterion],
+                deviation: *deviation,
+            })
+            .collect();
+        criteria.sort_by_key(|criterion| criterion.id);
+        deviations.push(SelfAssessmentDeviation {
+            id: review_id,
+            submission: submission_id,
+            title,
+            firstname,
+            lastname,
+            reviews: peers.len(),
+            deviation: selfassessment::mean_deviation(&criterion_deviations),
+            criteria,
+        });
+    }
+    Ok(deviations)
+}
+
+/// Get the agreement of the peer reviews of all graded submissions from a workshop.
+/// Teacher reviews are not part of the statistics.
+/// Submissions are not graded here, ungraded submissions are left out.
+/// Only teachers of the workshop can view the statistics.
+pub fn get_workshop_statistics(
+    conn: &MysqlConnection,
+    teacher_id: u64,
+    workshop_id: u64,
+) -> Result<WorkshopStatistics, DbError> {
+    if !db::workshops::teacher_in_workshop(conn, teacher_id, workshop_id) {
+        return Err(DbError::new(
+            DbErrorKind::NotFound,
+            format!("Teacher {} not in Workshop {}", teacher_id, workshop_id),
+        ));
+    }
+    let workshop: Result<Workshop, _> = workshops_t.filter(ws_id.eq(workshop_id)).first(conn);
+    if workshop.is_err() {
+        return Err(DbError::new(
+            DbErrorKind::NotFound,
+            format!("Workshop {} not found", workshop_id),
+        ));
+    }
+    let workshop = workshop.unwrap();
+    let graded_submissions = submissions_t
+        .filter(
+            sub_ws
+                .eq(workshop_id)
+                .and(sub_calibration.eq(false))
+                .and(sub_reviews_done.eq(true)),
+        )
+        .order(sub_id)
+        .get_results::<Submission>(conn);
+    if graded_submissions.is_err() {
+        return Err(DbError::new(
+            DbErrorKind::ReadFailed,
+            format!("No Submissions for Workshop {} found", workshop_id),
+        ));
+    }
+    let graded_submissions = graded_submissions.unwrap();
+
+    let mut submission_fractions: Vec<Vec<f64>> = Vec::new();
+    let mut outlier_count = 0;
+    let mut submission_statistics: Vec<SubmissionStatistics> = Vec::new();
+    for submission in graded_submissions {
+        let reviews = get_simple_review_points(conn, submission.id);
+        if let Err(err) = reviews {
+            return Err(err);
+        }
+        let reviews: Vec<SimpleReview> = reviews
+            .unwrap()
+            .into_iter()
+            .filter(|review| !review.teacher && grading::max_points(&review.points) > 0.0)
+            .collect();
+        let fractions: Vec<f64> = reviews
+            .iter()
+            .map(|review| {
+                grading::review_points(&review.points, &workshop.gradescale)
+                    / grading::max_points(&review.points)
+            })
+            .collect();
+        let outliers = statistics::outliers(&fractions);
+        outlier_count += outliers.iter().filter(|outlier| **outlier).count();
+
+        // Normalized points per criterion
+        let mut per_criterion: BTreeMap<u64, Vec<f64>> = BTreeMap::new();
+        for review in &reviews {
+            for (criterion, fraction) in
+                calibration::criterion_fractions(&review.points, &workshop.gradescale)
+            {
+                per_criterion.entry(criterion).or_default().push(fraction);
+            }
+        }
+        let criteria = criterion_t
+            .filter(c_id.eq_any(per_criterion.keys().copied().collect::<Vec<u64>>()))
+            .select((c_id, c_title))
+            .get_results::<(u64, String)>(conn);
+        if criteria.is_err() {
+            return Err(DbError::new(
+                DbErrorKind::ReadFailed,
+                format!("Criteria for Submission {} not found", submission.id),
+            ));
+        }
+        let titles: HashMap<u64, String> = criteria.unwrap().into_iter().collect();
+
+        let review_statistics: Vec<ReviewStatistics> = reviews
+            .iter()
+            .zip(fractions.iter().zip(outliers.iter()))
+            .map(|(review, (fraction, outlier))| {
+                let (firstname, lastname) = match review
+                    .reviewer
+                    .and_then(|reviewer_id| db::users::get_by_id(conn, reviewer_id).ok())
+                {
+                    Some(user) => (Some(user.firstname), Some(user.lastname)),
+                    None => (None, None),
+                };
+                ReviewStatistics {
+                    id: review.id,
+                    firstname,
+                    lastname,
+                    fraction: *fraction,
+                    outlier: *outlier,
+                }
+            })
+            .collect();
+        submission_statistics.push(SubmissionStatistics {
+            id: submission.id,
+            title: submission.title,
+            spread: statistics::spread(&fractions),
+            reviews: review_statistics,
+            criteria: per_criterion
+                .into_iter()
+                .filter_map(|(criterion, fractions)| {
+                    statistics::spread(&fractions).map(|spread| CriterionStatistics {
+                        id: criterion,
+                        title: titles.get(&criterion).cloned().unwrap_or_default(),
+                        spread,
+                    })
+                })
+                .collect(),
+        });
+        submission_fractions.push(fractions);
+    }
 
-    let mut simple_reviews: Vec<Vec<SimpleReviewPoints>> = Vec::new();
-    for review in reviews {
+    Ok(WorkshopStatistics {
+        agreement: statistics::agreement(&submission_fractions),
+        outliers: outlier_count,
+        submissions: submission_statistics,
+    })
+}
+
+// Get points for the given reviews (id, teacher, reviewer, submission).
+fn get_simple_review_points_internal(
+    conn: &MysqlConnection,
+    reviews: Vec<(u64, bool, Option<u64>, u64)>,
+) -> Result<Vec<SimpleReview>, DbError> {
+    let mut simple_reviews: Vec<SimpleReview> = Vec::new();
+    for (review, teacher, reviewer_id, submission_id) in reviews {
         let points = criterion_t
             .inner_join(reviewpoints_t.on(c_id.eq(rp_criterion)))
             .filter(rp_review.eq(review))
-            .select((c_weight, c_kind, rp_points))
-            .get_results::<(f64, Kind, Option<f64>)>(conn);
+            .select((c_id, c_weight, c_kind, c_minpoints, c_maxpoints, rp_points))
+            .get_results::<(u64, f64, Kind, Option<f64>, Option<f64>, Option<f64>)>(conn);
         if points.is_err() {
             return Err(DbError::new(
                 DbErrorKind::ReadFailed,
                 format!("No Points for Review {} found", review),
             ));
         }
-        let points: Vec<(f64, Kind, Option<f64>)> = points.unwrap();
+        let points: Vec<(u64, f64, Kind, Option<f64>, Option<f64>, Option<f64>)> = points.unwrap();
         let points: Vec<SimpleReviewPoints> = points
             .into_iter()
             .map(|point| SimpleReviewPoints {
-                weight: point.0,
-                kind: point.1,
-                points: point.2.unwrap(),
+                criterion: point.0,
+                weight: point.1,
+                kind: point.2,
+                custom_range: match (point.3, point.4) {
+                    (Some(min), Some(max)) => Some((min, max)),
+                    _ => None,
+                },
+                // Text criteria have no points
+                points: point.5.unwrap_or(0.0),
             })
             .collect();
-        simple_reviews.push(points);
+        simple_reviews.push(SimpleReview {
+            id: review,
+            teacher,
+            reviewer: reviewer_id,
+            submission: submission_id,
+            points,
+        });
     }
     Ok(simple_reviews)
 }
@@ -463,12 +1471,15 @@ fn get_full_reviews_internal(
         let points = criterion_t
             .inner_join(reviewpoints_t.on(c_id.eq(rp_criterion)))
             .filter(rp_review.eq(review.id))
-            .select((c_id, c_title, c_content, c_weight, c_kind, rp_points))
-            .get_results::<(u64, String, String, f64, Kind, Option<f64>)>(conn);
+            .select((
+                c_id, c_title, c_content, c_weight, c_kind, rp_points, rp_comment,
+            ))
+            .get_results::<(u64, String, String, f64, Kind, Option<f64>, Option<String>)>(conn);
         if points.is_err() {
             return Err(());
         }
-        let points: Vec<(u64, String, String, f64, Kind, Option<f64>)> = points.unwrap();
+        let points: Vec<(u64, String, String, f64, Kind, Option<f64>, Option<String>)> =
+            points.unwrap();
         let points: Vec<FullReviewPoints> = points
             .into_iter()
             .map(|point| FullReviewPoints {
@@ -477,7 +1488,8 @@ fn get_full_reviews_internal(
                 content: point.2,
                 weight: point.3,
                 kind: point.4,
-                points: point.5.unwrap(),
+                points: point.5,
+                comment: point.6,
             })
             .collect();
         let (firstname, lastname) = if with_names && review.reviewer.is_some() {
@@ -497,7 +1509,14 @@ fn get_full_reviews_internal(
             lastname,
             feedback: review.feedback.clone(),
             not_submitted: None,
+            draft: None,
+            teacher: review.teacher,
             points,
+            rating: get_rating(conn, review.id),
+            appeal: db::appeals::get_by_review(conn, review.id),
+            discarded: review.discarded,
+            self_assessment: review.selfassessment,
+            version: db::versions::get_number(conn, review.version),
         });
     }
     Ok(full_reviews)
@@ -528,6 +1547,7 @@ fn get_full_review_internal(
     conn: &MysqlConnection,
     review_id: u64,
     with_names: bool,
+    with_draft: bool,
 ) -> Result<FullReview, ()> {
     let review = reviews_t
         .filter(reviews_id.eq(review_id))
@@ -537,16 +1557,20 @@ fn get_full_review_internal(
     }
     let review: Review = review.unwrap();
 
-    let points = if review.done && !review.error {
+    // Drafts are only visible for the reviewer and teachers
+    let points = if (review.done || with_draft) && !review.error {
         let points = criterion_t
             .inner_join(reviewpoints_t.on(c_id.eq(rp_criterion)))
             .filter(rp_review.eq(review.id))
-            .select((c_id, c_title, c_content, c_weight, c_kind, rp_points))
-            .get_results::<(u64, String, String, f64, Kind, Option<f64>)>(conn);
+            .select((
+                c_id, c_title, c_content, c_weight, c_kind, rp_points, rp_comment,
+            ))
+            .get_results::<(u64, String, String, f64, Kind, Option<f64>, Option<String>)>(conn);
         if points.is_err() {
             Vec::new()
         } else {
-            let points: Vec<(u64, String, String, f64, Kind, Option<f64>)> = points.unwrap();
+            let points: Vec<(u64, String, String, f64, Kind, Option<f64>, Option<String>)> =
+                points.unwrap();
             points
                 .into_iter()
                 .map(|point| FullReviewPoints {
@@ -555,7 +1579,8 @@ fn get_full_review_internal(
                     content: point.2,
                     weight: point.3,
                     kind: point.4,
-                    points: point.5.unwrap(),
+                    points: point.5,
+                    comment: point.6,
                 })
                 .collect()
         }
@@ -581,20 +1606,30 @@ fn get_full_review_internal(
         lastname,
         feedback: review.feedback.clone(),
         not_submitted: Some(review.error),
+        draft: if with_draft { Some(!review.done) } else { None },
+        teacher: review.teacher,
         points,
+        rating: get_rating(conn, review.id),
+        appeal: db::appeals::get_by_review(conn, review.id),
+        discarded: review.discarded,
+        self_assessment: review.selfassessment,
+        version: db::versions::get_number(conn, review.version),
     })
 }
 
 /// Get detailed review.
 /// If workshop is anonymous no names will be returned.
+/// Reviews are only available after the results of the workshop are released.
 pub fn get_full_review(conn: &MysqlConnection, review_id: u64) -> Result<FullReview, ()> {
-    let workshop = db::workshops::get_by_submission_id(conn, review_id);
-    let with_names = if workshop.is_ok() {
-        !workshop.unwrap().anonymous
-    } else {
-        false
-    };
-    get_full_review_internal(conn, review_id, with_names)
+    let workshop = db::workshops::get_by_review_id(conn, review_id);
+    if workshop.is_err() {
+        return Err(());
+    }
+    let workshop = workshop.unwrap();
+    if !workshop.results_released(Local::now().naive_local()) {
+        return Err(());
+    }
+    get_full_review_internal(conn, review_id, !workshop.anonymous, false)
 }
 
 /// Get detailed review with names.
@@ -602,7 +1637,7 @@ pub fn get_full_review_with_names(
     conn: &MysqlConnection,
     review_id: u64,
 ) -> Result<FullReview, ()> {
-    get_full_review_internal(conn, review_id, true)
+    get_full_review_internal(conn, review_id, true, true)
 }
 
 /// Get missing reviews with names
@@ -653,6 +1688,71 @@ pub fn is_reviewer(conn: &MysqlConnection, submission_id: u64, student_id: u64)
     }
 }
 
+/// Get the ids of the peer reviewers of a submission.
+pub fn get_reviewer_ids(conn: &MysqlConnection, submission_id: u64) -> Result<Vec<u64>, Error> {
+    reviews_t
+        .filter(
+            reviews_sub
+                .eq(submission_id)
+                .and(reviews_self.eq(false))
+                .and(reviews_teacher.eq(false)),
+        )
+        .select(reviewer)
+        .get_results::<Option<u64>>(conn)
+        .map(|reviewers| reviewers.into_iter().flatten().collect())
+}
+
+/// Check if a review of a submission was started.
+/// Reviews with feedback or points count as started, self-assessments are ignored.
+pub fn is_started(conn: &MysqlConnection, submission_id: u64) -> bool {
+    let reviews = reviews_t
+        .filter(reviews_sub.eq(submission_id).and(reviews_self.eq(false)))
+        .get_results::<Review>(conn);
+    if reviews.is_err() {
+        return true;
+    }
+    reviews.unwrap().into_iter().any(|review| {
+        review.done
+            || !review.feedback.is_empty()
+            || select(exists(reviewpoints_t.filter(rp_review.eq(review.id))))
+                .get_result(conn)
+                .unwrap_or(true)
+    })
+}
+
+/// Delete all reviews of a submission with their points.
+pub(crate) fn delete_by_submission(
+    conn: &MysqlConnection,
+    submission_id: u64,
+) -> Result<(), DbError> {
+    let review_ids = reviews_t
+        .filter(reviews_sub.eq(submission_id))
+        .select(reviews_id)
+        .get_results::<u64>(conn);
+    if review_ids.is_err() {
+        return Err(DbError::new(
+            DbErrorKind::ReadFailed,
+            format!("Reviews of Submission {} not found", submission_id),
+        ));
+    }
+    let delete =
+        diesel::delete(reviewpoints_t.filter(rp_review.eq_any(review_ids.unwrap()))).execute(conn);
+    if delete.is_err() {
+        return Err(DbError::new(
+            DbErrorKind::DeleteFailed,
+            "Review Points Delete failed",
+        ));
+    }
+    let delete = diesel::delete(reviews_t.filter(reviews_sub.eq(submission_id))).execute(conn);
+    if delete.is_err() {
+        return Err(DbError::new(
+            DbErrorKind::DeleteFailed,
+            "Review Delete failed",
+        ));
+    }
+    Ok(())
+}
+
 /// Check if student is reviewer of given review.
 pub fn is_owner(conn: &MysqlConnection, review_id: u64, student_id: u64) -> bool {
     let exists: Result<Review, diesel::result::Error> = reviews_t
@@ -670,25 +1770,13 @@ pub fn is_submission_owner(conn: &MysqlConnection, review_id: u64, student_id: u
     let review: Result<Review, diesel::result::Error> =
         reviews_t.filter(reviews_id.eq(review_id)).first(conn);
     if review.is_ok() {
-        let review = review.unwrap();
-        let submission = db::submissions::get_by_id(conn, review.submission);
-        if submission.is_ok() {
-            let submission = submission.unwrap();
-            if let Some(student) = submission.student {
-                student == student_id
-            } else {
-                false
-            }
-        } else {
-            false
-        }
+        db::submissions::is_owner(conn, review.unwrap().submission, student_id)
     } else {
         false
     }
 }
 
 /// Get review by review id.
-#[allow(dead_code)]
 pub fn get_by_id(conn: &MysqlConnection, review_id: u64) -> Result<Review, Error> {
     reviews_t.filter(reviews_id.eq(review_id)).first(conn)
 }
@@ -712,6 +1800,8 @@ pub fn get_student_workshop_reviews(
             u_firstname,
             u_lastname,
             ws_id,
+            sub_calibration,
+            reviews_self,
         ))
         .get_results::<(
             u64,
@@ -721,6 +1811,8 @@ pub fn get_student_workshop_reviews(
             String,
             String,
             u64,
+            bool,
+            bool,
         )>(conn);
 
     if raw_reviews.is_err() {
@@ -740,7 +1832,9 @@ pub fn get_student_workshop_reviews(
             WorkshopReview {
                 id: review.0,
                 done: review.1,
-                deadline: review.2,
+                calibration: review.7,
+                self_assessment: review.8,
+                deadline: db::extensions::get_review_extension(conn, review.0).unwrap_or(review.2),
                 title: review.3,
                 firstname,
                 lastname,
@@ -0,0 +1,174 @@
+//! Background processing of submissions past their deadline.
+//! Reviews are closed and points are calculated periodically,
+//! instead of when a student happens to view them.
+
+use crate::db;
+use crate::db::error::{DbError, DbErrorKind};
+use crate::db::submissions::calculate_points;
+use crate::schema::submissions::dsl::{
+    calibration as sub_calibration, deadline as sub_deadline, id as sub_id,
+    reviewsdone as sub_reviews_done, submissions as submissions_t,
+};
+use crate::utils::error::AppError;
+use crate::utils::queue;
+use chrono::Local;
+use diesel::prelude::*;
+use rocket::logger::error;
+use rocket::Rocket;
+use rocket_contrib::databases::database_config;
+use std::sync::{Arc, Mutex};
+
+/// Status of the last scheduler run.
+#[derive(Clone, Serialize)]
+pub struct SchedulerStatus {
+    pub enabled: bool,
+    pub interval: u64,
+    #[serde(rename = "lastRun")]
+    pub last_run: Option<chrono::NaiveDateTime>,
+    #[serde(rename = "lastSuccess")]
+    pub last_success: Option<chrono::NaiveDateTime>,
+    pub processed: usize,
+    pub failed: Vec<u64>,
+    pub reminders: usize,
+    pub error: Option<String>,
+}
+
+/// Shared status of the scheduler, managed by Rocket.
+pub struct Scheduler(Arc<Mutex<SchedulerStatus>>);
+
+impl Scheduler {
+    /// Get a copy of the current status.
+    pub fn status(&self) -> SchedulerStatus {
+        match self.0.lock() {
+            Ok(status) => status.clone(),
+            Err(poisoned) => poisoned.into_inner().clone(),
+        }
+    }
+}
+
+/// Result of processing all due submissions once.
+pub struct SchedulerRun {
+    pub processed: usize,
+    pub failed: Vec<u64>,
+    pub reminders: usize,
+}
+
+/// Close reviews & calculate points of all submissions past their deadline.
+/// Afterwards reminders for reviews that end soon are queued.
+/// Every submission is processed in its own transaction with its row locked,
+/// so multiple instances never grade the same submission twice.
+pub fn process_due_submissions(conn: &MysqlConnection) -> Result<SchedulerRun, DbError> {
+    let now = Local::now().naive_local();
+    let due_submissions = submissions_t
+        .filter(
+            sub_reviews_done
+                .eq(false)
+                .and(sub_deadline.lt(now))
+                .and(sub_calibration.eq(false)),
+        )
+        .select(sub_id)
+        .get_results::<u64>(conn);
+    if due_submissions.is_err() {
+        return Err(DbError::new(
+            DbErrorKind::ReadFailed,
+            "Could not query due Submissions",
+        ));
+    }
+
+    let mut run = SchedulerRun {
+        processed: 0,
+        failed: Vec::new(),
+        reminders: 0,
+    };
+    for submission_id in due_submissions.unwrap() {
+        // Submissions with running review extensions stay open
+        match calculate_points(conn, submission_id) {
+            Ok(true) => run.processed += 1,
+            Ok(false) => {}
+            Err(err) => {
+                err.print_stacktrace();
+                run.failed.push(submission_id);
+            }
+        }
+    }
+
+    // Remind reviewers of reviews that end soon
+    match db::emails::queue_deadline_reminders(conn) {
+        Ok(reminders) => run.reminders = reminders,
+        Err(err) => err.print_stacktrace(),
+    }
+    Ok(run)
+}
+
+// Store the outcome of a scheduler run.
+// Returns the number of processed submissions or the error of the run.
+fn record(
+    status: &Mutex<SchedulerStatus>,
+    result: Result<SchedulerRun, DbError>,
+) -> Result<usize, DbError> {
+    let now = Local::now().naive_local();
+    let mut status = match status.lock() {
+        Ok(status) => status,
+        Err(poisoned) => poisoned.into_inner(),
+    };
+    status.last_run = Some(now);
+    match result {
+        Ok(run) => {
+            status.last_success = Some(now);
+            status.processed = run.processed;
+            status.failed = run.failed;
+            status.reminders = run.reminders;
+            status.error = None;
+            Ok(run.processed)
+        }
+        Err(err) => {
+            status.processed = 0;
+            status.failed = Vec::new();
+            status.reminders = 0;
+            status.error = Some(err.description());
+            Err(err)
+        }
+    }
+}
+
+/// Setup scheduler from `Rocket.toml` configuration file.
+/// Needs to be attached after the database migration.
+pub fn setup_scheduler(rocket: Rocket) -> Result<Rocket, Rocket> {
+    let enabled = rocket
+        .config()
+        .get_bool("scheduler_enabled")
+        .unwrap_or(true);
+    let interval = rocket
+        .config()
+        .get_int("scheduler_interval_seconds")
+        .unwrap_or(60)
+        .max(1) as u64;
+    let status = Arc::new(Mutex::new(SchedulerStatus {
+        enabled,
+        interval,
+        last_run: None,
+        last_success: None,
+        processed: 0,
+        failed: Vec::new(),
+        reminders: 0,
+        error: None,
+    }));
+
+    if enabled {
+        let url = match database_config("iprp_db", rocket.config()) {
+            Ok(config) => config.url.to_string(),
+            Err(e) => {
+                error(&format!(
+                    "Failed to read scheduler database config: {:?}",
+                    e
+                ));
+                return Err(rocket);
+            }
+        };
+        let thread_status = Arc::clone(&status);
+        queue::spawn("Scheduler", url, interval, move |conn| {
+            record(&thread_status, process_due_submissions(conn))
+        });
+    }
+    Ok(rocket.manage(Scheduler(status)))
+}
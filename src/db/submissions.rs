@@ -3,6 +3,10 @@
 use crate::db;
 use crate::db::error::{DbError, DbErrorKind};
 use crate::db::models::*;
+use crate::events::{self, Event, EventKind};
+use crate::grading;
+use crate::grading::aggregation::{self, WeightedScore};
+use crate::grading::late;
 use crate::schema::criterion::dsl::{criterion as criterion_t, id as c_id};
 use crate::schema::submissionattachments::dsl::{
     submission as subatt_sub, submissionattachments as subatt_t,
@@ -11,12 +15,17 @@ use crate::schema::submissioncriteria::dsl::{
     criterion as subcrit_crit, submission as subcrit_sub, submissioncriteria as subcrit_t,
 };
 use crate::schema::submissions::dsl::{
-    deadline as sub_deadline, id as sub_id, locked as sub_locked, reviewsdone as sub_reviews_done,
-    student as sub_student, submissions as submissions_t, workshop as sub_workshop,
+    calibration as sub_calibration, deadline as sub_deadline, id as sub_id,
+    justification as sub_justification, locked as sub_locked, maxpoint as sub_maxpoint,
+    meanpoints as sub_meanpoints, previous as sub_previous, reviewsdone as sub_reviews_done,
+    student as sub_student, submissions as submissions_t, team as sub_team,
+    workshop as sub_workshop,
 };
+use crate::utils::error::AppError;
 use chrono::Local;
 use diesel::prelude::*;
 use diesel::result::Error;
+use std::collections::HashMap;
 use std::ops::Add;
 
 /// Create a new submission for a workshop.
@@ -37,13 +46,36 @@ pub fn create<'a>(
         ));
     }
 
+    let workshop = db::workshops::get_by_id(conn, workshop_id);
+    if workshop.is_err() {
+        return Err(DbError::new(
+            DbErrorKind::NotFound,
+            format!("Workshop {} not found", workshop_id),
+        ));
+    }
+    let workshop = workshop.unwrap();
+    let extension = db::extensions::get_submission_extension(conn, workshop_id, student_id);
+    let late = db::workshops::check_submission_phase(&workshop, extension, date);
+    if let Err(err) = late {
+        return Err(err);
+    }
+    let late = late.unwrap();
+
     // Calculate deadline with review timespan from workshop
+    // An explicit review phase ends all reviews at once,
+    // submissions after the review phase get the full review timespan
     let review_timespan = crate::db::workshops::get_review_timespan(conn, workshop_id);
     if let Err(err) = review_timespan {
         return Err(err);
     }
     let review_timespan = review_timespan.unwrap();
-    let deadline = date.add(review_timespan);
+    let deadline = match workshop.reviewend {
+        Some(review_end) if review_end > date => review_end,
+        _ => date.add(review_timespan),
+    };
+
+    // Submissions of team members belong to the whole team
+    let team = db::teams::get_student_team(conn, workshop_id, student_id).map(|team| team.id);
 
     let new_submission = NewSubmission {
         title,
@@ -55,13 +87,124 @@ pub fn create<'a>(
         locked: false,
         reviewsdone: false,
         error: false,
+        calibration: false,
+        late,
+        team,
+        round: 1,
+        previous: None,
+    };
+    insert(conn, &new_submission, attachments, false)
+}
+
+/// Resubmit a revised submission after its reviews are done.
+/// The resubmission starts a new review round, which is graded separately.
+pub fn resubmit(
+    conn: &MysqlConnection,
+    submission_id: u64,
+    student_id: u64,
+    title: String,
+    comment: String,
+    attachments: Vec<u64>,
+    date: chrono::NaiveDateTime,
+) -> Result<Submission, DbError> {
+    if !is_owner(conn, submission_id, student_id) {
+        return Err(DbError::new(
+            DbErrorKind::NotFound,
+            "Submission does not exist and/or Student is not Owner of Submission",
+        ));
+    }
+    let previous = get_by_id(conn, submission_id);
+    if previous.is_err() {
+        return Err(DbError::new(
+            DbErrorKind::NotFound,
+            format!("Submission {} not found", submission_id),
+        ));
+    }
+    let previous = previous.unwrap();
+    if !previous.reviewsdone {
+        return Err(DbError::new(
+            DbErrorKind::NotFinished,
+            format!("Reviews of Submission {} are not done yet", submission_id),
+        ));
+    }
+    let workshop = db::workshops::get_by_id(conn, previous.workshop);
+    if workshop.is_err() {
+        return Err(DbError::new(
+            DbErrorKind::NotFound,
+            format!("Workshop {} not found", previous.workshop),
+        ));
+    }
+    let workshop = workshop.unwrap();
+    if previous.round >= workshop.rounds {
+        return Err(DbError::new(
+            DbErrorKind::Mismatch,
+            format!("Submission {} is already in the last round", submission_id),
+        ));
+    }
+    if date > workshop.end {
+        return Err(DbError::new(
+            DbErrorKind::PastDeadline,
+            "Resubmissions cannot be created after the Workshop end",
+        ));
+    }
+    let resubmitted: Result<Submission, _> = submissions_t
+        .filter(sub_previous.eq(submission_id))
+        .first(conn);
+    if resubmitted.is_ok() {
+        return Err(DbError::new(
+            DbErrorKind::Mismatch,
+            format!("Submission {} was already resubmitted", submission_id),
+        ));
+    }
+
+    // Review deadline is calculated like for the first round
+    let review_timespan = crate::db::workshops::get_review_timespan(conn, workshop.id);
+    if let Err(err) = review_timespan {
+        return Err(err);
+    }
+    let review_timespan = review_timespan.unwrap();
+    let deadline = match workshop.reviewend {
+        Some(review_end) if review_end > date => review_end,
+        _ => date.add(review_timespan),
     };
 
+    let new_submission = NewSubmission {
+        title,
+        comment,
+        student: student_id,
+        workshop: workshop.id,
+        date,
+        deadline,
+        locked: false,
+        reviewsdone: false,
+        error: false,
+        calibration: false,
+        late: false,
+        team: previous.team,
+        round: previous.round + 1,
+        previous: Some(previous.id),
+    };
+    insert(conn, &new_submission, attachments, workshop.samereviewers)
+}
+
+// Insert a submission with its attachments, criteria, first version & reviews.
+// Resubmissions can be reviewed by the reviewers of the previous round.
+fn insert(
+    conn: &MysqlConnection,
+    new_submission: &NewSubmission,
+    attachments: Vec<u64>,
+    same_reviewers: bool,
+) -> Result<Submission, DbError> {
+    let student_id = new_submission.student;
+    let workshop_id = new_submission.workshop;
+    let date = new_submission.date;
+    let deadline = new_submission.deadline;
+
     let mut t_error: Result<(), DbError> = Ok(());
     let submission = conn.transaction::<Submission, Error, _>(|| {
         // Insert submission
         let submission_insert = diesel::insert_into(submissions_t)
-            .values(&new_submission)
+            .values(new_submission)
             .execute(conn);
         if submission_insert.is_err() {
             return DbError::assign_and_rollback(
@@ -72,65 +215,32 @@ pub fn create<'a>(
         let submission: Submission = submissions_t.order(sub_id.desc()).first(conn).unwrap();
 
         // Relate attachments to submission
-        let all_student_attachments = db::attachments::get_ids_by_user_id(conn, student_id);
-        if all_student_attachments.is_err() {
-            return DbError::assign_and_rollback(
-                &mut t_error,
-                DbError::new(DbErrorKind::ReadFailed, "Student attachments not found"),
-            );
-        }
-        let all_student_attachments = all_student_attachments.unwrap();
-        let submission_attachments: Vec<Submissionattachment> = attachments
-            .into_iter()
-            .filter_map(|att_id| {
-                if all_student_attachments.contains(&att_id) {
-                    Some(Submissionattachment {
-                        submission: submission.id,
-                        attachment: att_id,
-                    })
-                } else {
-                    None
-                }
-            })
-            .collect();
-        let attachment_insert = diesel::insert_into(subatt_t)
-            .values(&submission_attachments)
-            .execute(conn);
-        if attachment_insert.is_err() {
-            return DbError::assign_and_rollback(
-                &mut t_error,
-                DbError::new(DbErrorKind::CreateFailed, "Attachment Insert failed"),
-            );
+        if let Err(err) = relate_attachments(conn, submission.id, &[student_id], attachments) {
+            return DbError::assign_and_rollback(&mut t_error, err);
         }
 
         // Relate criteria to submission
-        let workshop_criteria = db::workshops::get_criteria(conn, workshop_id);
-        if workshop_criteria.is_err() {
-            return DbError::assign_and_rollback(
-                &mut t_error,
-                DbError::new(DbErrorKind::ReadFailed, "Workshop Criteria not found"),
-            );
+        if let Err(err) = relate_criteria(conn, submission.id, workshop_id) {
+            return DbError::assign_and_rollback(&mut t_error, err);
         }
-        let workshop_criteria = workshop_criteria.unwrap();
-        let submission_criteria: Vec<Submissioncriteria> = workshop_criteria
-            .into_iter()
-            .map(|criterion| Submissioncriteria {
-                submission: submission.id,
-                criterion,
-            })
-            .collect();
-        let criteria_insert = diesel::insert_into(subcrit_t)
-            .values(&submission_criteria)
-            .execute(conn);
-        if criteria_insert.is_err() {
-            return DbError::assign_and_rollback(
-                &mut t_error,
-                DbError::new(DbErrorKind::CreateFailed, "Criteria Insert failed"),
-            );
+
+        // First version of the submission
+        if let Err(err) = db::versions::add(conn, submission.id, date) {
+            return DbError::assign_and_rollback(&mut t_error, err);
         }
 
         // Assign reviews
-        let assign = db::reviews::assign(conn, submission.id, student_id, workshop_id, deadline);
+        let assign = match submission.previous {
+            Some(previous_id) if same_reviewers => db::reviews::assign_previous_reviewers(
+                conn,
+                submission.id,
+                previous_id,
+                student_id,
+                workshop_id,
+                deadline,
+            ),
+            _ => db::reviews::assign(conn, submission.id, student_id, workshop_id, deadline),
+        };
         if assign.is_err() {
             return DbError::assign_and_rollback(
                 &mut t_error,
@@ -145,7 +255,23 @@ pub fn create<'a>(
     });
 
     match submission {
-        Ok(submission) => Ok(submission),
+        Ok(submission) => {
+            if let Ok(reviewers) = db::reviews::get_reviewer_ids(conn, submission.id) {
+                events::publish(
+                    &reviewers,
+                    Event {
+                        kind: EventKind::ReviewAssigned,
+                        workshop: Some(submission.workshop),
+                        submission: Some(submission.id),
+                        review: None,
+                    },
+                );
+            }
+            if let Err(err) = db::webhooks::submission_created(conn, &submission) {
+                err.print_stacktrace();
+            }
+            Ok(submission)
+        }
         Err(_) => Err(t_error.err().unwrap_or(DbError::new(
             DbErrorKind::TransactionFailed,
             "Unknown error",
@@ -153,28 +279,108 @@ pub fn create<'a>(
     }
 }
 
+/// Relate attachments of the owners to a submission.
+/// Attachments of other users are ignored.
+pub(crate) fn relate_attachments(
+    conn: &MysqlConnection,
+    submission_id: u64,
+    owner_ids: &[u64],
+    attachments: Vec<u64>,
+) -> Result<(), DbError> {
+    let mut all_owner_attachments: Vec<u64> = Vec::new();
+    for owner_id in owner_ids {
+        let owner_attachments = db::attachments::get_ids_by_user_id(conn, *owner_id);
+        if owner_attachments.is_err() {
+            return Err(DbError::new(
+                DbErrorKind::ReadFailed,
+                "Student attachments not found",
+            ));
+        }
+        all_owner_attachments.extend(owner_attachments.unwrap());
+    }
+    let submission_attachments: Vec<Submissionattachment> = attachments
+        .into_iter()
+        .filter_map(|att_id| {
+            if all_owner_attachments.contains(&att_id) {
+                Some(Submissionattachment {
+                    submission: submission_id,
+                    attachment: att_id,
+                })
+            } else {
+                None
+            }
+        })
+        .collect();
+    let attachment_insert = diesel::insert_into(subatt_t)
+        .values(&submission_attachments)
+        .execute(conn);
+    if attachment_insert.is_err() {
+        return Err(DbError::new(
+            DbErrorKind::CreateFailed,
+            "Attachment Insert failed",
+        ));
+    }
+    Ok(())
+}
+
+/// Relate the current criteria of a workshop to a submission.
+pub(crate) fn relate_criteria(
+    conn: &MysqlConnection,
+    submission_id: u64,
+    workshop_id: u64,
+) -> Result<(), DbError> {
+    let workshop_criteria = db::workshops::get_criteria(conn, workshop_id);
+    if workshop_criteria.is_err() {
+        return Err(DbError::new(
+            DbErrorKind::ReadFailed,
+            "Workshop Criteria not found",
+        ));
+    }
+    let submission_criteria: Vec<Submissioncriteria> = workshop_criteria
+        .unwrap()
+        .into_iter()
+        .map(|criterion| Submissioncriteria {
+            submission: submission_id,
+            criterion,
+        })
+        .collect();
+    let criteria_insert = diesel::insert_into(subcrit_t)
+        .values(&submission_criteria)
+        .execute(conn);
+    if criteria_insert.is_err() {
+        return Err(DbError::new(
+            DbErrorKind::CreateFailed,
+            "Criteria Insert failed",
+        ));
+    }
+    Ok(())
+}
+
 /// Check if student is owner of submission.
+/// Team submissions are owned by every team member.
 pub fn is_owner(conn: &MysqlConnection, submission_id: u64, student_id: u64) -> bool {
-    let exists: Result<Submission, diesel::result::Error> = submissions_t
-        .filter(sub_id.eq(submission_id).and(sub_student.eq(student_id)))
-        .first(conn);
-    if exists.is_ok() {
+    let submission = get_by_id(conn, submission_id);
+    if submission.is_err() {
+        return false;
+    }
+    let submission = submission.unwrap();
+    if submission.student == Some(student_id) {
         true
+    } else if let Some(team) = submission.team {
+        db::teams::is_member(conn, team, student_id)
     } else {
         false
     }
 }
 
 // Get detailed submission from submission id.
+// Without a student the representation is adapted for teachers.
 fn get_full_submission(
     conn: &MysqlConnection,
     submission_id: u64,
-    is_teacher: bool,
+    student_id: Option<u64>,
 ) -> Result<OwnSubmission, DbError> {
-    let points_calculation = calculate_points(conn, submission_id);
-    if let Err(err) = points_calculation {
-        return Err(err);
-    }
+    let is_teacher = student_id.is_none();
     let attachments = db::attachments::get_by_submission_id(conn, submission_id);
     if attachments.is_err() {
         return Err(DbError::new(
@@ -191,7 +397,22 @@ fn get_full_submission(
             format!("Submission {} not found", submission_id),
         ));
     }
-    let submission = submission.unwrap();
+    let mut submission = submission.unwrap();
+    let criteria = get_criteria(conn, submission_id);
+    if criteria.is_err() {
+        return Err(DbError::new(
+            DbErrorKind::ReadFailed,
+            format!("Criteria for Submission {} not found", submission_id),
+        ));
+    }
+    let criteria = db::criteria::get_full(conn, criteria.unwrap());
+    if criteria.is_err() {
+        return Err(DbError::new(
+            DbErrorKind::ReadFailed,
+            format!("Rubric Levels for Submission {} not found", submission_id),
+        ));
+    }
+    let criteria = criteria.unwrap();
 
     let (firstname, lastname) = if let Some(student) = submission.student {
         if let Ok(student) = db::users::get_by_id(conn, student) {
@@ -203,13 +424,32 @@ fn get_full_submission(
         (Some(String::from("Not Found")), Some(String::from("")))
     };
 
-    let no_reviews = if submission.meanpoints.is_none() {
+    let workshop = db::workshops::get_by_id(conn, submission.workshop);
+    if workshop.is_err() {
+        return Err(DbError::new(
+            DbErrorKind::NotFound,
+            format!("Workshop for Submission {} not found", submission_id),
+        ));
+    }
+    // Students see points & reviews only after the results are released
+    let results_released = is_teacher
+        || workshop
+            .unwrap()
+            .results_released(Local::now().naive_local());
+    if !results_released {
+        submission.meanpoints = None;
+        submission.maxpoint = None;
+        submission.computedpoints = None;
+        submission.justification = None;
+    }
+
+    let no_reviews = if submission.meanpoints.is_none() && results_released {
         true
     } else {
         false
     };
 
-    let reviews = if submission.reviewsdone {
+    let reviews = if submission.reviewsdone && results_released {
         if is_teacher {
             if let Ok(reviews) = db::reviews::get_full_reviews_with_names(conn, submission_id) {
                 reviews
@@ -241,16 +481,54 @@ fn get_full_submission(
         None
     };
 
+    let team = match submission.team {
+        Some(team) => match db::teams::get_by_id(conn, team) {
+            Ok(team) => match db::teams::get_workshop_team(conn, team) {
+                Ok(team) => Some(team),
+                Err(err) => return Err(err),
+            },
+            Err(_) => None,
+        },
+        None => None,
+    };
+
+    // Team members get their own reviewing component
+    let final_points = get_final_points(conn, &submission, student_id.or(submission.student));
+    let round_points = if results_released {
+        get_round_points(conn, &submission)
+    } else {
+        None
+    };
+
+    let version = db::versions::get_latest(conn, submission_id);
+    if version.is_err() {
+        return Err(DbError::new(
+            DbErrorKind::NotFound,
+            format!("No Version of Submission {} found", submission_id),
+        ));
+    }
+
     Ok(OwnSubmission {
         title: submission.title,
         comment: submission.comment,
         attachments,
+        criteria,
+        version: version.unwrap().version,
+        team,
         locked: submission.locked,
         date: submission.date,
+        late: submission.late,
+        round: submission.round,
+        previous: submission.previous,
+        round_points,
         reviews_done: submission.reviewsdone,
         no_reviews,
+        results_released,
         points: submission.meanpoints,
         max_points: submission.maxpoint,
+        computed_points: submission.computedpoints,
+        final_points,
+        justification: submission.justification,
         firstname,
         lastname,
         reviews,
@@ -259,11 +537,13 @@ fn get_full_submission(
 }
 
 /// Get detailed submission from submission id.
+/// Representation is adapted for the submission owner or a member of the team.
 pub fn get_own_submission(
     conn: &MysqlConnection,
     submission_id: u64,
+    student_id: u64,
 ) -> Result<OwnSubmission, DbError> {
-    get_full_submission(conn, submission_id, false)
+    get_full_submission(conn, submission_id, Some(student_id))
 }
 
 /// Get detailed submission from submission id.
@@ -271,36 +551,26 @@ pub fn get_teacher_submission(
     conn: &MysqlConnection,
     submission_id: u64,
 ) -> Result<OwnSubmission, DbError> {
-    get_full_submission(conn, submission_id, true)
+    get_full_submission(conn, submission_id, None)
 }
 
 /// Get simplified submission from submission id.
-/// Also locks submission so that submission owner can no longer update it.
+/// The review of the reviewer is pinned to the latest version,
+/// later updates of the submission owner are not shown to the reviewer.
 pub fn get_student_submission(
     conn: &MysqlConnection,
     submission_id: u64,
-    _user_id: u64,
+    user_id: u64,
 ) -> Result<OtherSubmission, DbError> {
-    let points_calculation = calculate_points(conn, submission_id);
-    if let Err(err) = points_calculation {
+    let version = db::versions::pin(conn, submission_id, user_id);
+    if let Err(err) = version {
         return Err(err);
     }
-    let attachments =
-        db::attachments::get_by_submission_id_and_lock_submission(conn, submission_id);
-    if let Err(err) = attachments {
+    let version = db::versions::get_full_version(conn, version.unwrap());
+    if let Err(err) = version {
         return Err(err);
     }
-    let attachments = attachments.ok().unwrap();
-
-    let submission: Result<Submission, _> =
-        submissions_t.filter(sub_id.eq(submission_id)).first(conn);
-    if submission.is_err() {
-        return Err(DbError::new(
-            DbErrorKind::ReadFailed,
-            format!("Submission {} not found", submission_id),
-        ));
-    }
-    let submission = submission.unwrap();
+    let version = version.unwrap();
 
     let submission_criteria: Result<Vec<u64>, _> = subcrit_t
         .filter(subcrit_sub.eq(submission_id))
@@ -323,13 +593,21 @@ pub fn get_student_submission(
             format!("Criteria Data for Submission {} not found", submission_id),
         ));
     }
+    let submission_criteria = db::criteria::get_full(conn, submission_criteria.unwrap());
+    if submission_criteria.is_err() {
+        return Err(DbError::new(
+            DbErrorKind::ReadFailed,
+            format!("Rubric Levels for Submission {} not found", submission_id),
+        ));
+    }
     let submission_criteria = submission_criteria.unwrap();
 
     Ok(OtherSubmission {
-        title: submission.title,
-        comment: submission.comment,
-        attachments,
+        title: version.title,
+        comment: version.comment,
+        attachments: version.attachments,
         criteria: submission_criteria,
+        version: version.version,
     })
 }
 
@@ -340,26 +618,26 @@ fn get_workshop_submissions_internal(
     student_id: u64,
     is_teacher: bool,
 ) -> Result<Vec<WorkshopSubmission>, ()> {
-    let submissions: Result<Vec<u64>, _> = submissions_t
-        .filter(sub_workshop.eq(workshop_id).and(sub_student.eq(student_id)))
-        .select(sub_id)
+    // Submissions of the team are part of every team member's submissions
+    let team = db::teams::get_student_team(conn, workshop_id, student_id).map(|team| team.id);
+    let submissions: Result<Vec<Submission>, _> = submissions_t
+        .filter(
+            sub_workshop
+                .eq(workshop_id)
+                .and(sub_student.eq(student_id).or(sub_team.eq(team))),
+        )
         .get_results(conn);
     if submissions.is_err() {
         return Err(());
     }
     let submissions = submissions.unwrap();
-    for submission_id in submissions {
-        if calculate_points(conn, submission_id).is_err() {
-            return Err(());
-        }
-    }
-    let submissions: Result<Vec<Submission>, _> = submissions_t
-        .filter(sub_workshop.eq(workshop_id).and(sub_student.eq(student_id)))
-        .get_results(conn);
-    if submissions.is_err() {
+    let workshop = db::workshops::get_by_id(conn, workshop_id);
+    if workshop.is_err() {
         return Err(());
     }
-    let submissions = submissions.unwrap();
+    let results_released = workshop
+        .unwrap()
+        .results_released(Local::now().naive_local());
     let submissions: Vec<WorkshopSubmission> = if is_teacher {
         submissions
             .into_iter()
@@ -369,38 +647,66 @@ fn get_workshop_submissions_internal(
                 } else {
                     false
                 };
+                let final_points = get_final_points(conn, &submission, Some(student_id));
+                let round_points = get_round_points(conn, &submission);
                 WorkshopSubmission {
                     id: submission.id,
                     title: submission.title,
                     date: submission.date,
+                    late: submission.late,
+                    team: submission.team,
+                    round: submission.round,
+                    round_points,
                     locked: None,
                     student_id: Some(student_id),
                     reviews_done: submission.reviewsdone,
                     no_reviews,
+                    results_released: true,
                     points: submission.meanpoints,
                     max_points: submission.maxpoint,
+                    computed_points: submission.computedpoints,
+                    final_points,
                 }
             })
             .collect()
     } else {
         submissions
             .into_iter()
-            .map(|submission| {
-                let no_reviews = if submission.meanpoints.is_none() {
+            .map(|mut submission| {
+                // Points are hidden until the results are released
+                if !results_released {
+                    submission.meanpoints = None;
+                    submission.maxpoint = None;
+                    submission.computedpoints = None;
+                }
+                let no_reviews = if submission.meanpoints.is_none() && results_released {
                     true
                 } else {
                     false
                 };
+                let final_points = get_final_points(conn, &submission, Some(student_id));
+                let round_points = if results_released {
+                    get_round_points(conn, &submission)
+                } else {
+                    None
+                };
                 WorkshopSubmission {
                     id: submission.id,
                     title: submission.title,
                     date: submission.date,
+                    late: submission.late,
+                    team: submission.team,
+                    round: submission.round,
+                    round_points,
                     locked: Some(submission.locked),
                     student_id: None,
                     reviews_done: submission.reviewsdone,
                     no_reviews,
+                    results_released,
                     points: submission.meanpoints,
                     max_points: submission.maxpoint,
+                    computed_points: submission.computedpoints,
+                    final_points,
                 }
             })
             .collect()
@@ -428,63 +734,175 @@ pub fn get_student_workshop_submissions(
     get_workshop_submissions_internal(conn, workshop_id, student_id, false)
 }
 
-/// Calculate points of a submission.
+/// Calculate points of a submission past its deadline.
 /// Also closes all pending reviews.
-pub(crate) fn calculate_points(conn: &MysqlConnection, submission_id: u64) -> Result<(), DbError> {
-    // let submission = submissions_t
-    //     .filter(
-    //         sub_id.eq(submission_id).and(
-    //             sub_reviews_done
-    //                 .eq(true)
-    //                 .and(sub_meanpoints.is_null().and(sub_error.eq(false))),
-    //         ),
-    //     )
-    //     .first(conn);
-
-    // Get submission past deadline with no calculated points
-    let now = Local::now().naive_local();
-    let submission = submissions_t
-        .filter(
-            sub_id
-                .eq(submission_id)
-                .and(sub_reviews_done.eq(false).and(sub_deadline.lt(now))),
-        )
-        .first(conn);
-    if submission.is_err() {
-        // Submission points are already calculated or not finished yet
-        return Ok(());
-    }
+/// The submission row is locked while grading, so concurrent calls never grade it twice.
+/// Returns whether the submission was graded by this call.
+pub(crate) fn calculate_points(
+    conn: &MysqlConnection,
+    submission_id: u64,
+) -> Result<bool, DbError> {
+    let mut t_error: Result<(), DbError> = Ok(());
+    let transaction = conn.transaction::<Option<Submission>, Error, _>(|| {
+        // Lock submission past deadline with no calculated points,
+        // concurrent calls wait until it is processed
+        let now = Local::now().naive_local();
+        let submission = submissions_t
+            .filter(
+                sub_id
+                    .eq(submission_id)
+                    .and(sub_reviews_done.eq(false).and(sub_deadline.lt(now)))
+                    .and(sub_calibration.eq(false)),
+            )
+            .for_update()
+            .first::<Submission>(conn);
+        let mut submission = match submission {
+            Ok(submission) => submission,
+            // Submission points are already calculated or not finished yet
+            Err(Error::NotFound) => return Ok(None),
+            Err(_) => {
+                return DbError::assign_and_rollback(
+                    &mut t_error,
+                    DbError::new(
+                        DbErrorKind::ReadFailed,
+                        format!("Could not lock Submission {}", submission_id),
+                    ),
+                )
+            }
+        };
+        // Extended reviews can still be done after the submission deadline
+        if let Some(end) = db::extensions::get_latest_review_extension(conn, submission_id) {
+            if end > now {
+                return Ok(None);
+            }
+        }
 
-    // Submission was not processed yet
-    // --------------------------------
-    let mut submission: Submission = submission.unwrap();
-    // If not already locked, lock it now
-    if !submission.locked {
-        let lock = db::submissions::lock(conn, submission_id);
-        if lock.is_err() {
-            return Err(DbError::new(
-                DbErrorKind::UpdateFailed,
-                "Submission Lock failed",
-            ));
+        // If not already locked, lock it now
+        if !submission.locked {
+            let lock = db::submissions::lock(conn, submission_id);
+            if lock.is_err() {
+                return DbError::assign_and_rollback(
+                    &mut t_error,
+                    DbError::new(DbErrorKind::UpdateFailed, "Submission Lock failed"),
+                );
+            }
+            submission.locked = true;
+        }
+
+        // Close all reviews
+        let close = db::reviews::close_reviews(conn, submission_id);
+        if close.is_err() {
+            return DbError::assign_and_rollback(
+                &mut t_error,
+                DbError::new(DbErrorKind::UpdateFailed, "Review Close failed"),
+            );
+        }
+        if let Err(err) = update_points(conn, submission.clone()) {
+            return DbError::assign_and_rollback(&mut t_error, err);
         }
-        submission.locked = true;
+        if let Err(err) = db::webhooks::submission_graded(conn, submission_id) {
+            err.print_stacktrace();
+        }
+        if let Err(err) = db::notifications::notify_submission_owners(
+            conn,
+            &submission,
+            NotificationKind::Graded,
+            "Reviews done".to_string(),
+            format!("All reviews of \"{}\" are done", submission.title),
+        ) {
+            err.print_stacktrace();
+        }
+        Ok(Some(submission))
+    });
+    match transaction {
+        // Events are only published for committed grades
+        Ok(Some(submission)) => {
+            if let Ok(owners) = get_owner_ids(conn, &submission) {
+                events::publish(
+                    &owners,
+                    Event {
+                        kind: EventKind::SubmissionGraded,
+                        workshop: Some(submission.workshop),
+                        submission: Some(submission.id),
+                        review: None,
+                    },
+                );
+            }
+            Ok(true)
+        }
+        Ok(None) => Ok(false),
+        Err(_) => Err(t_error.err().unwrap_or(DbError::new(
+            DbErrorKind::TransactionFailed,
+            "Unknown error",
+        ))),
     }
+}
 
-    // Close all reviews
-    // -----------------
-    let close = db::reviews::close_reviews(conn, submission_id);
-    if close.is_err() {
+/// Calculate points of an already graded submission again.
+/// Needed when reviews change after grading, like on resolved appeals.
+pub(crate) fn recalculate_points(
+    conn: &MysqlConnection,
+    submission_id: u64,
+) -> Result<(), DbError> {
+    let submission = get_by_id(conn, submission_id);
+    if submission.is_err() {
         return Err(DbError::new(
-            DbErrorKind::UpdateFailed,
-            "Review Close failed",
+            DbErrorKind::NotFound,
+            format!("Submission {} not found", submission_id),
         ));
     }
+    let submission = submission.unwrap();
+    if !submission.reviewsdone {
+        // Points are calculated when the submission is finished
+        return calculate_points(conn, submission_id).map(|_| ());
+    }
+    if let Err(err) = update_points(conn, submission) {
+        return Err(err);
+    }
+    if let Err(err) = db::webhooks::submission_graded(conn, submission_id) {
+        err.print_stacktrace();
+    }
+    Ok(())
+}
+
+// Aggregate the points of all valid reviews and save them to the submission.
+// Overridden points are kept, only the computed points change.
+fn update_points(conn: &MysqlConnection, mut submission: Submission) -> Result<(), DbError> {
+    let submission_id = submission.id;
+    let overridden = submission.justification.is_some();
     // Get all reviews without errors
     let reviews = db::reviews::get_simple_review_points(conn, submission_id);
     if let Err(err) = reviews {
         return Err(err);
     }
     let reviews = reviews.unwrap();
+    // Get grading settings of workshop
+    let workshop = db::workshops::get_by_submission_id(conn, submission_id);
+    if workshop.is_err() {
+        return Err(DbError::new(
+            DbErrorKind::ReadFailed,
+            format!("Workshop for Submission {} not found", submission_id),
+        ));
+    }
+    let workshop = workshop.unwrap();
+    let teacher_weight = workshop.teacherweight;
+    // Reliability of reviewers is only needed for weighted aggregation
+    let reliabilities = if workshop.aggregation == Aggregation::Weighted {
+        let reliabilities =
+            db::reviews::get_reviewer_reliabilities(conn, workshop.id, &workshop.gradescale);
+        if let Err(err) = reliabilities {
+            return Err(err);
+        }
+        reliabilities.unwrap()
+    } else {
+        HashMap::new()
+    };
+    // Accuracy of reviewers from calibration reviews
+    let accuracies = db::calibration::get_accuracies(conn, workshop.id, &workshop.gradescale);
+    if let Err(err) = accuracies {
+        return Err(err);
+    }
+    let accuracies = accuracies.unwrap();
 
     // Calculate points
     // ----------------
@@ -495,6 +913,11 @@ pub(crate) fn calculate_points(conn: &MysqlConnection, submission_id: u64) -> Re
             // Save error state
             submission.reviewsdone = true;
             submission.error = true;
+            submission.computedpoints = None;
+            if !overridden {
+                submission.meanpoints = None;
+                submission.maxpoint = None;
+            }
             let update = diesel::update(submissions_t.filter(sub_id.eq(submission.id)))
                 .set(&submission)
                 .execute(conn);
@@ -508,44 +931,45 @@ pub(crate) fn calculate_points(conn: &MysqlConnection, submission_id: u64) -> Re
                 );
             }
         } else {
-            // Calculate mean points and max points (based on criterion and weights)
-            // Max points
-            let point_range = 10.0;
-            let mut max_points = 0.0;
-            for points in reviews.first() {
-                for point in points {
-                    max_points += point_range * point.weight;
-                }
-            }
-            // Mean points
-            let mut mean_points = 0.0;
-            for points in reviews.iter() {
-                let mut review_mean_points = 0.0;
-                for point in points {
-                    let weighted_points = match point.kind {
-                        Kind::Point => point.points * point.weight,
-                        Kind::Grade => match point.points.round() as i64 {
-                            1 => point_range * point.weight,
-                            2 => point_range * 0.8 * point.weight,
-                            3 => point_range * 0.6 * point.weight,
-                            4 => point_range * 0.5 * point.weight,
-                            _ => 0.0,
-                        },
-                        Kind::Percentage => point.points * point.weight,
-                        Kind::Truefalse => match point.points.round() as i64 {
-                            1 => point_range * point.weight,
-                            _ => 0.0,
-                        },
-                    };
-                    review_mean_points += weighted_points;
-                }
-                mean_points += review_mean_points;
-            }
-            mean_points /= reviews.len() as f64;
+            // Calculate max points (based on criterion and weights)
+            let max_points = grading::max_points(&reviews[0].points);
+            // Aggregate review points with the configured method
+            // Teacher reviews are weighted with the configured teacher weight,
+            // peer reviews with the accuracy of the reviewer in calibration reviews
+            let scores: Vec<WeightedScore> = reviews
+                .iter()
+                .map(|review| WeightedScore {
+                    points: grading::review_points(&review.points, &workshop.gradescale),
+                    weight: if review.teacher {
+                        teacher_weight
+                    } else {
+                        review
+                            .reviewer
+                            .and_then(|reviewer_id| accuracies.get(&reviewer_id).copied())
+                            .unwrap_or(1.0)
+                    },
+                    reliability: review
+                        .reviewer
+                        .and_then(|reviewer_id| reliabilities.get(&reviewer_id).copied())
+                        .unwrap_or(1.0),
+                    teacher: review.teacher,
+                })
+                .collect();
+            let mean_points = aggregation::aggregate(&workshop.aggregation, &scores).unwrap_or(0.0);
+            // Late submissions lose the configured share of their points
+            let mean_points = if submission.late {
+                late::penalized_points(mean_points, workshop.latepenalty)
+            } else {
+                mean_points
+            };
             // Update submission
             submission.reviewsdone = true;
-            submission.maxpoint = Some(max_points);
-            submission.meanpoints = Some(mean_points);
+            submission.error = false;
+            submission.computedpoints = Some(mean_points);
+            if !overridden {
+                submission.maxpoint = Some(max_points);
+                submission.meanpoints = Some(mean_points);
+            }
             let update = diesel::update(submissions_t.filter(sub_id.eq(submission.id)))
                 .set(&submission)
                 .execute(conn);
@@ -569,6 +993,242 @@ pub(crate) fn calculate_points(conn: &MysqlConnection, submission_id: u64) -> Re
     }
 }
 
+// Get points including the reviewing component of a submission owner.
+// Only available if the workshop has a reviewing weight, overridden points are kept as they are.
+fn get_final_points(
+    conn: &MysqlConnection,
+    submission: &Submission,
+    student: Option<u64>,
+) -> Option<f64> {
+    if submission.justification.is_some() {
+        return None;
+    }
+    let (points, max_points, student) = match (submission.meanpoints, submission.maxpoint, student)
+    {
+        (Some(points), Some(max_points), Some(student)) => (points, max_points, student),
+        _ => return None,
+    };
+    // The reviewing component applies to the combined points of all rounds
+    let points = get_round_points(conn, submission).unwrap_or(points);
+    let workshop = db::workshops::get_by_submission_id(conn, submission.id);
+    if workshop.is_err() {
+        return None;
+    }
+    let workshop = workshop.unwrap();
+    if workshop.reviewingweight <= 0.0 {
+        return None;
+    }
+    let helpfulness = db::reviews::get_student_helpfulness(conn, workshop.id, student);
+    Some(grading::reviewing::final_points(
+        points,
+        max_points,
+        helpfulness,
+        workshop.reviewingweight,
+    ))
+}
+
+/// Combine the points of all rounds of a submission with the round policy of the workshop.
+/// Only available in workshops with more than one round.
+pub fn get_round_points(conn: &MysqlConnection, submission: &Submission) -> Option<f64> {
+    let workshop = db::workshops::get_by_id(conn, submission.workshop);
+    if workshop.is_err() {
+        return None;
+    }
+    let workshop = workshop.unwrap();
+    if workshop.rounds <= 1 {
+        return None;
+    }
+    let points: Vec<Option<f64>> = get_round_ids(conn, submission)
+        .into_iter()
+        .map(|round_id| {
+            get_by_id(conn, round_id)
+                .ok()
+                .and_then(|round| round.meanpoints)
+        })
+        .collect();
+    grading::rounds::round_points(&points, &workshop.roundpolicy)
+}
+
+/// Get the final grade of a student for a submission, as shown in the results.
+/// Final points if the workshop has a reviewing weight, otherwise the points of all rounds
+/// or the points of the submission.
+pub fn get_grade(
+    conn: &MysqlConnection,
+    submission: &Submission,
+    student: Option<u64>,
+) -> Option<f64> {
+    get_final_points(conn, submission, student)
+        .or_else(|| get_round_points(conn, submission))
+        .or(submission.meanpoints)
+}
+
+// Get the ids of all rounds a submission is part of, first round first.
+fn get_round_ids(conn: &MysqlConnection, submission: &Submission) -> Vec<u64> {
+    let mut rounds = vec![submission.id];
+    let mut previous = submission.previous;
+    while let Some(previous_id) = previous {
+        rounds.insert(0, previous_id);
+        previous = submissions_t
+            .filter(sub_id.eq(previous_id))
+            .select(sub_previous)
+            .first(conn)
+            .unwrap_or(None);
+    }
+    let mut next_id = submission.id;
+    while let Ok(next) = submissions_t
+        .filter(sub_previous.eq(next_id))
+        .select(sub_id)
+        .first::<u64>(conn)
+    {
+        rounds.push(next);
+        next_id = next;
+    }
+    rounds
+}
+
+/// Override points of a graded submission.
+/// Only teachers of the submission's workshop can override points.
+/// The computed points are kept so that the override can be reverted.
+pub fn override_points(
+    conn: &MysqlConnection,
+    submission_id: u64,
+    teacher_id: u64,
+    points: f64,
+    justification: String,
+) -> Result<(), DbError> {
+    if let Err(err) = check_teacher(conn, submission_id, teacher_id) {
+        return Err(err);
+    }
+    let points_calculation = calculate_points(conn, submission_id);
+    if let Err(err) = points_calculation {
+        return Err(err);
+    }
+    let submission = get_by_id(conn, submission_id);
+    if submission.is_err() {
+        return Err(DbError::new(
+            DbErrorKind::NotFound,
+            format!("Submission {} not found", submission_id),
+        ));
+    }
+    let submission = submission.unwrap();
+    if !submission.reviewsdone {
+        return Err(DbError::new(
+            DbErrorKind::NotFinished,
+            "Points can only be overridden after reviews are done",
+        ));
+    }
+
+    // Submissions without reviews have no max points yet
+    let max_points = if let Some(max_points) = submission.maxpoint {
+        max_points
+    } else {
+        let criteria = get_criteria(conn, submission_id);
+        if criteria.is_err() {
+            return Err(DbError::new(
+                DbErrorKind::ReadFailed,
+                format!("Criteria for Submission {} not found", submission_id),
+            ));
+        }
+        criteria
+            .unwrap()
+            .iter()
+            .filter(|criterion| criterion.kind.is_scored())
+            .map(|criterion| grading::POINT_RANGE * criterion.weight)
+            .sum()
+    };
+    if points > max_points {
+        return Err(DbError::new(
+            DbErrorKind::Mismatch,
+            format!("Points exceed maximum of {}", max_points),
+        ));
+    }
+
+    let update = diesel::update(submissions_t.filter(sub_id.eq(submission_id)))
+        .set((
+            sub_meanpoints.eq(Some(points)),
+            sub_maxpoint.eq(Some(max_points)),
+            sub_justification.eq(Some(justification)),
+        ))
+        .execute(conn);
+    match update {
+        Ok(_) => {
+            if let Err(err) = db::webhooks::submission_graded(conn, submission_id) {
+                err.print_stacktrace();
+            }
+            Ok(())
+        }
+        Err(_) => Err(DbError::new(
+            DbErrorKind::UpdateFailed,
+            "Submission Points Override failed",
+        )),
+    }
+}
+
+/// Revert overridden points of a submission to the computed points.
+/// Only teachers of the submission's workshop can revert points.
+pub fn reset_points(
+    conn: &MysqlConnection,
+    submission_id: u64,
+    teacher_id: u64,
+) -> Result<(), DbError> {
+    let submission = check_teacher(conn, submission_id, teacher_id);
+    if let Err(err) = submission {
+        return Err(err);
+    }
+    let submission = submission.unwrap();
+    let max_points = if submission.computedpoints.is_some() {
+        submission.maxpoint
+    } else {
+        None
+    };
+
+    let update = diesel::update(submissions_t.filter(sub_id.eq(submission_id)))
+        .set((
+            sub_meanpoints.eq(submission.computedpoints),
+            sub_maxpoint.eq(max_points),
+            sub_justification.eq(None::<String>),
+        ))
+        .execute(conn);
+    match update {
+        Ok(_) => {
+            if let Err(err) = db::webhooks::submission_graded(conn, submission_id) {
+                err.print_stacktrace();
+            }
+            Ok(())
+        }
+        Err(_) => Err(DbError::new(
+            DbErrorKind::UpdateFailed,
+            "Submission Points Reset failed",
+        )),
+    }
+}
+
+// Get submission if the teacher is part of its workshop.
+fn check_teacher(
+    conn: &MysqlConnection,
+    submission_id: u64,
+    teacher_id: u64,
+) -> Result<Submission, DbError> {
+    let submission = get_by_id(conn, submission_id);
+    if submission.is_err() {
+        return Err(DbError::new(
+            DbErrorKind::NotFound,
+            format!("Submission {} not found", submission_id),
+        ));
+    }
+    let submission = submission.unwrap();
+    if !db::workshops::teacher_in_workshop(conn, teacher_id, submission.workshop) {
+        return Err(DbError::new(
+            DbErrorKind::NotFound,
+            format!(
+                "Teacher {} not in Workshop {}",
+                teacher_id, submission.workshop
+            ),
+        ));
+    }
+    Ok(submission)
+}
+
 /// Get review criteria for a submission.
 pub fn get_criteria(conn: &MysqlConnection, submission_id: u64) -> Result<Vec<Criterion>, ()> {
     let submission_criteria: Result<Vec<u64>, _> = subcrit_t
@@ -612,6 +1272,76 @@ pub fn get_by_id(conn: &MysqlConnection, submission_id: u64) -> Result<Submissio
     submissions_t.filter(sub_id.eq(submission_id)).first(conn)
 }
 
+/// Get ids of all students owning a submission.
+/// Team submissions are owned by every team member.
+pub fn get_owner_ids(conn: &MysqlConnection, submission: &Submission) -> Result<Vec<u64>, Error> {
+    match submission.team {
+        Some(team_id) => db::teams::get_member_ids(conn, team_id),
+        None => Ok(submission.student.into_iter().collect()),
+    }
+}
+
+/// Withdraw a submission.
+/// Only possible while the submission is not locked and none of its reviews was started.
+/// The assigned reviews are removed so that their reviewers can get other submissions.
+pub fn withdraw(
+    conn: &MysqlConnection,
+    submission_id: u64,
+    student_id: u64,
+) -> Result<(), DbError> {
+    if !is_owner(conn, submission_id, student_id) {
+        return Err(DbError::new(
+            DbErrorKind::NotFound,
+            "Submission does not exist and/or Student is not Owner of Submission",
+        ));
+    }
+    let submission = get_by_id(conn, submission_id);
+    if submission.is_err() {
+        return Err(DbError::new(
+            DbErrorKind::NotFound,
+            format!("Submission {} not found", submission_id),
+        ));
+    }
+    if submission.unwrap().locked {
+        return Err(DbError::new(
+            DbErrorKind::PastDeadline,
+            "Locked Submissions cannot be withdrawn",
+        ));
+    }
+    if db::reviews::is_started(conn, submission_id) {
+        return Err(DbError::new(
+            DbErrorKind::Mismatch,
+            format!(
+                "Reviews of Submission {} were already started",
+                submission_id
+            ),
+        ));
+    }
+
+    let mut t_error: Result<(), DbError> = Ok(());
+    let withdraw = conn.transaction::<(), Error, _>(|| {
+        if let Err(err) = db::reviews::delete_by_submission(conn, submission_id) {
+            return DbError::assign_and_rollback(&mut t_error, err);
+        }
+        let delete = diesel::delete(submissions_t.filter(sub_id.eq(submission_id))).execute(conn);
+        if delete.is_err() {
+            return DbError::assign_and_rollback(
+                &mut t_error,
+                DbError::new(DbErrorKind::DeleteFailed, "Submission Delete failed"),
+            );
+        }
+        Ok(())
+    });
+
+    match withdraw {
+        Ok(_) => Ok(()),
+        Err(_) => Err(t_error.err().unwrap_or(DbError::new(
+            DbErrorKind::TransactionFailed,
+            "Unknown error",
+        ))),
+    }
+}
+
 /// Update submission.
 pub fn update<'a>(
     conn: &MysqlConnection,
@@ -644,6 +1374,32 @@ pub fn update<'a>(
             "Locked Submissions cannot be updated",
         ));
     }
+    let workshop = db::workshops::get_by_id(conn, submission.workshop);
+    if workshop.is_err() {
+        return Err(DbError::new(
+            DbErrorKind::NotFound,
+            format!("Workshop {} not found", submission.workshop),
+        ));
+    }
+    let extension = db::extensions::get_submission_extension(conn, submission.workshop, student_id);
+    let late = db::workshops::check_submission_phase(
+        &workshop.unwrap(),
+        extension,
+        Local::now().naive_local(),
+    );
+    if let Err(err) = late {
+        return Err(err);
+    }
+    let owner_ids = get_owner_ids(conn, &submission);
+    if owner_ids.is_err() {
+        return Err(DbError::new(
+            DbErrorKind::ReadFailed,
+            format!("Owners of Submission {} not found", submission_id),
+        ));
+    }
+    let owner_ids = owner_ids.unwrap();
+    // Updates after the submission phase make the submission late
+    submission.late = submission.late || late.unwrap();
     submission.title = title;
     submission.comment = comment;
 
@@ -669,36 +1425,14 @@ pub fn update<'a>(
             );
         }
 
-        // Relate attachments to submission
-        let all_student_attachments = db::attachments::get_ids_by_user_id(conn, student_id);
-        if all_student_attachments.is_err() {
-            return DbError::assign_and_rollback(
-                &mut t_error,
-                DbError::new(DbErrorKind::ReadFailed, "Student attachments not found"),
-            );
+        // Relate attachments to submission, team members keep the attachments of each other
+        if let Err(err) = relate_attachments(conn, submission_id, &owner_ids, attachments) {
+            return DbError::assign_and_rollback(&mut t_error, err);
         }
-        let all_student_attachments = all_student_attachments.unwrap();
-        let submission_attachments: Vec<Submissionattachment> = attachments
-            .into_iter()
-            .filter_map(|att_id| {
-                if all_student_attachments.contains(&att_id) {
-                    Some(Submissionattachment {
-                        submission: submission.id,
-                        attachment: att_id,
-                    })
-                } else {
-                    None
-                }
-            })
-            .collect();
-        let attachment_insert = diesel::insert_into(subatt_t)
-            .values(&submission_attachments)
-            .execute(conn);
-        if attachment_insert.is_err() {
-            return DbError::assign_and_rollback(
-                &mut t_error,
-                DbError::new(DbErrorKind::CreateFailed, "Attachment Insert failed"),
-            );
+
+        // Keep the updated state as new version
+        if let Err(err) = db::versions::add(conn, submission_id, Local::now().naive_local()) {
+            return DbError::assign_and_rollback(&mut t_error, err);
         }
         Ok(())
     });
@@ -0,0 +1,285 @@
+//! CRUD operations for teams.
+//! One team member submits, the submission is owned by every member of the team.
+
+use crate::db;
+use crate::db::error::{DbError, DbErrorKind};
+use crate::db::models::*;
+use crate::schema::teammembers::dsl::{
+    student as tm_student, team as tm_team, teammembers as teammembers_t,
+};
+use crate::schema::teams::dsl::{id as t_id, teams as teams_t, workshop as t_workshop};
+use crate::schema::users::dsl::{id as u_id, users as users_t};
+use diesel::prelude::*;
+use diesel::result::Error;
+
+/// Create a new team for a workshop.
+pub fn create(
+    conn: &MysqlConnection,
+    teacher_id: u64,
+    workshop_id: u64,
+    title: String,
+    members: Vec<u64>,
+) -> Result<Team, DbError> {
+    if !db::workshops::teacher_in_workshop(conn, teacher_id, workshop_id) {
+        return Err(DbError::new(
+            DbErrorKind::NotFound,
+            format!("Teacher {} not in Workshop {}", teacher_id, workshop_id),
+        ));
+    }
+    if let Err(err) = check_members(conn, workshop_id, None, &members) {
+        return Err(err);
+    }
+
+    let new_team = NewTeam {
+        workshop: workshop_id,
+        title,
+    };
+    let mut t_error: Result<(), DbError> = Ok(());
+    let team = conn.transaction::<Team, Error, _>(|| {
+        let team_insert = diesel::insert_into(teams_t).values(&new_team).execute(conn);
+        if team_insert.is_err() {
+            return DbError::assign_and_rollback(
+                &mut t_error,
+                DbError::new(DbErrorKind::CreateFailed, "Team Insert failed"),
+            );
+        }
+        let team: Team = teams_t.order(t_id.desc()).first(conn).unwrap();
+        if let Err(err) = relate_members(conn, team.id, members) {
+            return DbError::assign_and_rollback(&mut t_error, err);
+        }
+        Ok(team)
+    });
+
+    match team {
+        Ok(team) => Ok(team),
+        Err(_) => Err(t_error.err().unwrap_or(DbError::new(
+            DbErrorKind::TransactionFailed,
+            "Unknown error",
+        ))),
+    }
+}
+
+/// Update title & members of a team.
+/// Submissions of the team are owned by the new members.
+pub fn update(
+    conn: &MysqlConnection,
+    teacher_id: u64,
+    team_id: u64,
+    title: String,
+    members: Vec<u64>,
+) -> Result<(), DbError> {
+    let team = get_by_id(conn, team_id);
+    if team.is_err() {
+        return Err(DbError::new(
+            DbErrorKind::NotFound,
+            format!("Team {} not found", team_id),
+        ));
+    }
+    let mut team = team.unwrap();
+    if !db::workshops::teacher_in_workshop(conn, teacher_id, team.workshop) {
+        return Err(DbError::new(
+            DbErrorKind::NotFound,
+            format!("Teacher {} not in Workshop {}", teacher_id, team.workshop),
+        ));
+    }
+    if let Err(err) = check_members(conn, team.workshop, Some(team_id), &members) {
+        return Err(err);
+    }
+    team.title = title;
+
+    let mut t_error: Result<(), DbError> = Ok(());
+    let update = conn.transaction::<(), Error, _>(|| {
+        let update = diesel::update(teams_t.filter(t_id.eq(team_id)))
+            .set(&team)
+            .execute(conn);
+        if update.is_err() {
+            return DbError::assign_and_rollback(
+                &mut t_error,
+                DbError::new(DbErrorKind::UpdateFailed, "Team Update failed"),
+            );
+        }
+        let delete = diesel::delete(teammembers_t.filter(tm_team.eq(team_id))).execute(conn);
+        if delete.is_err() {
+            return DbError::assign_and_rollback(
+                &mut t_error,
+                DbError::new(DbErrorKind::DeleteFailed, "Team Member Delete failed"),
+            );
+        }
+        if let Err(err) = relate_members(conn, team_id, members) {
+            return DbError::assign_and_rollback(&mut t_error, err);
+        }
+        Ok(())
+    });
+
+    match update {
+        Ok(_) => Ok(()),
+        Err(_) => Err(t_error.err().unwrap_or(DbError::new(
+            DbErrorKind::TransactionFailed,
+            "Unknown error",
+        ))),
+    }
+}
+
+/// Delete a team.
+/// Submissions of the team are only owned by their submitter afterwards.
+pub fn delete(conn: &MysqlConnection, teacher_id: u64, team_id: u64) -> Result<(), DbError> {
+    let team = get_by_id(conn, team_id);
+    if team.is_err() {
+        return Err(DbError::new(
+            DbErrorKind::NotFound,
+            format!("Team {} not found", team_id),
+        ));
+    }
+    let team = team.unwrap();
+    if !db::workshops::teacher_in_workshop(conn, teacher_id, team.workshop) {
+        return Err(DbError::new(
+            DbErrorKind::NotFound,
+            format!("Teacher {} not in Workshop {}", teacher_id, team.workshop),
+        ));
+    }
+    let delete = diesel::delete(teams_t.filter(t_id.eq(team_id))).execute(conn);
+    match delete {
+        Ok(_) => Ok(()),
+        Err(_) => Err(DbError::new(
+            DbErrorKind::DeleteFailed,
+            "Team Delete failed",
+        )),
+    }
+}
+
+// Members need to be students of the workshop and cannot be part of another team.
+fn check_members(
+    conn: &MysqlConnection,
+    workshop_id: u64,
+    team_id: Option<u64>,
+    members: &[u64],
+) -> Result<(), DbError> {
+    for &student_id in members {
+        if !db::workshops::student_in_workshop(conn, student_id, workshop_id) {
+            return Err(DbError::new(
+                DbErrorKind::NotFound,
+                format!("Student {} not in Workshop {}", student_id, workshop_id),
+            ));
+        }
+        match get_student_team(conn, workshop_id, student_id) {
+            Some(team) if Some(team.id) != team_id => {
+                return Err(DbError::new(
+                    DbErrorKind::Mismatch,
+                    format!("Student {} is already in Team {}", student_id, team.id),
+                ));
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+// Relate members to a team.
+fn relate_members(
+    conn: &MysqlConnection,
+    team_id: u64,
+    mut members: Vec<u64>,
+) -> Result<(), DbError> {
+    members.sort();
+    members.dedup();
+    let team_members: Vec<TeamMember> = members
+        .into_iter()
+        .map(|student| TeamMember {
+            team: team_id,
+            student,
+        })
+        .collect();
+    let members_insert = diesel::insert_into(teammembers_t)
+        .values(&team_members)
+        .execute(conn);
+    if members_insert.is_err() {
+        return Err(DbError::new(
+            DbErrorKind::CreateFailed,
+            "Team Member Insert failed",
+        ));
+    }
+    Ok(())
+}
+
+/// Get team by team id.
+pub fn get_by_id(conn: &MysqlConnection, team_id: u64) -> Result<Team, Error> {
+    teams_t.filter(t_id.eq(team_id)).first(conn)
+}
+
+/// Get the team of a student in a workshop.
+pub fn get_student_team(conn: &MysqlConnection, workshop_id: u64, student_id: u64) -> Option<Team> {
+    teams_t
+        .inner_join(teammembers_t.on(tm_team.eq(t_id)))
+        .filter(t_workshop.eq(workshop_id).and(tm_student.eq(student_id)))
+        .select(crate::schema::teams::all_columns)
+        .first(conn)
+        .ok()
+}
+
+/// Get the ids of all members of a team.
+pub fn get_member_ids(conn: &MysqlConnection, team_id: u64) -> Result<Vec<u64>, Error> {
+    teammembers_t
+        .filter(tm_team.eq(team_id))
+        .select(tm_student)
+        .get_results(conn)
+}
+
+/// Check if student is member of a team.
+pub fn is_member(conn: &MysqlConnection, team_id: u64, student_id: u64) -> bool {
+    let member: Result<TeamMember, _> = teammembers_t
+        .filter(tm_team.eq(team_id).and(tm_student.eq(student_id)))
+        .first(conn);
+    member.is_ok()
+}
+
+/// Get team with its members.
+pub fn get_workshop_team(conn: &MysqlConnection, team: Team) -> Result<WorkshopTeam, DbError> {
+    let members = users_t
+        .inner_join(teammembers_t.on(tm_student.eq(u_id)))
+        .filter(tm_team.eq(team.id))
+        .select(crate::schema::users::all_columns)
+        .get_results::<User>(conn);
+    if members.is_err() {
+        return Err(DbError::new(
+            DbErrorKind::ReadFailed,
+            format!("Members of Team {} not found", team.id),
+        ));
+    }
+    let members = members
+        .unwrap()
+        .into_iter()
+        .map(|user| SimpleUser {
+            id: user.id,
+            firstname: user.firstname,
+            lastname: user.lastname,
+            unit: user.unit,
+        })
+        .collect();
+    Ok(WorkshopTeam {
+        id: team.id,
+        title: team.title,
+        members,
+    })
+}
+
+/// Get all teams of a workshop with their members.
+pub fn get_workshop_teams(
+    conn: &MysqlConnection,
+    workshop_id: u64,
+) -> Result<Vec<WorkshopTeam>, DbError> {
+    let teams = teams_t
+        .filter(t_workshop.eq(workshop_id))
+        .order(t_id)
+        .get_results::<Team>(conn);
+    if teams.is_err() {
+        return Err(DbError::new(
+            DbErrorKind::ReadFailed,
+            format!("Teams of Workshop {} not found", workshop_id),
+        ));
+    }
+    teams
+        .unwrap()
+        .into_iter()
+        .map(|team| get_workshop_team(conn, team))
+        .collect()
+}
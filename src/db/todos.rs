@@ -3,14 +3,17 @@
 use crate::db;
 use crate::db::error::{DbError, DbErrorKind};
 use crate::db::models::*;
-use crate::db::submissions::calculate_points;
 use crate::schema::reviews::dsl::{
     deadline as review_deadline, done as review_done, id as review_id, locked as review_locked,
     reviewer, reviews as reviews_t, submission as review_submission,
 };
+use crate::schema::submissionextensions::dsl::{
+    end as se_end, student as se_student, submissionextensions as submissionextensions_t,
+    workshop as se_workshop,
+};
 use crate::schema::submissions::dsl::{
-    deadline as sub_deadline, id as sub_id, reviewsdone as sub_reviews_done,
-    student as sub_student, submissions as submissions_t, title as sub_title, workshop as sub_ws,
+    id as sub_id, student as sub_student, submissions as submissions_t, team as sub_team,
+    title as sub_title, workshop as sub_ws,
 };
 use crate::schema::users::dsl::{
     firstname as user_firstname, id as user_id, lastname as user_lastname, users as users_t,
@@ -21,52 +24,31 @@ use crate::schema::workshoplist::dsl::{
 use crate::schema::workshops::dsl::{
     end as ws_end, id as ws_id, title as ws_title, workshops as workshops_t,
 };
-use crate::utils::error::AppError;
 use chrono::Local;
 use diesel::dsl::exists;
 use diesel::dsl::not;
 use diesel::prelude::*;
+use diesel::select;
 
 /// Get student T O D O.
 pub fn get(conn: &MysqlConnection, student_id: u64) -> Result<Todo, DbError> {
-    // Query Submission IDs to related Submissions/Reviews where points & reviews
-    // were not processed yet
-    // --------------------------------------------------------------------------
-    let now = Local::now().naive_local();
-    let submissions_to_close: QueryResult<Vec<u64>> = reviews_t
-        .inner_join(submissions_t.on(sub_id.eq(review_submission)))
-        .inner_join(workshops_t.on(ws_id.eq(sub_ws)))
-        .inner_join(users_t.on(user_id.nullable().eq(sub_student.nullable())))
-        .filter(
-            reviewer.eq(student_id).and(
-                review_locked
-                    .eq(false)
-                    .and(sub_reviews_done.eq(false).and(sub_deadline.lt(now))),
-            ),
-        )
-        .select(sub_id)
-        .get_results::<u64>(conn);
-
-    if submissions_to_close.is_err() {
-        return Err(DbError::new(
-            DbErrorKind::ReadFailed,
-            "Could not query done Submissions",
-        ));
+    let reviews = get_reviews(conn, student_id);
+    if let Err(err) = reviews {
+        return Err(err);
     }
-    let submissions_to_close = submissions_to_close.unwrap();
-    // Process finished submissions
-    for submission_id in submissions_to_close {
-        if let Err(source_err) = calculate_points(conn, submission_id) {
-            return Err(DbError::new_with_source(
-                DbErrorKind::UpdateFailed,
-                "Could not process finished Submissions",
-                Box::new(source_err) as Box<dyn AppError>,
-            ));
-        }
+    let submissions = get_missing_submissions(conn, student_id);
+    if let Err(err) = submissions {
+        return Err(err);
     }
 
-    // Query reviews that can still be updated (deadline not reached yet)
-    // ------------------------------------------------------------------
+    Ok(Todo {
+        reviews: reviews.unwrap(),
+        submissions: submissions.unwrap(),
+    })
+}
+
+/// Get reviews of a student that can still be updated (deadline not reached yet).
+pub fn get_reviews(conn: &MysqlConnection, student_id: u64) -> Result<Vec<TodoReview>, DbError> {
     /*
     select r.id, r.done, r.deadline, s.id, u.firstname, u.lastname, w.title
          from reviews r
@@ -123,7 +105,7 @@ pub fn get(conn: &MysqlConnection, student_id: u64) -> Result<Todo, DbError> {
             TodoReview {
                 id: review.0,
                 done: review.1,
-                deadline: review.2,
+                deadline: db::extensions::get_review_extension(conn, review.0).unwrap_or(review.2),
                 title: review.3,
                 firstname,
                 lastname,
@@ -132,10 +114,16 @@ pub fn get(conn: &MysqlConnection, student_id: u64) -> Result<Todo, DbError> {
             }
         })
         .collect();
+    Ok(reviews)
+}
 
-    // Get Workshops where no Submissions were placed yet
-    // Also filter only current workshops
-    // --------------------------------------------------
+/// Get workshops where the student placed no submission yet.
+/// Only current workshops or workshops with a running extension are returned.
+pub fn get_missing_submissions(
+    conn: &MysqlConnection,
+    student_id: u64,
+) -> Result<Vec<TodoSubmission>, DbError> {
+    let now = Local::now().naive_local();
     let raw_submissions = workshops_t
         .left_outer_join(workshoplist_t.on(ws_id.eq(wsl_ws)))
         .left_outer_join(users_t.on(user_id.eq(wsl_user)))
@@ -145,7 +133,16 @@ pub fn get(conn: &MysqlConnection, student_id: u64) -> Result<Todo, DbError> {
                 .and(not(exists(
                     submissions_t.filter(sub_student.eq(student_id).and(sub_ws.eq(ws_id))),
                 )))
-                .and(ws_end.ge(now)),
+                .and(
+                    ws_end.ge(now).or(exists(
+                        submissionextensions_t.filter(
+                            se_workshop
+                                .eq(ws_id)
+                                .and(se_student.eq(student_id))
+                                .and(se_end.ge(now)),
+                        ),
+                    )),
+                ),
         )
         .select((ws_id, ws_title))
         .get_results::<(u64, String)>(conn);
@@ -158,8 +155,17 @@ pub fn get(conn: &MysqlConnection, student_id: u64) -> Result<Todo, DbError> {
     }
     let raw_submissions = raw_submissions.unwrap();
 
+    // Submissions of team members count for the whole team
     let submissions: Vec<TodoSubmission> = raw_submissions
         .into_iter()
+        .filter(
+            |workshop| match db::teams::get_student_team(conn, workshop.0, student_id) {
+                Some(team) => !select(exists(submissions_t.filter(sub_team.eq(team.id))))
+                    .get_result::<bool>(conn)
+                    .unwrap_or(false),
+                None => true,
+            },
+        )
         .map(|workshop| TodoSubmission {
             id: workshop.0,
             workshop_name: workshop.1,
@@ -221,8 +227,5 @@ pub fn get(conn: &MysqlConnection, student_id: u64) -> Result<Todo, DbError> {
 
     */
 
-    Ok(Todo {
-        reviews,
-        submissions,
-    })
+    Ok(submissions)
 }
@@ -16,12 +16,13 @@ pub fn create_student<'a>(
     lastname: String,
     password: String,
     unit: String,
+    email: Option<String>,
 ) -> Result<User, &'static str> {
     let exists: Result<User, _> = users.filter(dsl_username.eq(&username)).first(conn);
     if exists.is_ok() {
         return Err("Already exists");
     }
-    let new_user = NewStudent::new(username, firstname, lastname, password, unit);
+    let new_user = NewStudent::new(username, firstname, lastname, password, unit, email);
 
     diesel::insert_into(users)
         .values(&new_user)
@@ -38,12 +39,13 @@ pub fn create_teacher<'a>(
     firstname: String,
     lastname: String,
     password: String,
+    email: Option<String>,
 ) -> Result<User, &'static str> {
     let exists: Result<User, _> = users.filter(dsl_username.eq(&username)).first(conn);
     if exists.is_ok() {
         return Err("Already exists");
     }
-    let new_user = NewTeacher::new(username, firstname, lastname, password);
+    let new_user = NewTeacher::new(username, firstname, lastname, password, email);
 
     diesel::insert_into(users)
         .values(&new_user)
@@ -0,0 +1,313 @@
+//! Operations for submission versions.
+//! Every creation & update of a submission adds an immutable version,
+//! reviews are pinned to the version the reviewer saw first.
+
+use crate::db::error::{DbError, DbErrorKind};
+use crate::db::models::*;
+use crate::schema::attachments::dsl::{
+    attachments as attachments_t, id as att_id, title as att_title,
+};
+use crate::schema::reviews::dsl::{
+    id as reviews_id, reviewer, reviews as reviews_t, selfassessment as reviews_self,
+    submission as reviews_sub, version as reviews_version,
+};
+use crate::schema::submissionattachments::dsl::{
+    attachment as subatt_att, submission as subatt_sub, submissionattachments as subatt_t,
+};
+use crate::schema::submissions::dsl::{id as sub_id, submissions as submissions_t};
+use crate::schema::submissionversions::dsl::{
+    id as v_id, submission as v_sub, submissionversions as versions_t, version as v_version,
+};
+use crate::schema::versionattachments::dsl::{
+    attachment as va_att, version as va_version, versionattachments as va_t,
+};
+use crate::utils::diff::diff_lines;
+use diesel::dsl::max;
+use diesel::prelude::*;
+use diesel::result::Error;
+
+/// Add the current state of a submission as new version.
+/// Needs to be called after every change of title, comment or attachments.
+pub(crate) fn add(
+    conn: &MysqlConnection,
+    submission_id: u64,
+    date: chrono::NaiveDateTime,
+) -> Result<SubmissionVersion, DbError> {
+    let submission: Result<Submission, _> =
+        submissions_t.filter(sub_id.eq(submission_id)).first(conn);
+    if submission.is_err() {
+        return Err(DbError::new(
+            DbErrorKind::NotFound,
+            format!("Submission {} not found", submission_id),
+        ));
+    }
+    let submission = submission.unwrap();
+    let attachments: Result<Vec<u64>, _> = subatt_t
+        .filter(subatt_sub.eq(submission_id))
+        .select(subatt_att)
+        .get_results(conn);
+    if attachments.is_err() {
+        return Err(DbError::new(
+            DbErrorKind::ReadFailed,
+            format!("Attachments for Submission {} not found", submission_id),
+        ));
+    }
+    let latest: Result<Option<i32>, _> = versions_t
+        .filter(v_sub.eq(submission_id))
+        .select(max(v_version))
+        .first(conn);
+    if latest.is_err() {
+        return Err(DbError::new(
+            DbErrorKind::ReadFailed,
+            format!("Versions for Submission {} not found", submission_id),
+        ));
+    }
+
+    let new_version = NewSubmissionVersion {
+        submission: submission_id,
+        version: latest.unwrap().unwrap_or(0) + 1,
+        title: submission.title,
+        comment: submission.comment,
+        date,
+    };
+    let version_insert = diesel::insert_into(versions_t)
+        .values(&new_version)
+        .execute(conn);
+    if version_insert.is_err() {
+        return Err(DbError::new(
+            DbErrorKind::CreateFailed,
+            "Submission Version Insert failed",
+        ));
+    }
+    let version = get_latest(conn, submission_id);
+    if version.is_err() {
+        return Err(DbError::new(
+            DbErrorKind::ReadFailed,
+            "Submission Version not found",
+        ));
+    }
+    let version = version.unwrap();
+
+    let version_attachments: Vec<VersionAttachment> = attachments
+        .unwrap()
+        .into_iter()
+        .map(|attachment| VersionAttachment {
+            version: version.id,
+            attachment,
+        })
+        .collect();
+    let attachment_insert = diesel::insert_into(va_t)
+        .values(&version_attachments)
+        .execute(conn);
+    if attachment_insert.is_err() {
+        return Err(DbError::new(
+            DbErrorKind::CreateFailed,
+            "Version Attachment Insert failed",
+        ));
+    }
+    Ok(version)
+}
+
+/// Get the latest version of a submission.
+pub fn get_latest(conn: &MysqlConnection, submission_id: u64) -> Result<SubmissionVersion, Error> {
+    versions_t
+        .filter(v_sub.eq(submission_id))
+        .order(v_version.desc())
+        .first(conn)
+}
+
+/// Get version by version id.
+pub fn get_by_id(conn: &MysqlConnection, version_id: u64) -> Result<SubmissionVersion, Error> {
+    versions_t.filter(v_id.eq(version_id)).first(conn)
+}
+
+/// Get the version number of a version id.
+pub fn get_number(conn: &MysqlConnection, version_id: Option<u64>) -> Option<i32> {
+    version_id.and_then(|version_id| {
+        versions_t
+            .filter(v_id.eq(version_id))
+            .select(v_version)
+            .first(conn)
+            .ok()
+    })
+}
+
+/// Pin the review of a reviewer to the latest version of a submission.
+/// Already pinned reviews keep their version.
+pub(crate) fn pin(
+    conn: &MysqlConnection,
+    submission_id: u64,
+    reviewer_id: u64,
+) -> Result<SubmissionVersion, DbError> {
+    let review: Result<Review, _> = reviews_t
+        .filter(
+            reviews_sub
+                .eq(submission_id)
+                .and(reviewer.eq(reviewer_id))
+                .and(reviews_self.eq(false)),
+        )
+        .first(conn);
+    if review.is_err() {
+        return Err(DbError::new(
+            DbErrorKind::NotFound,
+            format!(
+                "No Review of Student {} for Submission {}",
+                reviewer_id, submission_id
+            ),
+        ));
+    }
+    let review = review.unwrap();
+    if let Some(version_id) = review.version {
+        if let Ok(version) = get_by_id(conn, version_id) {
+            return Ok(version);
+        }
+    }
+
+    let version = get_latest(conn, submission_id);
+    if version.is_err() {
+        return Err(DbError::new(
+            DbErrorKind::NotFound,
+            format!("No Version of Submission {} found", submission_id),
+        ));
+    }
+    let version = version.unwrap();
+    let update = diesel::update(reviews_t.filter(reviews_id.eq(review.id)))
+        .set(reviews_version.eq(version.id))
+        .execute(conn);
+    if update.is_err() {
+        return Err(DbError::new(
+            DbErrorKind::UpdateFailed,
+            "Review Version Update failed",
+        ));
+    }
+    Ok(version)
+}
+
+// Get attachments of a version.
+fn get_attachments(
+    conn: &MysqlConnection,
+    version_id: u64,
+) -> Result<Vec<SimpleAttachment>, Error> {
+    attachments_t
+        .inner_join(va_t.on(va_att.eq(att_id)))
+        .filter(va_version.eq(version_id))
+        .select((att_id, att_title))
+        .get_results::<SimpleAttachment>(conn)
+}
+
+/// Get version with its attachments.
+pub fn get_full_version(
+    conn: &MysqlConnection,
+    version: SubmissionVersion,
+) -> Result<FullSubmissionVersion, DbError> {
+    let attachments = get_attachments(conn, version.id);
+    if attachments.is_err() {
+        return Err(DbError::new(
+            DbErrorKind::ReadFailed,
+            format!("Attachments for Version {} not found", version.version),
+        ));
+    }
+    Ok(FullSubmissionVersion {
+        version: version.version,
+        title: version.title,
+        comment: version.comment,
+        date: version.date,
+        attachments: attachments.unwrap(),
+    })
+}
+
+/// Get all versions of a submission, oldest first.
+pub fn get_versions(
+    conn: &MysqlConnection,
+    submission_id: u64,
+) -> Result<Vec<FullSubmissionVersion>, DbError> {
+    let versions = versions_t
+        .filter(v_sub.eq(submission_id))
+        .order(v_version)
+        .get_results::<SubmissionVersion>(conn);
+    if versions.is_err() {
+        return Err(DbError::new(
+            DbErrorKind::ReadFailed,
+            format!("Versions for Submission {} not found", submission_id),
+        ));
+    }
+    versions
+        .unwrap()
+        .into_iter()
+        .map(|version| get_full_version(conn, version))
+        .collect()
+}
+
+// Get a specific version of a submission.
+fn get_version(
+    conn: &MysqlConnection,
+    submission_id: u64,
+    version: i32,
+) -> Result<FullSubmissionVersion, DbError> {
+    let submission_version = versions_t
+        .filter(v_sub.eq(submission_id).and(v_version.eq(version)))
+        .first::<SubmissionVersion>(conn);
+    if submission_version.is_err() {
+        return Err(DbError::new(
+            DbErrorKind::NotFound,
+            format!(
+                "Version {} of Submission {} not found",
+                version, submission_id
+            ),
+        ));
+    }
+    get_full_version(conn, submission_version.unwrap())
+}
+
+/// Get the changes between two versions of a submission.
+/// Without `to` the changes up to the latest version are returned.
+pub fn get_diff(
+    conn: &MysqlConnection,
+    submission_id: u64,
+    from: i32,
+    to: Option<i32>,
+) -> Result<SubmissionVersionDiff, DbError> {
+    let to = match to {
+        Some(to) => to,
+        None => match get_latest(conn, submission_id) {
+            Ok(latest) => latest.version,
+            Err(_) => {
+                return Err(DbError::new(
+                    DbErrorKind::NotFound,
+                    format!("No Version of Submission {} found", submission_id),
+                ))
+            }
+        },
+    };
+    let old = get_version(conn, submission_id, from);
+    if let Err(err) = old {
+        return Err(err);
+    }
+    let old = old.unwrap();
+    let new = get_version(conn, submission_id, to);
+    if let Err(err) = new {
+        return Err(err);
+    }
+    let new = new.unwrap();
+
+    let added_attachments = new
+        .attachments
+        .iter()
+        .filter(|att| !old.attachments.iter().any(|old_att| old_att.id == att.id))
+        .cloned()
+        .collect();
+    let removed_attachments = old
+        .attachments
+        .iter()
+        .filter(|att| !new.attachments.iter().any(|new_att| new_att.id == att.id))
+        .cloned()
+        .collect();
+    Ok(SubmissionVersionDiff {
+        from,
+        to,
+        title: diff_lines(&old.title, &new.title),
+        comment: diff_lines(&old.comment, &new.comment),
+        added_attachments,
+        removed_attachments,
+    })
+}
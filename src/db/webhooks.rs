@@ -0,0 +1,478 @@
+//! Operations for outgoing webhooks.
+//! Webhooks belong to a workshop or are global, global webhooks can only be managed by the admin.
+//! Events are queued as deliveries which are sent in the background, see `webhooks`.
+
+use crate::db;
+use crate::db::error::{DbError, DbErrorKind};
+use crate::db::models::*;
+use crate::schema::webhookdeliveries::dsl::{
+    attempts as wd_attempts, delivered as wd_delivered, failed as wd_failed, id as wd_id,
+    lastattempt as wd_lastattempt, lasterror as wd_lasterror, nextattempt as wd_nextattempt,
+    status as wd_status, webhook as wd_webhook, webhookdeliveries as webhookdeliveries_t,
+};
+use crate::schema::webhookevents::dsl::{
+    event as we_event, webhook as we_webhook, webhookevents as webhookevents_t,
+};
+use crate::schema::webhooks::dsl::{
+    active as wh_active, id as wh_id, secret as wh_secret, url as wh_url, webhooks as webhooks_t,
+    workshop as wh_workshop,
+};
+use crate::schema::workshoplist::dsl::{
+    role as wsl_role, user as wsl_user, workshop as wsl_ws, workshoplist as workshoplist_t,
+};
+use crate::utils::queue;
+use chrono::Local;
+use diesel::prelude::*;
+use diesel::result::Error;
+use serde_json::{json, Value};
+
+/// Number of deliveries shown in the delivery log.
+const DELIVERY_LOG_LIMIT: i64 = 100;
+
+/// Check if an user may manage webhooks of a workshop.
+/// Global webhooks are reserved to the admin.
+pub fn can_manage(conn: &MysqlConnection, user: &User, workshop_id: Option<u64>) -> bool {
+    if user.username == "admin" {
+        return true;
+    }
+    match workshop_id {
+        Some(workshop_id) => db::workshops::teacher_in_workshop(conn, user.id, workshop_id),
+        None => false,
+    }
+}
+
+/// Get webhook by id.
+pub fn get_by_id(conn: &MysqlConnection, webhook_id: u64) -> Result<Webhook, Error> {
+    webhooks_t.filter(wh_id.eq(webhook_id)).first(conn)
+}
+
+/// Get webhooks an user can manage.
+/// The admin sees all webhooks, teachers the webhooks of their workshops.
+pub fn get_by_user(conn: &MysqlConnection, user: &User) -> Result<Vec<Webhook>, Error> {
+    if user.username == "admin" {
+        return webhooks_t.order(wh_id).get_results(conn);
+    }
+    let workshop_ids = workshoplist_t
+        .filter(wsl_user.eq(user.id).and(wsl_role.eq(Role::Teacher)))
+        .select(wsl_ws.nullable());
+    webhooks_t
+        .filter(wh_workshop.eq_any(workshop_ids))
+        .order(wh_id)
+        .get_results(conn)
+}
+
+/// Get events a webhook is subscribed to.
+pub fn get_events(conn: &MysqlConnection, webhook_id: u64) -> Result<Vec<WebhookEventKind>, Error> {
+    webhookevents_t
+        .filter(we_webhook.eq(webhook_id))
+        .select(we_event)
+        .get_results(conn)
+}
+
+/// Create a webhook for a workshop or a global one.
+pub fn create(
+    conn: &MysqlConnection,
+    user: &User,
+    workshop_id: Option<u64>,
+    url: String,
+    secret: String,
+    events: Vec<WebhookEventKind>,
+) -> Result<Webhook, DbError> {
+    if !can_manage(conn, user, workshop_id) {
+        return Err(DbError::new(
+            DbErrorKind::NotFound,
+            match workshop_id {
+                Some(workshop_id) => format!("Teacher {} not in Workshop {}", user.id, workshop_id),
+                None => String::from("Global Webhooks can only be created by the admin"),
+            },
+        ));
+    }
+
+    let new_webhook = NewWebhook {
+        owner: user.id,
+        workshop: workshop_id,
+        url,
+        secret,
+        active: true,
+        created: Local::now().naive_local(),
+    };
+    let mut t_error: Result<(), DbError> = Ok(());
+    let webhook = conn.transaction::<Webhook, Error, _>(|| {
+        let insert = diesel::insert_into(webhooks_t)
+            .values(&new_webhook)
+            .execute(conn);
+        if insert.is_err() {
+            return DbError::assign_and_rollback(
+                &mut t_error,
+                DbError::new(DbErrorKind::CreateFailed, "Webhook Insert failed"),
+            );
+        }
+        let webhook: Webhook = webhooks_t.order(wh_id.desc()).first(conn).unwrap();
+        if let Err(err) = relate_events(conn, webhook.id, events) {
+            return DbError::assign_and_rollback(&mut t_error, err);
+        }
+        Ok(webhook)
+    });
+
+    match webhook {
+        Ok(webhook) => Ok(webhook),
+        Err(_) => Err(t_error.err().unwrap_or(DbError::new(
+            DbErrorKind::TransactionFailed,
+            "Unknown error",
+        ))),
+    }
+}
+
+/// Update url, events & state of a webhook.
+/// The secret is only replaced if a new one is given.
+pub fn update(
+    conn: &MysqlConnection,
+    user: &User,
+    webhook_id: u64,
+    url: String,
+    secret: Option<String>,
+    events: Vec<WebhookEventKind>,
+    active: bool,
+) -> Result<(), DbError> {
+    let webhook = get_managed(conn, user, webhook_id);
+    if let Err(err) = webhook {
+        return Err(err);
+    }
+    let webhook = webhook.unwrap();
+
+    let mut t_error: Result<(), DbError> = Ok(());
+    let update = conn.transaction::<(), Error, _>(|| {
+        let update = diesel::update(webhooks_t.filter(wh_id.eq(webhook.id)))
+            .set((
+                wh_url.eq(url.clone()),
+                wh_secret.eq(secret.clone().unwrap_or(webhook.secret.clone())),
+                wh_active.eq(active),
+            ))
+            .execute(conn);
+        if update.is_err() {
+            return DbError::assign_and_rollback(
+                &mut t_error,
+                DbError::new(DbErrorKind::UpdateFailed, "Webhook Update failed"),
+            );
+        }
+        let delete =
+            diesel::delete(webhookevents_t.filter(we_webhook.eq(webhook.id))).execute(conn);
+        if delete.is_err() {
+            return DbError::assign_and_rollback(
+                &mut t_error,
+                DbError::new(DbErrorKind::DeleteFailed, "Webhook Event Delete failed"),
+            );
+        }
+        if let Err(err) = relate_events(conn, webhook.id, events.clone()) {
+            return DbError::assign_and_rollback(&mut t_error, err);
+        }
+        Ok(())
+    });
+
+    match update {
+        Ok(_) => Ok(()),
+        Err(_) => Err(t_error.err().unwrap_or(DbError::new(
+            DbErrorKind::TransactionFailed,
+            "Unknown error",
+        ))),
+    }
+}
+
+/// Delete a webhook with its delivery log.
+pub fn delete(conn: &MysqlConnection, user: &User, webhook_id: u64) -> Result<(), DbError> {
+    let webhook = get_managed(conn, user, webhook_id);
+    if let Err(err) = webhook {
+        return Err(err);
+    }
+    let webhook = webhook.unwrap();
+    let delete = diesel::delete(webhooks_t.filter(wh_id.eq(webhook.id))).execute(conn);
+    if delete.is_err() {
+        return Err(DbError::new(
+            DbErrorKind::DeleteFailed,
+            "Webhook Delete failed",
+        ));
+    }
+    Ok(())
+}
+
+/// Get the latest deliveries of a webhook.
+pub fn get_deliveries(
+    conn: &MysqlConnection,
+    user: &User,
+    webhook_id: u64,
+) -> Result<Vec<WebhookDelivery>, DbError> {
+    let webhook = get_managed(conn, user, webhook_id);
+    if let Err(err) = webhook {
+        return Err(err);
+    }
+    let webhook = webhook.unwrap();
+    let deliveries = webhookdeliveries_t
+        .filter(wd_webhook.eq(webhook.id))
+        .order(wd_id.desc())
+        .limit(DELIVERY_LOG_LIMIT)
+        .get_results(conn);
+    if deliveries.is_err() {
+        return Err(DbError::new(
+            DbErrorKind::ReadFailed,
+            format!("Could not get Deliveries of Webhook {}", webhook_id),
+        ));
+    }
+    Ok(deliveries.unwrap())
+}
+
+// Get webhook that can be managed by the user.
+fn get_managed(conn: &MysqlConnection, user: &User, webhook_id: u64) -> Result<Webhook, DbError> {
+    let webhook = get_by_id(conn, webhook_id);
+    match webhook {
+        Ok(webhook) if can_manage(conn, user, webhook.workshop) => Ok(webhook),
+        _ => Err(DbError::new(
+            DbErrorKind::NotFound,
+            format!("Webhook {} not found", webhook_id),
+        )),
+    }
+}
+
+// Subscribe webhook to events.
+fn relate_events(
+    conn: &MysqlConnection,
+    webhook_id: u64,
+    events: Vec<WebhookEventKind>,
+) -> Result<(), DbError> {
+    let new_events: Vec<WebhookEvent> = events
+        .into_iter()
+        .map(|event| WebhookEvent {
+            webhook: webhook_id,
+            event,
+        })
+        .collect();
+    let insert = diesel::insert_or_ignore_into(webhookevents_t)
+        .values(&new_events)
+        .execute(conn);
+    if insert.is_err() {
+        return Err(DbError::new(
+            DbErrorKind::CreateFailed,
+            "Webhook Event Insert failed",
+        ));
+    }
+    Ok(())
+}
+
+/// Queue deliveries of an event for all active webhooks of the workshop and all global webhooks.
+pub fn trigger(
+    conn: &MysqlConnection,
+    workshop_id: u64,
+    event: WebhookEventKind,
+    data: Value,
+) -> Result<(), DbError> {
+    let webhook_ids = webhooks_t
+        .inner_join(webhookevents_t.on(we_webhook.eq(wh_id)))
+        .filter(
+            wh_active
+                .eq(true)
+                .and(we_event.eq(event.clone()))
+                .and(wh_workshop.eq(workshop_id).or(wh_workshop.is_null())),
+        )
+        .select(wh_id)
+        .get_results::<u64>(conn);
+    if webhook_ids.is_err() {
+        return Err(DbError::new(
+            DbErrorKind::ReadFailed,
+            "Could not query Webhooks",
+        ));
+    }
+    let webhook_ids = webhook_ids.unwrap();
+    if webhook_ids.is_empty() {
+        return Ok(());
+    }
+
+    let now = Local::now().naive_local();
+    let payload = json!({
+        "event": event,
+        "workshop": workshop_id,
+        "date": now,
+        "data": data,
+    })
+    .to_string();
+    let new_deliveries: Vec<NewWebhookDelivery> = webhook_ids
+        .into_iter()
+        .map(|webhook_id| NewWebhookDelivery {
+            webhook: webhook_id,
+            event: event.clone(),
+            payload: payload.clone(),
+            created: now,
+            nextattempt: now,
+        })
+        .collect();
+    let insert = diesel::insert_into(webhookdeliveries_t)
+        .values(&new_deliveries)
+        .execute(conn);
+    if insert.is_err() {
+        return Err(DbError::new(
+            DbErrorKind::CreateFailed,
+            "Webhook Delivery Insert failed",
+        ));
+    }
+    Ok(())
+}
+
+/// Queue `submission.created` event.
+pub fn submission_created(conn: &MysqlConnection, submission: &Submission) -> Result<(), DbError> {
+    trigger(
+        conn,
+        submission.workshop,
+        WebhookEventKind::SubmissionCreated,
+        json!({
+            "submission": submission.id,
+            "title": submission.title,
+            "student": submission.student,
+            "team": submission.team,
+            "round": submission.round,
+            "late": submission.late,
+            "date": submission.date,
+        }),
+    )
+}
+
+/// Queue `review.completed` event.
+pub fn review_completed(conn: &MysqlConnection, review: &Review) -> Result<(), DbError> {
+    trigger(
+        conn,
+        review.workshop,
+        WebhookEventKind::ReviewCompleted,
+        json!({
+            "review": review.id,
+            "submission": review.submission,
+            "reviewer": review.reviewer,
+            "teacher": review.teacher,
+        }),
+    )
+}
+
+/// Queue `submission.graded` event with the final points of a submission.
+pub fn submission_graded(conn: &MysqlConnection, submission_id: u64) -> Result<(), DbError> {
+    let submission = db::submissions::get_by_id(conn, submission_id);
+    if submission.is_err() {
+        return Err(DbError::new(
+            DbErrorKind::NotFound,
+            format!("Submission {} not found", submission_id),
+        ));
+    }
+    let submission = submission.unwrap();
+    let owners = db::submissions::get_owner_ids(conn, &submission);
+    if owners.is_err() {
+        return Err(DbError::new(
+            DbErrorKind::ReadFailed,
+            format!("Owners of Submission {} not found", submission.id),
+        ));
+    }
+    trigger(
+        conn,
+        submission.workshop,
+        WebhookEventKind::SubmissionGraded,
+        json!({
+            "submission": submission.id,
+            "students": owners.unwrap(),
+            "team": submission.team,
+            "points": submission.meanpoints,
+            "maxPoints": submission.maxpoint,
+            "error": submission.error,
+        }),
+    )
+}
+
+/// Queue `workshop.updated` event.
+pub fn workshop_updated(conn: &MysqlConnection, workshop: &Workshop) -> Result<(), DbError> {
+    trigger(
+        conn,
+        workshop.id,
+        WebhookEventKind::WorkshopUpdated,
+        json!({
+            "title": workshop.title,
+            "end": workshop.end,
+            "anonymous": workshop.anonymous,
+        }),
+    )
+}
+
+/// Get ids of deliveries that are due to be sent.
+pub fn get_pending_ids(conn: &MysqlConnection) -> Result<Vec<u64>, Error> {
+    let now = Local::now().naive_local();
+    webhookdeliveries_t
+        .filter(
+            wd_delivered
+                .eq(false)
+                .and(wd_failed.eq(false))
+                .and(wd_nextattempt.le(now)),
+        )
+        .order(wd_id)
+        .select(wd_id)
+        .get_results(conn)
+}
+
+/// Lock a delivery that is still pending.
+/// Needs to be called inside a transaction.
+pub fn lock_pending(conn: &MysqlConnection, delivery_id: u64) -> Result<WebhookDelivery, Error> {
+    webhookdeliveries_t
+        .filter(
+            wd_id
+                .eq(delivery_id)
+                .and(wd_delivered.eq(false))
+                .and(wd_failed.eq(false)),
+        )
+        .for_update()
+        .first(conn)
+}
+
+/// Mark delivery as delivered.
+pub fn mark_delivered(
+    conn: &MysqlConnection,
+    delivery: &WebhookDelivery,
+    status: i32,
+) -> Result<(), DbError> {
+    let update = diesel::update(webhookdeliveries_t.filter(wd_id.eq(delivery.id)))
+        .set((
+            wd_delivered.eq(true),
+            wd_attempts.eq(delivery.attempts + 1),
+            wd_status.eq(Some(status)),
+            wd_lasterror.eq(None::<String>),
+            wd_lastattempt.eq(Some(Local::now().naive_local())),
+        ))
+        .execute(conn);
+    if update.is_err() {
+        return Err(DbError::new(
+            DbErrorKind::UpdateFailed,
+            "Webhook Delivery Update failed",
+        ));
+    }
+    Ok(())
+}
+
+/// Mark failed attempt of a delivery.
+/// The next attempt is delayed by the backoff of the queue, after `max_attempts` the delivery is given up.
+pub fn mark_failed(
+    conn: &MysqlConnection,
+    delivery: &WebhookDelivery,
+    status: Option<i32>,
+    error: String,
+    max_attempts: i32,
+) -> Result<(), DbError> {
+    let attempts = delivery.attempts + 1;
+    let now = Local::now().naive_local();
+    let update = diesel::update(webhookdeliveries_t.filter(wd_id.eq(delivery.id)))
+        .set((
+            wd_attempts.eq(attempts),
+            wd_failed.eq(attempts >= max_attempts),
+            wd_status.eq(status),
+            wd_lasterror.eq(Some(error)),
+            wd_nextattempt.eq(now + queue::backoff(attempts)),
+            wd_lastattempt.eq(Some(now)),
+        ))
+        .execute(conn);
+    if update.is_err() {
+        return Err(DbError::new(
+            DbErrorKind::UpdateFailed,
+            "Webhook Delivery Update failed",
+        ));
+    }
+    Ok(())
+}
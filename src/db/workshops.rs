@@ -3,10 +3,20 @@
 use crate::db;
 use crate::db::error::{DbError, DbErrorKind};
 use crate::db::models::*;
+use crate::events::{self, Event, EventKind};
+use crate::mail;
 use crate::schema::criteria::dsl::{
     criteria as criteria_t, criterion as criteria_criterion, workshop as criteria_workshop,
 };
 use crate::schema::criterion::dsl::{criterion as criterion_t, id as c_id};
+use crate::schema::reviews::dsl::{
+    deadline as reviews_deadline, locked as reviews_locked, reviews as reviews_t,
+    submission as reviews_sub,
+};
+use crate::schema::submissions::dsl::{
+    calibration as sub_calibration, deadline as sub_deadline, id as sub_id,
+    reviewsdone as sub_reviews_done, submissions as submissions_t, workshop as sub_ws,
+};
 use crate::schema::users::dsl::{
     firstname as u_firstname, id as u_id, lastname as u_lastname, role as u_role, unit as u_unit,
     users as users_t,
@@ -21,6 +31,8 @@ use crate::schema::workshops::dsl::{
     anonymous as ws_anonymous, id as ws_id, reviewtimespan as ws_reviewtimespan,
     workshops as workshops_t,
 };
+use crate::utils::error::AppError;
+use chrono::Local;
 use diesel::prelude::*;
 use diesel::result::Error;
 
@@ -56,8 +68,12 @@ pub fn get_by_submission_id(conn: &MysqlConnection, submission_id: u64) -> Resul
         .first(conn)
 }
 
+/// Get workshop by workshop id.
+pub fn get_by_id(conn: &MysqlConnection, workshop_id: u64) -> Result<Workshop, Error> {
+    workshops_t.filter(ws_id.eq(workshop_id)).first(conn)
+}
+
 /// Get workshop by review id.
-#[allow(dead_code)]
 pub fn get_by_review_id(conn: &MysqlConnection, review_id: u64) -> Result<Workshop, Error> {
     let review = db::reviews::get_by_id(conn, review_id);
     if review.is_err() {
@@ -71,24 +87,44 @@ pub fn get_by_review_id(conn: &MysqlConnection, review_id: u64) -> Result<Worksh
 pub fn create<'a>(
     conn: &MysqlConnection,
     teacher_id: u64,
-    title: String,
-    content: String,
-    end: chrono::NaiveDateTime,
-    review_timespan: i64,
+    settings: WorkshopSettings,
     anonymous: bool,
     teachers: Vec<u64>,
     students: Vec<u64>,
-    criteria: Vec<NewCriterion>,
+    criteria: Vec<NewWorkshopCriterion>,
     attachments: Vec<u64>,
 ) -> Result<Workshop, DbError> {
     let new_workshop = NewWorkshop {
-        title,
-        content,
-        end,
-        reviewtimespan: review_timespan,
+        title: settings.title,
+        content: settings.content,
+        end: settings.end,
+        reviewtimespan: settings.review_timespan,
+        teacherweight: settings.teacher_weight,
+        aggregation: settings.aggregation,
+        gradescale: settings.grade_scale,
+        commentthreshold: settings.comment_threshold,
+        draftpolicy: settings.draft_policy,
+        reviewingweight: settings.reviewing_weight,
+        appealwindow: settings.appeal_window,
+        selfassessment: settings.self_assessment,
+        submissionstart: settings.submission_start,
+        submissionend: settings.submission_end,
+        reviewend: settings.review_end,
+        resultsdate: settings.results_date,
+        latepolicy: settings.late_policy,
+        latepenalty: settings.late_penalty,
+        rounds: settings.rounds,
+        samereviewers: settings.same_reviewers,
+        roundpolicy: settings.round_policy,
         anonymous,
     };
 
+    // Rubric levels are inserted after their criteria
+    let (criteria, levels): (Vec<NewCriterion>, Vec<Vec<NewRubricLevel>>) = criteria
+        .into_iter()
+        .map(|criterion| (criterion.criterion, criterion.levels))
+        .unzip();
+
     let mut t_error: Result<(), DbError> = Ok(());
     let ws = conn.transaction::<Workshop, _, _>(|| {
         // Filter students & teachers
@@ -130,6 +166,13 @@ pub fn create<'a>(
         last_criterion_id += 1;
         let first_criterion_id = last_criterion_id - criteria.len() as u64;
         let criterion_ids: Vec<u64> = (first_criterion_id..last_criterion_id).collect();
+        let levels_insert = db::criteria::insert_levels(conn, &criterion_ids, levels);
+        if levels_insert.is_err() {
+            return DbError::assign_and_rollback(
+                &mut t_error,
+                DbError::new(DbErrorKind::CreateFailed, "Could not insert Rubric Levels"),
+            );
+        }
         // Insert workshop
         let insert = diesel::insert_into(workshops_t)
             .values(&new_workshop)
@@ -141,6 +184,25 @@ pub fn create<'a>(
             );
         }
         let workshop: Workshop = workshops_t.order(ws_id.desc()).first(conn).unwrap();
+        // Notify students, notifications are optional so errors do not fail the creation
+        for student in &students {
+            let email = mail::templates::workshop_created(&student.firstname, &workshop.title);
+            if let Err(err) = db::emails::enqueue(conn, student, email) {
+                err.print_stacktrace();
+            }
+        }
+        let student_ids: Vec<u64> = students.iter().map(|student| student.id).collect();
+        if let Err(err) = db::notifications::create(
+            conn,
+            &student_ids,
+            NotificationKind::Workshop,
+            "New workshop".to_string(),
+            format!("You were added to the workshop \"{}\"", workshop.title),
+            Some(workshop.id),
+            None,
+        ) {
+            err.print_stacktrace();
+        }
         // Assign students & teachers to workshop
         let mut new_workshoplist = students;
         new_workshoplist.append(&mut teachers);
@@ -215,7 +277,10 @@ pub fn create<'a>(
         Ok(workshop)
     });
     match ws {
-        Ok(ws) => Ok(ws),
+        Ok(ws) => {
+            publish_workshop_changed(conn, ws.id);
+            Ok(ws)
+        }
         Err(_) => Err(t_error.err().unwrap_or(DbError::new(
             DbErrorKind::TransactionFailed,
             "Unknown error",
@@ -228,13 +293,10 @@ pub fn update(
     conn: &MysqlConnection,
     teacher_id: u64,
     workshop_id: u64,
-    title: String,
-    content: String,
-    end: chrono::NaiveDateTime,
-    review_timespan: i64,
+    settings: WorkshopSettings,
     teachers: Vec<u64>,
     students: Vec<u64>,
-    criteria: Vec<NewCriterion>,
+    criteria: Vec<NewWorkshopCriterion>,
     attachments: Vec<u64>,
 ) -> Result<Workshop, DbError> {
     let workshop = workshops_t.filter(ws_id.eq(workshop_id)).first(conn);
@@ -245,10 +307,33 @@ pub fn update(
         ));
     }
     let mut workshop: Workshop = workshop.unwrap();
-    workshop.title = title;
-    workshop.content = content;
-    workshop.end = end;
-    workshop.reviewtimespan = review_timespan;
+    workshop.title = settings.title;
+    workshop.content = settings.content;
+    workshop.end = settings.end;
+    workshop.reviewtimespan = settings.review_timespan;
+    workshop.teacherweight = settings.teacher_weight;
+    workshop.aggregation = settings.aggregation;
+    workshop.gradescale = settings.grade_scale;
+    workshop.commentthreshold = settings.comment_threshold;
+    workshop.draftpolicy = settings.draft_policy;
+    workshop.reviewingweight = settings.reviewing_weight;
+    workshop.appealwindow = settings.appeal_window;
+    workshop.selfassessment = settings.self_assessment;
+    workshop.submissionstart = settings.submission_start;
+    workshop.submissionend = settings.submission_end;
+    workshop.reviewend = settings.review_end;
+    workshop.resultsdate = settings.results_date;
+    workshop.latepolicy = settings.late_policy;
+    workshop.latepenalty = settings.late_penalty;
+    workshop.rounds = settings.rounds;
+    workshop.samereviewers = settings.same_reviewers;
+    workshop.roundpolicy = settings.round_policy;
+
+    // Rubric levels are inserted after their criteria
+    let (criteria, levels): (Vec<NewCriterion>, Vec<Vec<NewRubricLevel>>) = criteria
+        .into_iter()
+        .map(|criterion| (criterion.criterion, criterion.levels))
+        .unzip();
 
     let mut t_error: Result<(), DbError> = Ok(());
     let ws = conn.transaction::<Workshop, _, _>(|| {
@@ -321,6 +406,13 @@ pub fn update(
         last_criterion_id += 1;
         let first_criterion_id = last_criterion_id - criteria.len() as u64;
         let criterion_ids: Vec<u64> = (first_criterion_id..last_criterion_id).collect();
+        let levels_insert = db::criteria::insert_levels(conn, &criterion_ids, levels);
+        if levels_insert.is_err() {
+            return DbError::assign_and_rollback(
+                &mut t_error,
+                DbError::new(DbErrorKind::CreateFailed, "Could not insert Rubric Levels"),
+            );
+        }
 
         // Update workshop
         let update = diesel::update(workshops_t.filter(ws_id.eq(workshop.id)))
@@ -332,6 +424,26 @@ pub fn update(
                 DbError::new(DbErrorKind::UpdateFailed, "Could not update Workshop"),
             );
         }
+        // Open reviews follow the review phase of the workshop
+        if let Some(review_end) = workshop.reviewend {
+            if let Err(err) = update_review_deadlines(conn, workshop.id, review_end) {
+                return DbError::assign_and_rollback(&mut t_error, err);
+            }
+        }
+
+        // Notify students, notifications are optional so errors do not fail the update
+        let student_ids: Vec<u64> = students.iter().map(|student| student.id).collect();
+        if let Err(err) = db::notifications::create(
+            conn,
+            &student_ids,
+            NotificationKind::Workshop,
+            "Workshop updated".to_string(),
+            format!("The workshop \"{}\" was updated", workshop.title),
+            Some(workshop.id),
+            None,
+        ) {
+            err.print_stacktrace();
+        }
 
         // Assign students & teachers to workshop
         let mut new_workshoplist = students;
@@ -407,7 +519,13 @@ pub fn update(
     });
 
     match ws {
-        Ok(ws) => Ok(ws),
+        Ok(ws) => {
+            publish_workshop_changed(conn, ws.id);
+            if let Err(err) = db::webhooks::workshop_updated(conn, &ws) {
+                err.print_stacktrace();
+            }
+            Ok(ws)
+        }
         Err(_) => Err(t_error.err().unwrap_or(DbError::new(
             DbErrorKind::TransactionFailed,
             "Unknown error",
@@ -415,6 +533,25 @@ pub fn update(
     }
 }
 
+// Publish change of a workshop to all of its members.
+fn publish_workshop_changed(conn: &MysqlConnection, workshop_id: u64) {
+    let members = workshoplist_t
+        .filter(wsl_ws.eq(workshop_id))
+        .select(wsl_user)
+        .get_results::<u64>(conn);
+    if let Ok(members) = members {
+        events::publish(
+            &members,
+            Event {
+                kind: EventKind::WorkshopChanged,
+                workshop: Some(workshop_id),
+                submission: None,
+                review: None,
+            },
+        );
+    }
+}
+
 /// Delete existing workshop.
 pub fn delete(conn: &MysqlConnection, id: u64) -> Result<(), ()> {
     let workshop: Result<Workshop, diesel::result::Error> =
@@ -447,6 +584,22 @@ pub fn student_in_workshop(conn: &MysqlConnection, student_id: u64, workshop_id:
     }
 }
 
+/// Check if teacher is part of a workshop.
+pub fn teacher_in_workshop(conn: &MysqlConnection, teacher_id: u64, workshop_id: u64) -> bool {
+    let exists: Result<Workshoplist, diesel::result::Error> = workshoplist_t
+        .filter(
+            wsl_ws
+                .eq(workshop_id)
+                .and(wsl_user.eq(teacher_id).and(wsl_role.eq(Role::Teacher))),
+        )
+        .first(conn);
+    if exists.is_ok() {
+        true
+    } else {
+        false
+    }
+}
+
 // Gets students/teachers of a workshop.
 fn roles_in_workshop(
     conn: &MysqlConnection,
@@ -507,7 +660,6 @@ pub fn students_in_workshop(
 }
 
 /// Gets teachers of a workshop.
-#[allow(dead_code)]
 pub fn teachers_in_workshop(
     conn: &MysqlConnection,
     workshop_id: u64,
@@ -555,6 +707,13 @@ pub fn get_teacher_workshop(
     if criteria.is_err() {
         return Err(DbError::new(DbErrorKind::ReadFailed, "Criteria not found"));
     }
+    let criteria = db::criteria::get_full(conn, criteria.unwrap());
+    if criteria.is_err() {
+        return Err(DbError::new(
+            DbErrorKind::ReadFailed,
+            "Rubric Levels not found",
+        ));
+    }
     let criteria = criteria.unwrap();
 
     let attachments = db::attachments::get_by_workshop_id(conn, workshop_id);
@@ -566,12 +725,45 @@ pub fn get_teacher_workshop(
     }
     let attachments = attachments.unwrap();
 
+    let extensions = db::extensions::get_history(conn, workshop_id);
+    if let Err(err) = extensions {
+        return Err(err);
+    }
+    let extensions = extensions.unwrap();
+
+    let teams = db::teams::get_workshop_teams(conn, workshop_id);
+    if let Err(err) = teams {
+        return Err(err);
+    }
+    let teams = teams.unwrap();
+
+    let phase = workshop.phase(Local::now().naive_local());
     Ok(TeacherWorkshop {
         title: workshop.title,
         content: workshop.content,
         end: workshop.end,
         review_timespan: workshop.reviewtimespan,
+        teacher_weight: workshop.teacherweight,
+        aggregation: workshop.aggregation,
+        grade_scale: workshop.gradescale,
+        comment_threshold: workshop.commentthreshold,
+        draft_policy: workshop.draftpolicy,
+        reviewing_weight: workshop.reviewingweight,
+        appeal_window: workshop.appealwindow,
+        self_assessment: workshop.selfassessment,
+        submission_start: workshop.submissionstart,
+        submission_end: workshop.submissionend,
+        review_end: workshop.reviewend,
+        results_date: workshop.resultsdate,
+        phase,
+        late_policy: workshop.latepolicy,
+        late_penalty: workshop.latepenalty,
+        rounds: workshop.rounds,
+        same_reviewers: workshop.samereviewers,
+        round_policy: workshop.roundpolicy,
+        extensions,
         anonymous: workshop.anonymous,
+        teams,
         students,
         teachers,
         criteria,
@@ -579,6 +771,124 @@ pub fn get_teacher_workshop(
     })
 }
 
+/// Get the grades of all students from a workshop.
+/// Points are the final grade of every student, the same value students see in their results.
+pub fn get_grade_export(
+    conn: &MysqlConnection,
+    teacher_id: u64,
+    workshop_id: u64,
+) -> Result<GradeExport, DbError> {
+    if !teacher_in_workshop(conn, teacher_id, workshop_id) {
+        return Err(DbError::new(
+            DbErrorKind::NotFound,
+            format!("Teacher {} not in Workshop {}", teacher_id, workshop_id),
+        ));
+    }
+    let workshop = get_teacher_workshop(conn, workshop_id);
+    if let Err(err) = workshop {
+        return Err(err);
+    }
+    let workshop = workshop.unwrap();
+    let criteria: Vec<Criterion> = workshop
+        .criteria
+        .into_iter()
+        .map(|criterion| criterion.criterion)
+        .filter(|criterion| criterion.kind.is_scored())
+        .collect();
+
+    let mut rows: Vec<GradeRow> = Vec::new();
+    for student in workshop.students {
+        // Only the latest submission of the student or the team counts
+        let latest = student
+            .submissions
+            .unwrap_or_default()
+            .into_iter()
+            .max_by(|a, b| (a.round, a.date).cmp(&(b.round, b.date)));
+        let submission = match latest {
+            Some(latest) => match db::submissions::get_by_id(conn, latest.id) {
+                Ok(submission) => Some(submission),
+                Err(_) => {
+                    return Err(DbError::new(
+                        DbErrorKind::ReadFailed,
+                        format!("Submission {} not found", latest.id),
+                    ))
+                }
+            },
+            None => None,
+        };
+
+        // Mean points per criterion of the reviews that are part of the points
+        let criterion_means: Vec<Option<f64>> = match &submission {
+            Some(submission) if submission.reviewsdone => {
+                let reviews = db::reviews::get_simple_review_points(conn, submission.id);
+                if let Err(err) = reviews {
+                    return Err(err);
+                }
+                let reviews = reviews.unwrap();
+                criteria
+                    .iter()
+                    .map(|criterion| {
+                        let points: Vec<f64> = reviews
+                            .iter()
+                            .flat_map(|review| review.points.iter())
+                            .filter(|points| points.criterion == criterion.id)
+                            .map(|points| points.points)
+                            .collect();
+                        if points.is_empty() {
+                            None
+                        } else {
+                            Some(points.iter().sum::<f64>() / points.len() as f64)
+                        }
+                    })
+                    .collect()
+            }
+            _ => vec![None; criteria.len()],
+        };
+
+        let counts = db::reviews::get_review_counts(conn, workshop_id, student.id);
+        if let Err(err) = counts {
+            return Err(err);
+        }
+        let (reviews_written, reviews_missed) = counts.unwrap();
+
+        // Final points, round points or points, like the results of the student
+        let points = submission
+            .as_ref()
+            .and_then(|s| db::submissions::get_grade(conn, s, Some(student.id)));
+        let max_points = submission.as_ref().and_then(|s| s.maxpoint);
+        rows.push(GradeRow {
+            student: student.id,
+            firstname: student.firstname,
+            lastname: student.lastname,
+            group: student.group,
+            submission: submission.as_ref().map(|s| s.title.clone()),
+            points,
+            max_points,
+            percentage: match (points, max_points) {
+                (Some(points), Some(max_points)) if max_points > 0.0 => {
+                    Some(points / max_points * 100.0)
+                }
+                _ => None,
+            },
+            criteria: criterion_means,
+            reviews_written,
+            reviews_missed,
+            reviews_done: submission.as_ref().map_or(false, |s| s.reviewsdone),
+            error: submission.as_ref().map_or(false, |s| s.error),
+        });
+    }
+    rows.sort_by(|a, b| (&a.lastname, &a.firstname).cmp(&(&b.lastname, &b.firstname)));
+
+    Ok(GradeExport {
+        title: workshop.title,
+        criteria: criteria
+            .into_iter()
+            .map(|criterion| criterion.title)
+            .collect(),
+        rows,
+    })
+}
+
 /// Get student workshop by workshop id.
 pub fn get_student_workshop(
     conn: &MysqlConnection,
@@ -617,6 +927,19 @@ pub fn get_student_workshop(
         ));
     }
     let submissions = submissions.unwrap();
+    // Students added after calibration submissions need their calibration reviews
+    if let Err(err) = db::calibration::assign(conn, workshop_id, student_id) {
+        return Err(err);
+    }
+    let calibration_done = db::calibration::is_done(conn, workshop_id, student_id);
+    let extension = db::extensions::get_submission_extension(conn, workshop_id, student_id);
+    let team = match db::teams::get_student_team(conn, workshop_id, student_id) {
+        Some(team) => match db::teams::get_workshop_team(conn, team) {
+            Ok(team) => Some(team),
+            Err(err) => return Err(err),
+        },
+        None => None,
+    };
     let reviews = db::reviews::get_student_workshop_reviews(conn, workshop_id, student_id);
     if reviews.is_err() {
         return Err(DbError::new(DbErrorKind::ReadFailed, "Reviews not found"));
@@ -632,11 +955,29 @@ pub fn get_student_workshop(
     }
     let attachments = attachments.unwrap();
 
+    let phase = workshop.phase(Local::now().naive_local());
     Ok(StudentWorkshop {
         title: workshop.title,
         content: workshop.content,
         end: workshop.end,
+        grade_scale: workshop.gradescale,
+        comment_threshold: workshop.commentthreshold,
+        appeal_window: workshop.appealwindow,
+        self_assessment: workshop.selfassessment,
+        submission_start: workshop.submissionstart,
+        submission_end: workshop.submissionend,
+        review_end: workshop.reviewend,
+        results_date: workshop.resultsdate,
+        phase,
+        late_policy: workshop.latepolicy,
+        late_penalty: workshop.latepenalty,
+        rounds: workshop.rounds,
+        same_reviewers: workshop.samereviewers,
+        round_policy: workshop.roundpolicy,
+        extension,
+        calibration_done,
         anonymous: workshop.anonymous,
+        team,
         students,
         teachers,
         submissions,
@@ -684,3 +1025,96 @@ pub fn get_review_timespan(
     let review_timespan = chrono::Duration::minutes(minutes.unwrap());
     Ok(review_timespan)
 }
+
+/// Check if submissions can be created or updated in the current phase of the workshop.
+/// The submission phase ends with the workshop unless an explicit end or an extension is given.
+/// Returns if the submission is late, which is only possible with the accept late policy.
+pub fn check_submission_phase(
+    workshop: &Workshop,
+    extension: Option<chrono::NaiveDateTime>,
+    now: chrono::NaiveDateTime,
+) -> Result<bool, DbError> {
+    if let Some(start) = workshop.submissionstart {
+        if now < start {
+            return Err(DbError::new(
+                DbErrorKind::NotStarted,
+                format!("Submission phase starts at {}", start),
+            ));
+        }
+    }
+    let end = extension.unwrap_or(workshop.submissionend.unwrap_or(workshop.end));
+    if now <= end {
+        return Ok(false);
+    }
+    match workshop.latepolicy {
+        LatePolicy::Accept => Ok(true),
+        LatePolicy::Block => Err(DbError::new(
+            DbErrorKind::PastDeadline,
+            format!("Submission phase ended at {}", end),
+        )),
+    }
+}
+
+/// Check if students can see the points and received reviews of the workshop.
+pub fn check_results_released(
+    workshop: &Workshop,
+    now: chrono::NaiveDateTime,
+) -> Result<(), DbError> {
+    if workshop.results_released(now) {
+        Ok(())
+    } else {
+        Err(DbError::new(
+            DbErrorKind::NotFinished,
+            "Results are not released yet",
+        ))
+    }
+}
+
+// Move the deadline of unfinished submissions and their reviews to the end of the review phase.
+fn update_review_deadlines(
+    conn: &MysqlConnection,
+    workshop_id: u64,
+    review_end: chrono::NaiveDateTime,
+) -> Result<(), DbError> {
+    let submission_ids = submissions_t
+        .filter(
+            sub_ws
+                .eq(workshop_id)
+                .and(sub_reviews_done.eq(false))
+                .and(sub_calibration.eq(false)),
+        )
+        .select(sub_id)
+        .get_results::<u64>(conn);
+    if submission_ids.is_err() {
+        return Err(DbError::new(
+            DbErrorKind::ReadFailed,
+            format!("Submissions of Workshop {} not found", workshop_id),
+        ));
+    }
+    let submission_ids = submission_ids.unwrap();
+    let update = diesel::update(submissions_t.filter(sub_id.eq_any(&submission_ids)))
+        .set(sub_deadline.eq(review_end))
+        .execute(conn);
+    if update.is_err() {
+        return Err(DbError::new(
+            DbErrorKind::UpdateFailed,
+            "Submission Deadline Update failed",
+        ));
+    }
+    let update = diesel::update(
+        reviews_t.filter(
+            reviews_sub
+                .eq_any(&submission_ids)
+                .and(reviews_locked.eq(false)),
+        ),
+    )
+    .set(reviews_deadline.eq(review_end))
+    .execute(conn);
+    if update.is_err() {
+        return Err(DbError::new(
+            DbErrorKind::UpdateFailed,
+            "Review Deadline Update failed",
+        ));
+    }
+    Ok(())
+}
@@ -0,0 +1,148 @@
+//! Internal event bus for live updates.
+//! The db layer publishes events after successful operations,
+//! every subscribed user receives the events addressed to them.
+
+mod stream;
+
+pub use stream::*;
+
+use std::sync::mpsc::{sync_channel, Receiver, SyncSender, TrySendError};
+use std::sync::Mutex;
+
+/// Pending events per subscriber, further events are dropped for slow subscribers.
+const SUBSCRIBER_BUFFER: usize = 64;
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EventKind {
+    ReviewAssigned,
+    ReviewSubmitted,
+    SubmissionGraded,
+    WorkshopChanged,
+}
+
+impl EventKind {
+    pub fn to_string(&self) -> String {
+        match self {
+            EventKind::ReviewAssigned => String::from("reviewassigned"),
+            EventKind::ReviewSubmitted => String::from("reviewsubmitted"),
+            EventKind::SubmissionGraded => String::from("submissiongraded"),
+            EventKind::WorkshopChanged => String::from("workshopchanged"),
+        }
+    }
+}
+
+/// Event with the ids of the entities that changed.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Event {
+    pub kind: EventKind,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub workshop: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub submission: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub review: Option<u64>,
+}
+
+struct Subscriber {
+    id: u64,
+    user: u64,
+    sender: SyncSender<Event>,
+}
+
+struct EventBus {
+    next_id: u64,
+    subscribers: Vec<Subscriber>,
+}
+
+lazy_static! {
+    static ref BUS: Mutex<EventBus> = Mutex::new(EventBus {
+        next_id: 0,
+        subscribers: Vec::new(),
+    });
+}
+
+/// Receives the events of an user until dropped.
+pub struct Subscription {
+    id: u64,
+    pub receiver: Receiver<Event>,
+}
+
+impl Drop for Subscription {
+    fn drop(&mut self) {
+        if let Ok(mut bus) = BUS.lock() {
+            bus.subscribers
+                .retain(|subscriber| subscriber.id != self.id);
+        }
+    }
+}
+
+/// Subscribe to the events of an user.
+pub fn subscribe(user_id: u64) -> Subscription {
+    let (sender, receiver) = sync_channel(SUBSCRIBER_BUFFER);
+    let mut bus = match BUS.lock() {
+        Ok(bus) => bus,
+        Err(poisoned) => poisoned.into_inner(),
+    };
+    let id = bus.next_id;
+    bus.next_id += 1;
+    bus.subscribers.push(Subscriber {
+        id,
+        user: user_id,
+        sender,
+    });
+    Subscription { id, receiver }
+}
+
+/// Publish event to the given users.
+/// Users without subscription do not receive anything.
+pub fn publish(users: &[u64], event: Event) {
+    let mut bus = match BUS.lock() {
+        Ok(bus) => bus,
+        Err(poisoned) => poisoned.into_inner(),
+    };
+    bus.subscribers.retain(|subscriber| {
+        if !users.contains(&subscriber.user) {
+            return true;
+        }
+        match subscriber.sender.try_send(event.clone()) {
+            Err(TrySendError::Disconnected(_)) => false,
+            _ => true,
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(kind: EventKind) -> Event {
+        Event {
+            kind,
+            workshop: Some(1),
+            submission: None,
+            review: None,
+        }
+    }
+
+    #[test]
+    fn publish_to_subscribed_user_ok() {
+        let subscription = subscribe(1001);
+        let other = subscribe(1002);
+        publish(&[1001], event(EventKind::WorkshopChanged));
+        assert_eq!(
+            subscription.receiver.try_recv(),
+            Ok(event(EventKind::WorkshopChanged))
+        );
+        assert!(other.receiver.try_recv().is_err());
+    }
+
+    #[test]
+    fn dropped_subscription_unsubscribes_ok() {
+        let subscription = subscribe(1003);
+        drop(subscription);
+        publish(&[1003], event(EventKind::ReviewAssigned));
+        let bus = BUS.lock().unwrap();
+        assert!(!bus.subscribers.iter().any(|s| s.user == 1003));
+    }
+}
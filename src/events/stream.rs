@@ -0,0 +1,140 @@
+use super::Subscription;
+use rocket::http::{ContentType, Status};
+use rocket::request::Request;
+use rocket::response::{Responder, Response};
+use std::io::{self, Read};
+use std::sync::mpsc::RecvTimeoutError;
+use std::time::{Duration, Instant};
+
+/// Interval of keep-alive comments, detects closed connections.
+const KEEP_ALIVE: Duration = Duration::from_secs(15);
+
+/// Lifetime of a stream, frees the worker thread. EventSource reconnects afterwards.
+const LIFETIME: Duration = Duration::from_secs(5 * 60);
+
+// Rocket fills the whole chunk before writing it to the connection.
+// Every message is therefore padded with a comment to the end of the chunk,
+// the chunk exceeds the buffer of the connection so that it is sent immediately.
+const CHUNK_SIZE: u64 = 8 * 1024 + 1;
+
+/// Server-sent events stream of a subscription.
+pub struct EventStream {
+    subscription: Subscription,
+    pending: Vec<u8>,
+    position: usize,
+    end: Instant,
+}
+
+impl EventStream {
+    pub fn new(subscription: Subscription) -> Self {
+        EventStream {
+            subscription,
+            pending: Vec::new(),
+            position: 0,
+            end: Instant::now() + LIFETIME,
+        }
+    }
+
+    // Wait for the next event and format it as server-sent event message.
+    fn next_message(&mut self) -> Option<String> {
+        let now = Instant::now();
+        if now >= self.end {
+            return None;
+        }
+        let timeout = KEEP_ALIVE.min(self.end - now);
+        match self.subscription.receiver.recv_timeout(timeout) {
+            Ok(event) => Some(format!(
+                "event: {}\ndata: {}\n\n",
+                event.kind.to_string(),
+                serde_json::to_string(&event).unwrap_or_default()
+            )),
+            Err(RecvTimeoutError::Timeout) => Some(String::from(": keep-alive\n\n")),
+            Err(RecvTimeoutError::Disconnected) => None,
+        }
+    }
+}
+
+impl Read for EventStream {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        if self.position >= self.pending.len() {
+            match self.next_message() {
+                Some(message) => {
+                    self.pending = message.into_bytes();
+                    self.position = 0;
+                }
+                None => return Ok(0),
+            }
+        }
+
+        let remaining = &self.pending[self.position..];
+        let len = remaining.len().min(buf.len());
+        buf[..len].copy_from_slice(&remaining[..len]);
+        self.position += len;
+        if self.position < self.pending.len() {
+            return Ok(len);
+        }
+        // Pad rest of the chunk with a comment line, which is ignored by clients
+        let padding = &mut buf[len..];
+        match padding.len() {
+            0 => {}
+            1 => padding[0] = b'\n',
+            n => {
+                padding[0] = b':';
+                for byte in padding[1..n - 1].iter_mut() {
+                    *byte = b' ';
+                }
+                padding[n - 1] = b'\n';
+            }
+        }
+        Ok(buf.len())
+    }
+}
+
+impl<'r> Responder<'r> for EventStream {
+    fn respond_to(self, _: &Request) -> Result<Response<'r>, Status> {
+        Response::build()
+            .header(ContentType::new("text", "event-stream"))
+            .raw_header("Cache-Control", "no-cache")
+            .raw_header("X-Accel-Buffering", "no")
+            .chunked_body(self, CHUNK_SIZE)
+            .ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::{publish, subscribe, Event, EventKind};
+
+    #[test]
+    fn event_stream_pads_chunk_ok() {
+        let mut stream = EventStream::new(subscribe(2001));
+        publish(
+            &[2001],
+            Event {
+                kind: EventKind::ReviewSubmitted,
+                workshop: None,
+                submission: Some(3),
+                review: Some(4),
+            },
+        );
+        let mut buf = [0u8; 128];
+        assert_eq!(stream.read(&mut buf).unwrap(), 128);
+        let text = String::from_utf8_lossy(&buf);
+        assert!(text.starts_with(
+            "event: reviewsubmitted\ndata: {\"kind\":\"reviewsubmitted\",\"submission\":3,\"review\":4}\n\n:"
+        ));
+        assert!(text.ends_with(" \n"));
+    }
+
+    #[test]
+    fn event_stream_ends_after_lifetime_ok() {
+        let mut stream = EventStream::new(subscribe(2002));
+        stream.end = Instant::now();
+        let mut buf = [0u8; 128];
+        assert_eq!(stream.read(&mut buf).unwrap(), 0);
+    }
+}
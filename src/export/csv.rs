@@ -0,0 +1,88 @@
+//! Rendering of tables as CSV.
+//! See: https://datatracker.ietf.org/doc/html/rfc4180
+
+use super::Cell;
+
+// Byte order mark, spreadsheet programs otherwise guess the encoding
+const BOM: &str = "\u{feff}";
+
+/// Render table as CSV with CRLF line endings.
+pub fn render(table: &[Vec<Cell>]) -> String {
+    let mut csv = String::from(BOM);
+    for row in table {
+        let line = row
+            .iter()
+            .map(|cell| field(cell))
+            .collect::<Vec<String>>()
+            .join(",");
+        csv.push_str(&line);
+        csv.push_str("\r\n");
+    }
+    csv
+}
+
+// Render a single field, text is quoted if needed.
+fn field(cell: &Cell) -> String {
+    match cell {
+        Cell::Text(text) => quote(&neutralize(text)),
+        Cell::Number(number) => number.to_string(),
+        Cell::Bool(value) => value.to_string(),
+        Cell::Empty => String::new(),
+    }
+}
+
+// Prefix text that spreadsheet programs would read as formula.
+fn neutralize(text: &str) -> String {
+    if text.starts_with(|c| c == '=' || c == '+' || c == '-' || c == '@') {
+        format!("'{}", text)
+    } else {
+        text.to_string()
+    }
+}
+
+// Quote text containing separators, quotes or line breaks.
+fn quote(text: &str) -> String {
+    if text.contains(|c| c == ',' || c == '"' || c == '\r' || c == '\n') {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render_ok() {
+        let table = vec![
+            vec![
+                Cell::Text("Name".to_string()),
+                Cell::Text("Points".to_string()),
+            ],
+            vec![Cell::Text("Max".to_string()), Cell::Number(12.5)],
+            vec![Cell::Empty, Cell::Bool(true)],
+        ];
+        assert_eq!(
+            render(&table),
+            "\u{feff}Name,Points\r\nMax,12.5\r\n,true\r\n"
+        );
+    }
+
+    #[test]
+    fn quote_ok() {
+        assert_eq!(field(&Cell::Text("a,b".to_string())), "\"a,b\"");
+        assert_eq!(
+            field(&Cell::Text("say \"hi\"".to_string())),
+            "\"say \"\"hi\"\"\""
+        );
+        assert_eq!(field(&Cell::Text("a\nb".to_string())), "\"a\nb\"");
+    }
+
+    #[test]
+    fn neutralize_formula_ok() {
+        assert_eq!(field(&Cell::Text("=1+1".to_string())), "'=1+1");
+        assert_eq!(field(&Cell::Text("@SUM(A1)".to_string())), "'@SUM(A1)");
+        assert_eq!(field(&Cell::Number(-1.0)), "-1");
+    }
+}
@@ -0,0 +1,218 @@
+//! Export of workshop grades as CSV or XLSX file.
+
+mod csv;
+mod xlsx;
+
+use crate::db::models::GradeExport;
+use rocket::http::{ContentType, Status};
+use rocket::request::Request;
+use rocket::response::{Responder, Response};
+use std::io::Cursor;
+
+/// File format of an export.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ExportFormat {
+    Csv,
+    Xlsx,
+}
+
+impl ExportFormat {
+    pub fn from(str: &str) -> Result<Self, String> {
+        let input = str.to_lowercase();
+        if input.eq("csv") {
+            Ok(ExportFormat::Csv)
+        } else if input.eq("xlsx") {
+            Ok(ExportFormat::Xlsx)
+        } else {
+            Err(String::new())
+        }
+    }
+
+    fn content_type(&self) -> ContentType {
+        match self {
+            ExportFormat::Csv => ContentType::CSV,
+            ExportFormat::Xlsx => ContentType::new(
+                "application",
+                "vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+            ),
+        }
+    }
+
+    fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Xlsx => "xlsx",
+        }
+    }
+}
+
+impl Default for ExportFormat {
+    fn default() -> Self {
+        ExportFormat::Csv
+    }
+}
+
+/// Cell of an exported table.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Cell {
+    Text(String),
+    Number(f64),
+    Bool(bool),
+    Empty,
+}
+
+impl Cell {
+    // Numbers are rounded to two decimals, missing numbers stay empty.
+    fn number(number: Option<f64>) -> Self {
+        match number {
+            Some(number) => Cell::Number((number * 100.0).round() / 100.0),
+            None => Cell::Empty,
+        }
+    }
+}
+
+/// Build table of a grade export, the first row holds the column titles.
+pub fn grade_table(export: &GradeExport) -> Vec<Vec<Cell>> {
+    let mut header: Vec<Cell> = vec![
+        "Id",
+        "Firstname",
+        "Lastname",
+        "Group",
+        "Submission",
+        "Points",
+        "Max Points",
+        "Percentage",
+    ]
+    .into_iter()
+    .map(|title| Cell::Text(title.to_string()))
+    .collect();
+    for criterion in &export.criteria {
+        header.push(Cell::Text(criterion.clone()));
+    }
+    for title in &["Reviews Written", "Reviews Missed", "Reviews Done", "Error"] {
+        header.push(Cell::Text(title.to_string()));
+    }
+
+    let mut table = vec![header];
+    for grade in &export.rows {
+        let mut row = vec![
+            Cell::Number(grade.student as f64),
+            Cell::Text(grade.firstname.clone()),
+            Cell::Text(grade.lastname.clone()),
+            grade.group.clone().map_or(Cell::Empty, Cell::Text),
+            grade.submission.clone().map_or(Cell::Empty, Cell::Text),
+            Cell::number(grade.points),
+            Cell::number(grade.max_points),
+            Cell::number(grade.percentage),
+        ];
+        for points in &grade.criteria {
+            row.push(Cell::number(*points));
+        }
+        row.push(Cell::Number(grade.reviews_written as f64));
+        row.push(Cell::Number(grade.reviews_missed as f64));
+        row.push(Cell::Bool(grade.reviews_done));
+        row.push(Cell::Bool(grade.error));
+        table.push(row);
+    }
+    table
+}
+
+/// Rendered export file.
+pub struct ExportFile {
+    format: ExportFormat,
+    filename: String,
+    body: Vec<u8>,
+}
+
+/// Render grade export in the given format.
+pub fn render(export: &GradeExport, format: ExportFormat) -> Result<ExportFile, String> {
+    let table = grade_table(export);
+    let body = match format {
+        ExportFormat::Csv => Ok(csv::render(&table).into_bytes()),
+        ExportFormat::Xlsx => xlsx::render(&table),
+    };
+    match body {
+        Ok(body) => Ok(ExportFile {
+            filename: filename(&export.title, &format),
+            format,
+            body,
+        }),
+        Err(err) => Err(err),
+    }
+}
+
+// File name from workshop title, only ASCII letters & digits are kept.
+fn filename(title: &str, format: &ExportFormat) -> String {
+    let title: String = title
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    format!("grades_{}.{}", title, format.extension())
+}
+
+impl<'r> Responder<'r> for ExportFile {
+    fn respond_to(self, _: &Request) -> Result<Response<'r>, Status> {
+        Response::build()
+            .header(self.format.content_type())
+            .raw_header(
+                "Content-Disposition",
+                format!("attachment; filename=\"{}\"", self.filename),
+            )
+            .sized_body(Cursor::new(self.body))
+            .ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::models::GradeRow;
+
+    fn export() -> GradeExport {
+        GradeExport {
+            title: "Web Engineering 2022".to_string(),
+            criteria: vec!["Structure".to_string()],
+            rows: vec![GradeRow {
+                student: 4,
+                firstname: "Max".to_string(),
+                lastname: "Mustermann".to_string(),
+                group: None,
+                submission: Some("Essay".to_string()),
+                points: Some(20.0),
+                max_points: Some(30.0),
+                percentage: Some(200.0 / 3.0),
+                criteria: vec![None],
+                reviews_written: 2,
+                reviews_missed: 1,
+                reviews_done: true,
+                error: false,
+            }],
+        }
+    }
+
+    #[test]
+    fn grade_table_ok() {
+        let table = grade_table(&export());
+        assert_eq!(table.len(), 2);
+        assert_eq!(table[0].len(), table[1].len());
+        assert_eq!(table[0][8], Cell::Text("Structure".to_string()));
+        assert_eq!(table[1][3], Cell::Empty);
+        assert_eq!(table[1][7], Cell::Number(66.67));
+        assert_eq!(table[1][8], Cell::Empty);
+        assert_eq!(table[1][10], Cell::Number(1.0));
+    }
+
+    #[test]
+    fn filename_ok() {
+        assert_eq!(
+            filename("Web Engineering 2022", &ExportFormat::Xlsx),
+            "grades_Web_Engineering_2022.xlsx"
+        );
+    }
+
+    #[test]
+    fn export_format_from_ok() {
+        assert_eq!(ExportFormat::from("XLSX"), Ok(ExportFormat::Xlsx));
+        assert!(ExportFormat::from("pdf").is_err());
+    }
+}
@@ -0,0 +1,34 @@
+//! Rendering of tables as XLSX workbook.
+
+use super::Cell;
+use rust_xlsxwriter::{Format, Workbook};
+
+/// Render table as workbook with a single sheet, the first row is bold.
+pub fn render(table: &[Vec<Cell>]) -> Result<Vec<u8>, String> {
+    let mut workbook = Workbook::new();
+    let worksheet = workbook.add_worksheet();
+    if let Err(e) = worksheet.set_name("Grades") {
+        return Err(e.to_string());
+    }
+    let bold = Format::new().set_bold();
+
+    for (r, row) in table.iter().enumerate() {
+        for (c, cell) in row.iter().enumerate() {
+            let (r, c) = (r as u32, c as u16);
+            let write = match cell {
+                Cell::Text(text) if r == 0 => worksheet
+                    .write_string_with_format(r, c, text, &bold)
+                    .map(|_| ()),
+                Cell::Text(text) => worksheet.write_string(r, c, text).map(|_| ()),
+                Cell::Number(number) => worksheet.write_number(r, c, *number).map(|_| ()),
+                Cell::Bool(value) => worksheet.write_boolean(r, c, *value).map(|_| ()),
+                Cell::Empty => Ok(()),
+            };
+            if let Err(e) = write {
+                return Err(e.to_string());
+            }
+        }
+    }
+
+    workbook.save_to_buffer().map_err(|e| e.to_string())
+}
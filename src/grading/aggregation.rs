@@ -0,0 +1,284 @@
+//! Aggregation of review points into the points of a submission.
+
+use crate::db::models::Aggregation;
+use std::collections::HashMap;
+
+/// Lowest reliability a reviewer can get so that no review is ignored completely.
+const MIN_RELIABILITY: f64 = 0.1;
+
+/// Points of a single review with its weight in the aggregate.
+#[derive(Debug, Clone)]
+pub struct WeightedScore {
+    pub points: f64,
+    /// Weight of the review, e.g. the teacher weight for teacher reviews.
+    pub weight: f64,
+    /// Reliability of the reviewer, only used by `Aggregation::Weighted`.
+    pub reliability: f64,
+    /// Teacher reviews are never trimmed by `Aggregation::Trimmedmean`.
+    pub teacher: bool,
+}
+
+/// Normalized points of a review in a workshop.
+/// Used to determine the reliability of reviewers.
+#[derive(Debug, Clone)]
+pub struct ReviewerScore {
+    pub submission: u64,
+    pub reviewer: u64,
+    /// Points divided by max points (`0.0..=1.0`).
+    pub fraction: f64,
+}
+
+/// Aggregate review points with the given method.
+/// Returns `None` when there are no points to aggregate.
+pub fn aggregate(method: &Aggregation, scores: &[WeightedScore]) -> Option<f64> {
+    match method {
+        Aggregation::Mean => mean(scores, false),
+        Aggregation::Median => median(scores),
+        Aggregation::Trimmedmean => trimmed_mean(scores),
+        Aggregation::Weighted => mean(scores, true),
+    }
+}
+
+// Weighted arithmetic mean.
+fn mean(scores: &[WeightedScore], with_reliability: bool) -> Option<f64> {
+    let mut sum = 0.0;
+    let mut total_weight = 0.0;
+    for score in scores {
+        let weight = if with_reliability {
+            score.weight * score.reliability
+        } else {
+            score.weight
+        };
+        sum += score.points * weight;
+        total_weight += weight;
+    }
+    if total_weight > 0.0 {
+        Some(sum / total_weight)
+    } else {
+        None
+    }
+}
+
+// Weighted median.
+// If the weights split exactly between two points their mean is used.
+fn median(scores: &[WeightedScore]) -> Option<f64> {
+    let scores = sorted(scores);
+    let total_weight: f64 = scores.iter().map(|score| score.weight).sum();
+    if total_weight <= 0.0 {
+        return None;
+    }
+    let half = total_weight / 2.0;
+    let mut cumulative_weight = 0.0;
+    for (i, score) in scores.iter().enumerate() {
+        cumulative_weight += score.weight;
+        if (cumulative_weight - half).abs() < f64::EPSILON {
+            let next = scores[i + 1..].iter().find(|score| score.weight > 0.0);
+            return match next {
+                Some(next) => Some((score.points + next.points) / 2.0),
+                None => Some(score.points),
+            };
+        }
+        if cumulative_weight > half {
+            return Some(score.points);
+        }
+    }
+    scores.last().map(|score| score.points)
+}
+
+// Mean without the lowest and highest peer points, teacher points are always kept.
+// Needs at least three peer points, otherwise the plain mean is used.
+fn trimmed_mean(scores: &[WeightedScore]) -> Option<f64> {
+    let (teachers, peers): (Vec<WeightedScore>, Vec<WeightedScore>) =
+        scores.iter().cloned().partition(|score| score.teacher);
+    let mut peers = sorted(&peers);
+    if peers.len() >= 3 {
+        peers = peers[1..peers.len() - 1].to_vec();
+    }
+    peers.extend(teachers);
+    mean(&peers, false)
+}
+
+fn sorted(scores: &[WeightedScore]) -> Vec<WeightedScore> {
+    let mut scores = scores.to_vec();
+    // See: https://stackoverflow.com/a/28248065/12347616
+    scores.sort_by(|a, b| a.points.partial_cmp(&b.points).unwrap());
+    scores
+}
+
+/// Calculate the reliability of every reviewer.
+/// The reliability is based on the agreement with the other reviewers of the same submissions
+/// and ranges from `MIN_RELIABILITY` to `1.0`.
+/// Reviewers without other reviewers to compare to are fully reliable.
+pub fn reliabilities(scores: &[ReviewerScore]) -> HashMap<u64, f64> {
+    let mut submissions: HashMap<u64, Vec<&ReviewerScore>> = HashMap::new();
+    for score in scores {
+        submissions
+            .entry(score.submission)
+            .or_insert_with(Vec::new)
+            .push(score);
+    }
+
+    let mut deviations: HashMap<u64, Vec<f64>> = HashMap::new();
+    for submission_scores in submissions.values() {
+        for score in submission_scores {
+            let others: Vec<f64> = submission_scores
+                .iter()
+                .filter(|other| other.reviewer != score.reviewer)
+                .map(|other| other.fraction)
+                .collect();
+            let reviewer_deviations = deviations.entry(score.reviewer).or_insert_with(Vec::new);
+            if others.len() > 0 {
+                let others_mean = others.iter().sum::<f64>() / others.len() as f64;
+                reviewer_deviations.push((score.fraction - others_mean).abs());
+            }
+        }
+    }
+
+    deviations
+        .into_iter()
+        .map(|(reviewer, deviations)| (reviewer, reliability(&deviations)))
+        .collect()
+}
+
+/// Calculate the reliability from the deviations to other reviewers.
+pub fn reliability(deviations: &[f64]) -> f64 {
+    if deviations.len() == 0 {
+        return 1.0;
+    }
+    let mean_deviation = deviations.iter().sum::<f64>() / deviations.len() as f64;
+    (1.0 - mean_deviation).max(MIN_RELIABILITY).min(1.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn score(points: f64) -> WeightedScore {
+        WeightedScore {
+            points,
+            weight: 1.0,
+            reliability: 1.0,
+            teacher: false,
+        }
+    }
+
+    fn reviewer_score(submission: u64, reviewer: u64, fraction: f64) -> ReviewerScore {
+        ReviewerScore {
+            submission,
+            reviewer,
+            fraction,
+        }
+    }
+
+    #[test]
+    fn aggregate_no_scores_none() {
+        assert_eq!(aggregate(&Aggregation::Mean, &[]), None);
+        assert_eq!(aggregate(&Aggregation::Median, &[]), None);
+        assert_eq!(aggregate(&Aggregation::Trimmedmean, &[]), None);
+        assert_eq!(aggregate(&Aggregation::Weighted, &[]), None);
+    }
+
+    #[test]
+    fn mean_ok() {
+        let scores = vec![score(0.0), score(8.0), score(10.0)];
+        assert_eq!(aggregate(&Aggregation::Mean, &scores), Some(6.0));
+    }
+
+    #[test]
+    fn mean_with_teacher_weight_ok() {
+        let mut teacher = score(10.0);
+        teacher.weight = 2.0;
+        let scores = vec![score(4.0), score(4.0), teacher];
+        assert_eq!(aggregate(&Aggregation::Mean, &scores), Some(7.0));
+    }
+
+    #[test]
+    fn median_odd_count_ok() {
+        let scores = vec![score(10.0), score(0.0), score(7.0)];
+        assert_eq!(aggregate(&Aggregation::Median, &scores), Some(7.0));
+    }
+
+    #[test]
+    fn median_even_count_ok() {
+        let scores = vec![score(2.0), score(8.0), score(4.0), score(10.0)];
+        assert_eq!(aggregate(&Aggregation::Median, &scores), Some(6.0));
+    }
+
+    #[test]
+    fn median_with_teacher_weight_ok() {
+        let mut teacher = score(9.0);
+        teacher.weight = 3.0;
+        let scores = vec![score(1.0), score(2.0), teacher];
+        assert_eq!(aggregate(&Aggregation::Median, &scores), Some(9.0));
+    }
+
+    #[test]
+    fn trimmed_mean_ignores_outliers_ok() {
+        let scores = vec![score(0.0), score(6.0), score(8.0), score(10.0)];
+        assert_eq!(aggregate(&Aggregation::Trimmedmean, &scores), Some(7.0));
+    }
+
+    #[test]
+    fn trimmed_mean_keeps_teacher_scores_ok() {
+        let mut teacher = score(10.0);
+        teacher.teacher = true;
+        teacher.weight = 2.0;
+        let scores = vec![score(0.0), score(6.0), score(8.0), score(9.0), teacher];
+        // Peers 0 & 9 are trimmed, the highest teacher points are kept
+        // (6 + 8 + 10 * 2) / 4
+        assert_eq!(aggregate(&Aggregation::Trimmedmean, &scores), Some(8.5));
+    }
+
+    #[test]
+    fn trimmed_mean_few_scores_uses_mean_ok() {
+        let scores = vec![score(2.0), score(6.0)];
+        assert_eq!(aggregate(&Aggregation::Trimmedmean, &scores), Some(4.0));
+    }
+
+    #[test]
+    fn weighted_uses_reliability_ok() {
+        let mut unreliable = score(0.0);
+        unreliable.reliability = 0.25;
+        let scores = vec![score(8.0), score(8.0), unreliable];
+        // (8 + 8 + 0 * 0.25) / 2.25
+        let points = aggregate(&Aggregation::Weighted, &scores).unwrap();
+        assert!((points - 16.0 / 2.25).abs() < 1e-9);
+        // Mean ignores reliability
+        let points = aggregate(&Aggregation::Mean, &scores).unwrap();
+        assert!((points - 16.0 / 3.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn reliability_without_deviations_is_full() {
+        assert_eq!(reliability(&[]), 1.0);
+    }
+
+    #[test]
+    fn reliability_is_bounded() {
+        assert_eq!(reliability(&[1.0, 1.0]), MIN_RELIABILITY);
+        assert_eq!(reliability(&[0.0]), 1.0);
+    }
+
+    #[test]
+    fn reliabilities_detect_disagreeing_reviewer() {
+        let scores = vec![
+            reviewer_score(1, 10, 0.8),
+            reviewer_score(1, 11, 0.8),
+            reviewer_score(1, 12, 0.0),
+            reviewer_score(2, 10, 0.6),
+            reviewer_score(2, 11, 0.6),
+            reviewer_score(2, 12, 1.0),
+        ];
+        let reliabilities = reliabilities(&scores);
+        let agreeing = reliabilities[&10];
+        let disagreeing = reliabilities[&12];
+        assert!(agreeing > disagreeing);
+        assert!((disagreeing - (1.0 - (0.8 + 0.4) / 2.0)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn reliabilities_single_reviewer_is_full() {
+        let scores = vec![reviewer_score(1, 10, 0.3)];
+        assert_eq!(reliabilities(&scores)[&10], 1.0);
+    }
+}
@@ -0,0 +1,97 @@
+//! Accuracy of reviewers based on calibration submissions.
+//! Students review example submissions of teachers, their reviews are compared
+//! with the reference review of the teacher.
+
+use crate::db::models::{GradeScale, SimpleReviewPoints};
+use crate::grading::aggregation;
+use crate::grading::normalization;
+use std::collections::HashMap;
+
+/// Normalized points (`0.0..=1.0`) of every scored criterion of a review.
+pub fn criterion_fractions(points: &[SimpleReviewPoints], scale: &GradeScale) -> HashMap<u64, f64> {
+    points
+        .iter()
+        .filter(|point| point.kind.is_scored())
+        .map(|point| {
+            let fraction =
+                normalization::normalize(&point.kind, scale, point.custom_range, point.points);
+            (point.criterion, fraction)
+        })
+        .collect()
+}
+
+/// Deviation of a review from the reference review for every criterion of the reference.
+/// Criteria missing in the review count as maximal deviation.
+pub fn deviations(reference: &HashMap<u64, f64>, review: &HashMap<u64, f64>) -> HashMap<u64, f64> {
+    reference
+        .iter()
+        .map(|(criterion, reference_fraction)| {
+            let deviation = match review.get(criterion) {
+                Some(fraction) => (fraction - reference_fraction).abs(),
+                None => 1.0,
+            };
+            (*criterion, deviation)
+        })
+        .collect()
+}
+
+/// Calculate the accuracy of a reviewer from the deviations to the reference reviews.
+/// Uses the same range as the reliability of reviewers.
+pub fn accuracy(deviations: &[f64]) -> f64 {
+    aggregation::reliability(deviations)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::models::Kind;
+
+    fn point(criterion: u64, kind: Kind, points: f64) -> SimpleReviewPoints {
+        SimpleReviewPoints {
+            criterion,
+            weight: 1.0,
+            kind,
+            custom_range: None,
+            points,
+        }
+    }
+
+    #[test]
+    fn criterion_fractions_ok() {
+        let points = vec![
+            point(1, Kind::Point, 5.0),
+            point(2, Kind::Percentage, 100.0),
+            // Text criteria are not scored
+            point(3, Kind::Text, 0.0),
+        ];
+        let fractions = criterion_fractions(&points, &GradeScale::Austrian);
+        assert_eq!(fractions.len(), 2);
+        assert!((fractions[&1] - 0.5).abs() < 1e-9);
+        assert!((fractions[&2] - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn deviations_ok() {
+        let reference: HashMap<u64, f64> = vec![(1, 0.5), (2, 1.0), (3, 0.0)].into_iter().collect();
+        let review: HashMap<u64, f64> = vec![(1, 0.75), (2, 1.0)].into_iter().collect();
+        let deviations = deviations(&reference, &review);
+        assert_eq!(deviations.len(), 3);
+        assert!((deviations[&1] - 0.25).abs() < 1e-9);
+        assert!(deviations[&2].abs() < 1e-9);
+        // Missing criterion
+        assert!((deviations[&3] - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn accuracy_ok() {
+        let cases: Vec<(Vec<f64>, f64)> = vec![
+            (vec![], 1.0),
+            (vec![0.0, 0.0], 1.0),
+            (vec![0.2, 0.4], 0.7),
+            (vec![1.0, 1.0], 0.1),
+        ];
+        for (deviations, expected) in cases {
+            assert!((accuracy(&deviations) - expected).abs() < 1e-9);
+        }
+    }
+}
@@ -0,0 +1,32 @@
+//! Penalty for late submissions.
+//! Workshops with the accept late policy take submissions after the end of the submission phase,
+//! their points can be reduced by a penalty.
+
+/// Reduce points of a late submission.
+/// `penalty` is the share (`0.0..=1.0`) of the points that is deducted.
+pub fn penalized_points(points: f64, penalty: f64) -> f64 {
+    points * (1.0 - penalty.max(0.0).min(1.0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn penalized_points_ok() {
+        let cases = vec![
+            // No penalty
+            (8.0, 0.0, 8.0),
+            (8.0, 0.25, 6.0),
+            (8.0, 1.0, 0.0),
+            (0.0, 0.5, 0.0),
+            // Penalty is clamped
+            (8.0, 1.5, 0.0),
+            (8.0, -0.5, 8.0),
+        ];
+        for (points, penalty, expected) in cases {
+            let result = penalized_points(points, penalty);
+            assert!((result - expected).abs() < 1e-9);
+        }
+    }
+}
@@ -0,0 +1,39 @@
+//! Grading of submissions based on their reviews.
+
+pub mod aggregation;
+pub mod calibration;
+pub mod late;
+pub mod normalization;
+pub mod reviewing;
+pub mod rounds;
+pub mod selfassessment;
+pub mod statistics;
+
+use crate::db::models::{GradeScale, SimpleReviewPoints};
+
+/// Points a criterion with weight 1 is worth.
+pub const POINT_RANGE: f64 = 10.0;
+
+/// Calculate the weighted points of a single review.
+/// Points of every criterion are normalized first so that all kinds have the same range.
+pub fn review_points(points: &[SimpleReviewPoints], scale: &GradeScale) -> f64 {
+    points
+        .iter()
+        .filter(|point| point.kind.is_scored())
+        .map(|point| {
+            let fraction =
+                normalization::normalize(&point.kind, scale, point.custom_range, point.points);
+            fraction * POINT_RANGE * point.weight
+        })
+        .sum()
+}
+
+/// Calculate the maximum points a review can reach.
+/// Text criteria are not part of the points.
+pub fn max_points(points: &[SimpleReviewPoints]) -> f64 {
+    points
+        .iter()
+        .filter(|point| point.kind.is_scored())
+        .map(|point| POINT_RANGE * point.weight)
+        .sum()
+}
@@ -0,0 +1,226 @@
+//! Normalization of review points.
+//! Every criterion kind is mapped to a fraction (`0.0..=1.0`) of its maximum.
+//! Range and rubric criteria use the custom range of the criterion.
+
+use crate::db::models::{GradeScale, Kind};
+
+/// Grades of a scale with their fraction, from best to worst.
+fn grades(scale: &GradeScale) -> &'static [(i64, f64)] {
+    match scale {
+        // 1 (Sehr gut) to 5 (Nicht genügend)
+        GradeScale::Austrian => &[(1, 1.0), (2, 0.8), (3, 0.6), (4, 0.5), (5, 0.0)],
+        // 1 (sehr gut) to 6 (ungenügend)
+        GradeScale::German => &[(1, 1.0), (2, 0.8), (3, 0.65), (4, 0.5), (5, 0.0), (6, 0.0)],
+        // 6 (sehr gut) to 1 (sehr schwach)
+        GradeScale::Swiss => &[(6, 1.0), (5, 0.8), (4, 0.6), (3, 0.4), (2, 0.2), (1, 0.0)],
+    }
+}
+
+/// Lowest and highest points that can be given for a criterion kind.
+pub fn point_range(kind: &Kind, scale: &GradeScale, custom: Option<(f64, f64)>) -> (f64, f64) {
+    match kind {
+        Kind::Point => (0.0, 10.0),
+        Kind::Grade => {
+            let grades = grades(scale);
+            let min = grades.iter().map(|grade| grade.0).min().unwrap_or(0);
+            let max = grades.iter().map(|grade| grade.0).max().unwrap_or(0);
+            (min as f64, max as f64)
+        }
+        Kind::Percentage => (0.0, 100.0),
+        Kind::Truefalse => (0.0, 1.0),
+        Kind::Range | Kind::Rubric => custom.unwrap_or((0.0, 10.0)),
+        Kind::Text => (0.0, 0.0),
+    }
+}
+
+/// Clamp given points into the point range of a criterion kind.
+pub fn clamp(kind: &Kind, scale: &GradeScale, custom: Option<(f64, f64)>, points: f64) -> f64 {
+    let (min, max) = point_range(kind, scale, custom);
+    points.max(min).min(max)
+}
+
+/// Map points of a criterion kind to a fraction (`0.0..=1.0`).
+/// Unknown grades and text criteria are mapped to `0.0`.
+pub fn normalize(kind: &Kind, scale: &GradeScale, custom: Option<(f64, f64)>, points: f64) -> f64 {
+    match kind {
+        Kind::Point | Kind::Percentage | Kind::Truefalse | Kind::Range | Kind::Rubric => {
+            let (min, max) = point_range(kind, scale, custom);
+            if max <= min {
+                return 0.0;
+            }
+            (clamp(kind, scale, custom, points) - min) / (max - min)
+        }
+        Kind::Grade => {
+            let grade = points.round() as i64;
+            grades(scale)
+                .iter()
+                .find(|(scale_grade, _)| *scale_grade == grade)
+                .map(|(_, fraction)| *fraction)
+                .unwrap_or(0.0)
+        }
+        Kind::Text => 0.0,
+    }
+}
+
+/// Round points of range criteria to the closest step within the range.
+pub fn round_to_step(min: f64, max: f64, step: f64, points: f64) -> f64 {
+    let rounded = min + ((points - min) / step).round() * step;
+    if rounded > max {
+        rounded - step
+    } else {
+        rounded
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalize_ok() {
+        let cases = vec![
+            // Points
+            (Kind::Point, GradeScale::Austrian, 0.0, 0.0),
+            (Kind::Point, GradeScale::Austrian, 7.5, 0.75),
+            (Kind::Point, GradeScale::Austrian, 10.0, 1.0),
+            (Kind::Point, GradeScale::Austrian, 12.0, 1.0),
+            (Kind::Point, GradeScale::Austrian, -1.0, 0.0),
+            // Percentage
+            (Kind::Percentage, GradeScale::Austrian, 0.0, 0.0),
+            (Kind::Percentage, GradeScale::Austrian, 50.0, 0.5),
+            (Kind::Percentage, GradeScale::Austrian, 100.0, 1.0),
+            (Kind::Percentage, GradeScale::Austrian, 150.0, 1.0),
+            // Truefalse
+            (Kind::Truefalse, GradeScale::Austrian, 0.0, 0.0),
+            (Kind::Truefalse, GradeScale::Austrian, 1.0, 1.0),
+            // Austrian grades
+            (Kind::Grade, GradeScale::Austrian, 1.0, 1.0),
+            (Kind::Grade, GradeScale::Austrian, 2.0, 0.8),
+            (Kind::Grade, GradeScale::Austrian, 3.0, 0.6),
+            (Kind::Grade, GradeScale::Austrian, 4.0, 0.5),
+            (Kind::Grade, GradeScale::Austrian, 5.0, 0.0),
+            (Kind::Grade, GradeScale::Austrian, 6.0, 0.0),
+            (Kind::Grade, GradeScale::Austrian, 1.4, 1.0),
+            // German grades
+            (Kind::Grade, GradeScale::German, 1.0, 1.0),
+            (Kind::Grade, GradeScale::German, 3.0, 0.65),
+            (Kind::Grade, GradeScale::German, 4.0, 0.5),
+            (Kind::Grade, GradeScale::German, 6.0, 0.0),
+            // Swiss grades
+            (Kind::Grade, GradeScale::Swiss, 6.0, 1.0),
+            (Kind::Grade, GradeScale::Swiss, 4.0, 0.6),
+            (Kind::Grade, GradeScale::Swiss, 1.0, 0.0),
+            (Kind::Grade, GradeScale::Swiss, 0.0, 0.0),
+        ];
+        for (kind, scale, points, expected) in cases {
+            let fraction = normalize(&kind, &scale, None, points);
+            assert!(
+                (fraction - expected).abs() < 1e-9,
+                "{:?} {:?} {} => {} (expected {})",
+                kind,
+                scale,
+                points,
+                fraction,
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn point_range_ok() {
+        let cases = vec![
+            (Kind::Point, GradeScale::Austrian, (0.0, 10.0)),
+            (Kind::Percentage, GradeScale::Austrian, (0.0, 100.0)),
+            (Kind::Truefalse, GradeScale::Austrian, (0.0, 1.0)),
+            (Kind::Grade, GradeScale::Austrian, (1.0, 5.0)),
+            (Kind::Grade, GradeScale::German, (1.0, 6.0)),
+            (Kind::Grade, GradeScale::Swiss, (1.0, 6.0)),
+            (Kind::Text, GradeScale::Austrian, (0.0, 0.0)),
+        ];
+        for (kind, scale, expected) in cases {
+            assert_eq!(point_range(&kind, &scale, None), expected);
+        }
+    }
+
+    #[test]
+    fn clamp_ok() {
+        let cases = vec![
+            (Kind::Point, GradeScale::Austrian, 11.0, 10.0),
+            (Kind::Point, GradeScale::Austrian, -2.0, 0.0),
+            (Kind::Percentage, GradeScale::Austrian, 120.0, 100.0),
+            (Kind::Grade, GradeScale::Austrian, 0.0, 1.0),
+            (Kind::Grade, GradeScale::Austrian, 6.0, 5.0),
+            (Kind::Grade, GradeScale::German, 6.0, 6.0),
+        ];
+        for (kind, scale, points, expected) in cases {
+            assert_eq!(clamp(&kind, &scale, None, points), expected);
+        }
+    }
+
+    #[test]
+    fn normalized_points_never_exceed_range() {
+        let kinds = vec![
+            Kind::Point,
+            Kind::Grade,
+            Kind::Percentage,
+            Kind::Truefalse,
+            Kind::Range,
+            Kind::Rubric,
+            Kind::Text,
+        ];
+        let scales = vec![GradeScale::Austrian, GradeScale::German, GradeScale::Swiss];
+        for kind in &kinds {
+            for scale in &scales {
+                for points in &[-10.0, 0.0, 1.0, 5.0, 10.0, 100.0, 1000.0] {
+                    let fraction = normalize(kind, scale, Some((2.0, 20.0)), *points);
+                    assert!(fraction >= 0.0 && fraction <= 1.0);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn normalize_custom_range_ok() {
+        let cases = vec![
+            (Kind::Range, Some((0.0, 20.0)), 5.0, 0.25),
+            (Kind::Range, Some((10.0, 20.0)), 15.0, 0.5),
+            (Kind::Range, Some((10.0, 20.0)), 5.0, 0.0),
+            (Kind::Range, Some((10.0, 20.0)), 25.0, 1.0),
+            (Kind::Rubric, Some((0.0, 4.0)), 3.0, 0.75),
+            (Kind::Rubric, Some((0.0, 4.0)), 4.0, 1.0),
+            // Missing custom ranges fall back to the point range
+            (Kind::Range, None, 5.0, 0.5),
+            // Text is never scored
+            (Kind::Text, Some((0.0, 10.0)), 10.0, 0.0),
+            // Custom ranges are ignored by other kinds
+            (Kind::Point, Some((0.0, 20.0)), 5.0, 0.5),
+        ];
+        for (kind, custom, points, expected) in cases {
+            let fraction = normalize(&kind, &GradeScale::Austrian, custom, points);
+            assert!(
+                (fraction - expected).abs() < 1e-9,
+                "{:?} {:?} {} => {} (expected {})",
+                kind,
+                custom,
+                points,
+                fraction,
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn round_to_step_ok() {
+        let cases = vec![
+            (0.0, 10.0, 0.5, 2.2, 2.0),
+            (0.0, 10.0, 0.5, 2.3, 2.5),
+            (1.0, 10.0, 2.0, 4.2, 5.0),
+            (1.0, 10.0, 2.0, 1.0, 1.0),
+            // Last step does not fit into the range
+            (0.0, 10.0, 4.0, 10.0, 8.0),
+        ];
+        for (min, max, step, points, expected) in cases {
+            assert!((round_to_step(min, max, step, points) - expected).abs() < 1e-9);
+        }
+    }
+}
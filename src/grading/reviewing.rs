@@ -0,0 +1,66 @@
+//! Reviewing component of grades.
+//! Authors rate the helpfulness of received reviews, the ratings of the reviews a student wrote
+//! can be part of the student's own points.
+
+/// Lowest helpfulness rating.
+pub const MIN_HELPFULNESS: i32 = 1;
+/// Highest helpfulness rating.
+pub const MAX_HELPFULNESS: i32 = 5;
+
+/// Map the mean of helpfulness ratings to a fraction (`0.0..=1.0`).
+/// Returns `None` when there are no ratings.
+pub fn helpfulness_fraction(ratings: &[i32]) -> Option<f64> {
+    if ratings.len() == 0 {
+        return None;
+    }
+    let mean = ratings.iter().sum::<i32>() as f64 / ratings.len() as f64;
+    let fraction = (mean - MIN_HELPFULNESS as f64) / (MAX_HELPFULNESS - MIN_HELPFULNESS) as f64;
+    Some(fraction.max(0.0).min(1.0))
+}
+
+/// Combine submission points with the reviewing component.
+/// `weight` is the share (`0.0..=1.0`) of the reviewing component.
+/// Without ratings the submission points are used as they are.
+pub fn final_points(points: f64, max_points: f64, helpfulness: Option<f64>, weight: f64) -> f64 {
+    match helpfulness {
+        Some(helpfulness) => points * (1.0 - weight) + helpfulness * max_points * weight,
+        None => points,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn helpfulness_fraction_ok() {
+        let cases: Vec<(Vec<i32>, Option<f64>)> = vec![
+            (vec![], None),
+            (vec![1], Some(0.0)),
+            (vec![5], Some(1.0)),
+            (vec![3], Some(0.5)),
+            (vec![2, 4], Some(0.5)),
+            (vec![4, 5, 5, 4], Some(0.875)),
+        ];
+        for (ratings, expected) in cases {
+            assert_eq!(helpfulness_fraction(&ratings), expected);
+        }
+    }
+
+    #[test]
+    fn final_points_ok() {
+        let cases = vec![
+            // No ratings
+            (8.0, 10.0, None, 0.2, 8.0),
+            // No reviewing weight
+            (8.0, 10.0, Some(0.0), 0.0, 8.0),
+            (8.0, 10.0, Some(1.0), 0.2, 8.4),
+            (8.0, 10.0, Some(0.5), 0.5, 6.5),
+            (0.0, 20.0, Some(1.0), 1.0, 20.0),
+        ];
+        for (points, max_points, helpfulness, weight, expected) in cases {
+            let result = final_points(points, max_points, helpfulness, weight);
+            assert!((result - expected).abs() < 1e-9);
+        }
+    }
+}
@@ -0,0 +1,41 @@
+//! Points of submissions with several review rounds.
+//! Every round is graded on its own, the round policy of the workshop combines them.
+
+use crate::db::models::RoundPolicy;
+
+/// Combine the points of all rounds, ordered from the first to the latest round.
+/// Rounds that are not graded yet are ignored.
+pub fn round_points(points: &[Option<f64>], policy: &RoundPolicy) -> Option<f64> {
+    let graded = points.iter().filter_map(|points| *points);
+    match policy {
+        RoundPolicy::Last => graded.last(),
+        RoundPolicy::Best => graded.fold(None, |best: Option<f64>, points| {
+            Some(best.map_or(points, |best| best.max(points)))
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_points_last_ok() {
+        let points = vec![Some(8.0), Some(6.0), None];
+        assert_eq!(round_points(&points, &RoundPolicy::Last), Some(6.0));
+    }
+
+    #[test]
+    fn round_points_best_ok() {
+        let points = vec![Some(6.0), Some(8.0), Some(7.0)];
+        assert_eq!(round_points(&points, &RoundPolicy::Best), Some(8.0));
+    }
+
+    #[test]
+    fn round_points_not_graded_ok() {
+        let points = vec![None, None];
+        assert_eq!(round_points(&points, &RoundPolicy::Last), None);
+        assert_eq!(round_points(&points, &RoundPolicy::Best), None);
+        assert_eq!(round_points(&[], &RoundPolicy::Best), None);
+    }
+}
@@ -0,0 +1,97 @@
+//! Comparison of self-assessments with the peer reviews of a submission.
+//! Submitters grade their own work, the normalized points per criterion are compared
+//! with the mean of the peer reviews.
+
+use std::collections::{BTreeMap, HashMap};
+
+/// Mean normalized points of the peer reviews for every criterion.
+pub fn peer_fractions(reviews: &[HashMap<u64, f64>]) -> HashMap<u64, f64> {
+    let mut per_criterion: BTreeMap<u64, Vec<f64>> = BTreeMap::new();
+    for review in reviews {
+        for (criterion, fraction) in review {
+            per_criterion.entry(*criterion).or_default().push(*fraction);
+        }
+    }
+    per_criterion
+        .into_iter()
+        .map(|(criterion, fractions)| {
+            let mean = fractions.iter().sum::<f64>() / fractions.len() as f64;
+            (criterion, mean)
+        })
+        .collect()
+}
+
+/// Signed deviation of the self-assessment from the peers for every criterion graded by both.
+/// Positive deviations mean that the submitter rated the own work higher than the peers.
+pub fn deviations(own: &HashMap<u64, f64>, peers: &HashMap<u64, f64>) -> HashMap<u64, f64> {
+    own.iter()
+        .filter_map(|(criterion, own_fraction)| {
+            peers
+                .get(criterion)
+                .map(|peer_fraction| (*criterion, own_fraction - peer_fraction))
+        })
+        .collect()
+}
+
+/// Mean absolute deviation over all criteria.
+/// Returns `None` if no criterion can be compared.
+pub fn mean_deviation(deviations: &HashMap<u64, f64>) -> Option<f64> {
+    if deviations.is_empty() {
+        return None;
+    }
+    let sum: f64 = deviations.values().map(|deviation| deviation.abs()).sum();
+    Some(sum / deviations.len() as f64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fractions(values: &[(u64, f64)]) -> HashMap<u64, f64> {
+        values.iter().copied().collect()
+    }
+
+    #[test]
+    fn peer_fractions_ok() {
+        let reviews = vec![
+            fractions(&[(1, 0.5), (2, 1.0)]),
+            fractions(&[(1, 1.0), (2, 0.0)]),
+            // Criterion only graded by one peer
+            fractions(&[(3, 0.25)]),
+        ];
+        let peers = peer_fractions(&reviews);
+        assert_eq!(peers.len(), 3);
+        assert!((peers[&1] - 0.75).abs() < 1e-9);
+        assert!((peers[&2] - 0.5).abs() < 1e-9);
+        assert!((peers[&3] - 0.25).abs() < 1e-9);
+        assert!(peer_fractions(&[]).is_empty());
+    }
+
+    #[test]
+    fn deviations_ok() {
+        let own = fractions(&[(1, 1.0), (2, 0.25), (3, 0.5)]);
+        let peers = fractions(&[(1, 0.75), (2, 0.5)]);
+        let deviations = deviations(&own, &peers);
+        // Criterion 3 has no peer points
+        assert_eq!(deviations.len(), 2);
+        assert!((deviations[&1] - 0.25).abs() < 1e-9);
+        assert!((deviations[&2] + 0.25).abs() < 1e-9);
+    }
+
+    #[test]
+    fn mean_deviation_ok() {
+        let cases: Vec<(Vec<(u64, f64)>, Option<f64>)> = vec![
+            (vec![], None),
+            (vec![(1, 0.0)], Some(0.0)),
+            (vec![(1, 0.25), (2, -0.25)], Some(0.25)),
+            (vec![(1, 0.5), (2, -0.1), (3, 0.3)], Some(0.3)),
+        ];
+        for (deviations, expected) in cases {
+            let mean = mean_deviation(&fractions(&deviations));
+            match expected {
+                Some(expected) => assert!((mean.unwrap() - expected).abs() < 1e-9),
+                None => assert!(mean.is_none()),
+            }
+        }
+    }
+}
@@ -0,0 +1,162 @@
+//! Agreement of reviewers on the points of submissions.
+//! All values are normalized points (`0.0..=1.0`) so that criteria of every kind can be compared.
+
+use crate::db::models::Spread;
+
+/// Reviews deviating more than this from the median of all reviews are outliers.
+pub const OUTLIER_THRESHOLD: f64 = 0.25;
+
+/// Calculate mean, standard deviation and range of the given points.
+/// Returns `None` when there are no points.
+pub fn spread(values: &[f64]) -> Option<Spread> {
+    if values.len() == 0 {
+        return None;
+    }
+    let mean = mean(values);
+    let variance = values
+        .iter()
+        .map(|value| (value - mean).powi(2))
+        .sum::<f64>()
+        / values.len() as f64;
+    let min = values.iter().copied().fold(f64::INFINITY, f64::min);
+    let max = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    Some(Spread {
+        mean,
+        std_dev: variance.sqrt(),
+        min,
+        max,
+        range: max - min,
+    })
+}
+
+/// Flag points that deviate more than `OUTLIER_THRESHOLD` from the median.
+/// Needs at least three points, otherwise no point can be singled out.
+pub fn outliers(values: &[f64]) -> Vec<bool> {
+    if values.len() < 3 {
+        return vec![false; values.len()];
+    }
+    let mut sorted = values.to_vec();
+    sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    let middle = sorted.len() / 2;
+    let median = if sorted.len() % 2 == 0 {
+        (sorted[middle - 1] + sorted[middle]) / 2.0
+    } else {
+        sorted[middle]
+    };
+    values
+        .iter()
+        .map(|value| (value - median).abs() > OUTLIER_THRESHOLD)
+        .collect()
+}
+
+/// Calculate the agreement of reviewers with the one-way intraclass correlation ICC(1).
+/// Every group holds the points of one submission, groups with less than two points are ignored.
+/// The coefficient is at most `1.0` (full agreement), values around or below `0.0`
+/// mean that reviewers agree no more than by chance.
+/// Returns `None` when less than two groups remain or there is no variance at all.
+pub fn agreement(groups: &[Vec<f64>]) -> Option<f64> {
+    let groups: Vec<&Vec<f64>> = groups.iter().filter(|group| group.len() >= 2).collect();
+    if groups.len() < 2 {
+        return None;
+    }
+    let group_count = groups.len() as f64;
+    let total_count = groups.iter().map(|group| group.len()).sum::<usize>() as f64;
+    let grand_mean = groups.iter().flat_map(|group| group.iter()).sum::<f64>() / total_count;
+
+    let mut between = 0.0;
+    let mut within = 0.0;
+    for group in &groups {
+        let group_mean = mean(group);
+        between += group.len() as f64 * (group_mean - grand_mean).powi(2);
+        within += group
+            .iter()
+            .map(|value| (value - group_mean).powi(2))
+            .sum::<f64>();
+    }
+    let between = between / (group_count - 1.0);
+    let within = within / (total_count - group_count);
+    // Average group size adjusted for unequal review counts
+    let squared_counts = groups
+        .iter()
+        .map(|group| (group.len() as f64).powi(2))
+        .sum::<f64>();
+    let group_size = (total_count - squared_counts / total_count) / (group_count - 1.0);
+
+    let denominator = between + (group_size - 1.0) * within;
+    if denominator <= 0.0 {
+        return None;
+    }
+    Some((between - within) / denominator)
+}
+
+fn mean(values: &[f64]) -> f64 {
+    values.iter().sum::<f64>() / values.len() as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn spread_ok() {
+        assert_eq!(spread(&[]), None);
+        let single = spread(&[0.5]).unwrap();
+        assert_eq!(single.std_dev, 0.0);
+        assert_eq!(single.range, 0.0);
+
+        let spread = spread(&[0.5, 1.0, 0.75]).unwrap();
+        assert!((spread.mean - 0.75).abs() < 1e-9);
+        assert!((spread.std_dev - (0.125f64 / 3.0).sqrt()).abs() < 1e-9);
+        assert!((spread.min - 0.5).abs() < 1e-9);
+        assert!((spread.max - 1.0).abs() < 1e-9);
+        assert!((spread.range - 0.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn outliers_ok() {
+        let cases: Vec<(Vec<f64>, Vec<bool>)> = vec![
+            (vec![], vec![]),
+            // Two reviews cannot be told apart
+            (vec![0.0, 1.0], vec![false, false]),
+            (vec![0.8, 0.2, 0.8], vec![false, true, false]),
+            (vec![0.5, 0.6, 0.7], vec![false, false, false]),
+            (vec![0.1, 0.5, 0.6, 0.9], vec![true, false, false, true]),
+        ];
+        for (values, expected) in cases {
+            assert_eq!(outliers(&values), expected);
+        }
+    }
+
+    #[test]
+    fn agreement_not_enough_groups_none() {
+        assert_eq!(agreement(&[]), None);
+        assert_eq!(agreement(&[vec![0.5, 0.6]]), None);
+        // Groups with a single review are ignored
+        assert_eq!(agreement(&[vec![0.5, 0.6], vec![0.2]]), None);
+        // No variance at all
+        assert_eq!(agreement(&[vec![0.5, 0.5], vec![0.5, 0.5]]), None);
+    }
+
+    #[test]
+    fn agreement_ok() {
+        // Full agreement
+        let full = agreement(&[vec![1.0, 1.0], vec![0.0, 0.0]]).unwrap();
+        assert!((full - 1.0).abs() < 1e-9);
+        // Reviewers disagree on every submission
+        let none = agreement(&[vec![0.0, 1.0], vec![0.0, 1.0]]).unwrap();
+        assert!((none + 1.0).abs() < 1e-9);
+
+        let groups = vec![vec![0.8, 0.9], vec![0.4, 0.5], vec![0.1, 0.2]];
+        let between = 0.74 / 3.0;
+        let within = 0.005;
+        let expected = (between - within) / (between + within);
+        assert!((agreement(&groups).unwrap() - expected).abs() < 1e-9);
+    }
+
+    #[test]
+    fn agreement_unequal_group_sizes_ok() {
+        let groups = vec![vec![0.9, 0.8, 0.85], vec![0.3, 0.4]];
+        let agreement = agreement(&groups).unwrap();
+        assert!(agreement > 0.9 && agreement <= 1.0);
+    }
+}
@@ -0,0 +1,7 @@
+//! Email notifications.
+//! Emails are queued in the database and sent by a background job via SMTP.
+
+mod sender;
+pub mod templates;
+
+pub use sender::*;
@@ -0,0 +1,255 @@
+use crate::db;
+use crate::db::error::DbError;
+use crate::db::models::QueuedEmail;
+use crate::utils::queue;
+use diesel::prelude::*;
+use lettre::message::Mailbox;
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{Message, SmtpTransport, Transport};
+use rocket::logger::error;
+use rocket::Rocket;
+use rocket_contrib::databases::database_config;
+
+/// SMTP settings of the mailer.
+pub struct MailConfig {
+    pub host: String,
+    pub port: u16,
+    pub tls: bool,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    pub from: Mailbox,
+    pub max_attempts: i32,
+}
+
+impl MailConfig {
+    /// Build SMTP transport from configuration.
+    pub fn transport(&self) -> Result<SmtpTransport, String> {
+        let builder = if self.tls {
+            match SmtpTransport::starttls_relay(&self.host) {
+                Ok(builder) => builder,
+                Err(e) => return Err(format!("Invalid SMTP relay: {}", e)),
+            }
+        } else {
+            // Plain connection, e.g. for a local SMTP catcher
+            SmtpTransport::builder_dangerous(&self.host)
+        };
+        let builder = builder.port(self.port);
+        let builder = match (&self.username, &self.password) {
+            (Some(username), Some(password)) => {
+                builder.credentials(Credentials::new(username.clone(), password.clone()))
+            }
+            _ => builder,
+        };
+        Ok(builder.build())
+    }
+}
+
+/// Send a queued email via SMTP.
+pub fn send(
+    config: &MailConfig,
+    transport: &SmtpTransport,
+    email: &QueuedEmail,
+) -> Result<(), String> {
+    let to = email.address.parse::<Mailbox>().map_err(|e| e.to_string());
+    if let Err(e) = to {
+        return Err(e);
+    }
+    let message = Message::builder()
+        .from(config.from.clone())
+        .to(to.unwrap())
+        .subject(email.subject.clone())
+        .body(email.body.clone())
+        .map_err(|e| e.to_string());
+    if let Err(e) = message {
+        return Err(e);
+    }
+    transport
+        .send(&message.unwrap())
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+}
+
+/// Send all queued emails that are due.
+/// Returns the number of sent emails.
+pub fn process_queue(
+    conn: &MysqlConnection,
+    config: &MailConfig,
+    transport: &SmtpTransport,
+) -> Result<usize, DbError> {
+    queue::process(
+        conn,
+        "Email",
+        db::emails::get_pending_ids(conn),
+        db::emails::lock_pending,
+        |email| {
+            let result = send(config, transport, email);
+            let update = match &result {
+                Ok(_) => db::emails::mark_sent(conn, email),
+                Err(e) => db::emails::mark_failed(conn, email, e.clone(), config.max_attempts),
+            };
+            update.map(|_| result.is_ok())
+        },
+    )
+}
+
+/// Setup mailer from `Rocket.toml` configuration file.
+/// Queued emails are sent periodically in the background.
+pub fn setup_mailer(rocket: Rocket) -> Result<Rocket, Rocket> {
+    let enabled = rocket.config().get_bool("mail_enabled").unwrap_or(false);
+    if !enabled {
+        return Ok(rocket);
+    }
+    let interval = rocket
+        .config()
+        .get_int("mail_interval_seconds")
+        .unwrap_or(30)
+        .max(1) as u64;
+    let from = rocket
+        .config()
+        .get_str("mail_from")
+        .unwrap_or("IPRP <noreply@localhost>")
+        .parse::<Mailbox>();
+    let from = match from {
+        Ok(from) => from,
+        Err(e) => {
+            error(&format!("Invalid mail sender address: {}", e));
+            return Err(rocket);
+        }
+    };
+    let config = MailConfig {
+        host: rocket
+            .config()
+            .get_str("smtp_host")
+            .unwrap_or("127.0.0.1")
+            .to_string(),
+        port: rocket.config().get_int("smtp_port").unwrap_or(1025) as u16,
+        tls: rocket.config().get_bool("smtp_tls").unwrap_or(false),
+        username: rocket
+            .config()
+            .get_str("smtp_username")
+            .ok()
+            .map(String::from),
+        password: rocket
+            .config()
+            .get_str("smtp_password")
+            .ok()
+            .map(String::from),
+        from,
+        max_attempts: rocket.config().get_int("mail_max_attempts").unwrap_or(5) as i32,
+    };
+    let transport = match config.transport() {
+        Ok(transport) => transport,
+        Err(e) => {
+            error(&format!("Failed to setup mailer: {}", e));
+            return Err(rocket);
+        }
+    };
+    let url = match database_config("iprp_db", rocket.config()) {
+        Ok(config) => config.url.to_string(),
+        Err(e) => {
+            error(&format!("Failed to read mailer database config: {:?}", e));
+            return Err(rocket);
+        }
+    };
+
+    queue::spawn("Mailer", url, interval, move |conn| {
+        process_queue(conn, &config, &transport)
+    });
+    Ok(rocket)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::models::EmailKind;
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpListener;
+    use std::thread;
+
+    // Minimal SMTP catcher, accepts a single email and returns its data.
+    fn catch_email(listener: TcpListener) -> String {
+        let (stream, _) = listener.accept().unwrap();
+        let mut reader = BufReader::new(stream.try_clone().unwrap());
+        let mut writer = stream;
+        writer.write_all(b"220 localhost\r\n").unwrap();
+        let mut data = String::new();
+        let mut in_data = false;
+        loop {
+            let mut line = String::new();
+            if reader.read_line(&mut line).unwrap() == 0 {
+                break;
+            }
+            if in_data {
+                if line == ".\r\n" {
+                    in_data = false;
+                    writer.write_all(b"250 OK\r\n").unwrap();
+                } else {
+                    data.push_str(&line);
+                }
+                continue;
+            }
+            let command = line.to_uppercase();
+            if command.starts_with("DATA") {
+                in_data = true;
+                writer.write_all(b"354 Go ahead\r\n").unwrap();
+            } else if command.starts_with("QUIT") {
+                writer.write_all(b"221 Bye\r\n").unwrap();
+                break;
+            } else {
+                writer.write_all(b"250 OK\r\n").unwrap();
+            }
+        }
+        data
+    }
+
+    fn queued_email(address: &str) -> QueuedEmail {
+        let now = chrono::Local::now().naive_local();
+        QueuedEmail {
+            id: 1,
+            recipient: 1,
+            address: address.to_string(),
+            kind: EmailKind::Assigned,
+            subject: "New review".to_string(),
+            body: "Please review".to_string(),
+            attempts: 0,
+            sent: false,
+            failed: false,
+            created: now,
+            nextattempt: now,
+            lasterror: None,
+        }
+    }
+
+    fn mail_config(port: u16) -> MailConfig {
+        MailConfig {
+            host: "127.0.0.1".to_string(),
+            port,
+            tls: false,
+            username: None,
+            password: None,
+            from: "IPRP <noreply@localhost>".parse().unwrap(),
+            max_attempts: 5,
+        }
+    }
+
+    #[test]
+    fn send_ok() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let config = mail_config(listener.local_addr().unwrap().port());
+        let catcher = thread::spawn(move || catch_email(listener));
+        let transport = config.transport().unwrap();
+        let result = send(&config, &transport, &queued_email("student@localhost"));
+        assert_eq!(result, Ok(()));
+        let data = catcher.join().unwrap();
+        assert!(data.contains("Subject: New review"));
+        assert!(data.contains("To: student@localhost"));
+        assert!(data.contains("Please review"));
+    }
+
+    #[test]
+    fn send_invalid_address_err() {
+        let config = mail_config(1);
+        let transport = config.transport().unwrap();
+        assert!(send(&config, &transport, &queued_email("no address")).is_err());
+    }
+}
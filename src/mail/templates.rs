@@ -0,0 +1,107 @@
+//! Templates of notification emails.
+
+use crate::db::models::EmailKind;
+
+const DATE_FORMAT: &str = "%d.%m.%Y %H:%M";
+
+/// Rendered email ready to be queued.
+#[derive(Debug, PartialEq)]
+pub struct Email {
+    pub kind: EmailKind,
+    pub subject: String,
+    pub body: String,
+}
+
+// Wrap the content of an email with greeting & footer.
+fn render(kind: EmailKind, firstname: &str, subject: String, content: String) -> Email {
+    let body = format!(
+        "Hello {},\n\n{}\n\nYou can change which emails you receive in your notification settings.",
+        firstname, content
+    );
+    Email {
+        kind,
+        subject,
+        body,
+    }
+}
+
+/// New review assigned to a student.
+pub fn review_assigned(firstname: &str, workshop: &str, deadline: &chrono::NaiveDateTime) -> Email {
+    render(
+        EmailKind::Assigned,
+        firstname,
+        format!("New review in {}", workshop),
+        format!(
+            "a new submission in the workshop \"{}\" is waiting for your review.\nThe review needs to be submitted until {}.",
+            workshop,
+            deadline.format(DATE_FORMAT)
+        ),
+    )
+}
+
+/// Review deadline ends soon.
+pub fn review_deadline(
+    firstname: &str,
+    workshop: &str,
+    submission: &str,
+    deadline: &chrono::NaiveDateTime,
+) -> Email {
+    render(
+        EmailKind::Deadline,
+        firstname,
+        format!("Review deadline in {}", workshop),
+        format!(
+            "your review of \"{}\" in the workshop \"{}\" is not submitted yet.\nThe deadline ends at {}.",
+            submission,
+            workshop,
+            deadline.format(DATE_FORMAT)
+        ),
+    )
+}
+
+/// Review of a submission was submitted.
+pub fn review_received(firstname: &str, workshop: &str, submission: &str) -> Email {
+    render(
+        EmailKind::Received,
+        firstname,
+        format!("New review in {}", workshop),
+        format!(
+            "your submission \"{}\" in the workshop \"{}\" received a new review.\nPoints are shown once all reviews are done.",
+            submission, workshop
+        ),
+    )
+}
+
+/// Student was added to a new workshop.
+pub fn workshop_created(firstname: &str, workshop: &str) -> Email {
+    render(
+        EmailKind::Workshop,
+        firstname,
+        format!("New workshop {}", workshop),
+        format!("you were added to the new workshop \"{}\".", workshop),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    #[test]
+    fn review_assigned_ok() {
+        let deadline = NaiveDate::from_ymd(2022, 3, 14).and_hms(9, 30, 0);
+        let email = review_assigned("Max", "WS", &deadline);
+        assert_eq!(email.kind, EmailKind::Assigned);
+        assert_eq!(email.subject, "New review in WS");
+        assert!(email.body.starts_with("Hello Max,"));
+        assert!(email.body.contains("14.03.2022 09:30"));
+    }
+
+    #[test]
+    fn workshop_created_ok() {
+        let email = workshop_created("Max", "WS");
+        assert_eq!(email.kind, EmailKind::Workshop);
+        assert_eq!(email.subject, "New workshop WS");
+        assert!(email.body.contains("\"WS\""));
+    }
+}
@@ -17,6 +17,9 @@ extern crate diesel_derive_enum;
 extern crate serde_derive;
 extern crate base64;
 extern crate crypto;
+extern crate lettre;
+#[macro_use]
+extern crate lazy_static;
 
 use rocket::fairing::AdHoc;
 use rocket_cors::CorsOptions;
@@ -35,6 +38,16 @@ mod auth;
 mod cors;
 // import utilities
 mod utils;
+// import grading logic
+mod grading;
+// import email notifications
+mod mail;
+// import live update events
+mod events;
+// import outgoing webhooks
+mod webhooks;
+// import grade exports
+mod export;
 
 // Configure Database
 #[database("iprp_db")]
@@ -57,6 +70,12 @@ fn main() {
         ))
         .attach(IprpDB::fairing())
         .attach(AdHoc::on_attach("Database Migration", db::run_db_migration))
+        .attach(AdHoc::on_attach(
+            "Scheduler",
+            db::scheduler::setup_scheduler,
+        ))
+        .attach(AdHoc::on_attach("Mailer", mail::setup_mailer))
+        .attach(AdHoc::on_attach("Webhooks", webhooks::setup_webhooks))
         .attach(cors)
         .mount(
             "/",
@@ -65,12 +84,47 @@ fn main() {
                 routes::users::logout,
                 routes::users::create_student,
                 routes::users::create_teacher,
+                routes::users::email_preferences,
+                routes::users::update_email_preferences,
+                routes::users::calendar,
+                routes::users::reset_calendar,
+                routes::users::calendar_feed,
+                routes::notifications::notifications,
+                routes::notifications::unread_notifications,
+                routes::notifications::read_notification,
+                routes::notifications::read_notifications,
+                routes::notifications::event_stream,
                 routes::teachers::workshop,
+                routes::teachers::reviewers,
+                routes::teachers::appeals,
+                routes::teachers::resolve_appeal,
+                routes::teachers::create_calibration,
+                routes::teachers::calibration,
+                routes::teachers::self_assessments,
+                routes::teachers::statistics,
+                routes::teachers::export_grades,
+                routes::teachers::scheduler,
+                routes::teachers::extensions,
+                routes::teachers::grant_submission_extension,
+                routes::teachers::revoke_submission_extension,
+                routes::teachers::grant_review_extension,
+                routes::teachers::revoke_review_extension,
+                routes::teachers::create_team,
+                routes::teachers::update_team,
+                routes::teachers::delete_team,
+                routes::teachers::webhooks,
+                routes::teachers::create_webhook,
+                routes::teachers::update_webhook,
+                routes::teachers::delete_webhook,
+                routes::teachers::webhook_deliveries,
                 routes::teachers::workshops,
                 routes::teachers::search_student,
                 routes::teachers::create_workshop,
                 routes::teachers::update_workshop,
                 routes::teachers::delete_workshop,
+                routes::teachers::create_review,
+                routes::teachers::override_points,
+                routes::teachers::reset_points,
                 routes::attachments::upload,
                 routes::attachments::download,
                 routes::attachments::remove,
@@ -80,7 +134,14 @@ fn main() {
                 routes::submissions::create_submission,
                 routes::submissions::get_submission,
                 routes::submissions::update_submission,
+                routes::submissions::resubmit_submission,
+                routes::submissions::withdraw_submission,
+                routes::submissions::get_versions,
+                routes::submissions::get_diff,
                 routes::submissions::update_review,
+                routes::submissions::submit_review,
+                routes::submissions::rate_review,
+                routes::submissions::appeal_review,
                 routes::submissions::get_review,
             ],
         )
@@ -29,6 +29,7 @@ pub struct User {
     pub password: String,
     pub role: Role,
     pub unit: Option<String>,
+    pub email: Option<String>,
 }
 
 #[derive(Insertable)]
@@ -40,6 +41,7 @@ pub struct NewStudent {
     pub password: String,
     pub role: Role,
     pub unit: String,
+    pub email: Option<String>,
 }
 
 impl NewStudent {
@@ -49,6 +51,7 @@ impl NewStudent {
         lastname: String,
         password: String,
         unit: String,
+        email: Option<String>,
     ) -> Self {
         let role = Role::Student;
         NewStudent {
@@ -58,6 +61,7 @@ impl NewStudent {
             password,
             role,
             unit,
+            email,
         }
     }
 }
@@ -70,10 +74,17 @@ pub struct NewTeacher {
     pub lastname: String,
     pub password: String,
     pub role: Role,
+    pub email: Option<String>,
 }
 
 impl NewTeacher {
-    pub fn new(username: String, firstname: String, lastname: String, password: String) -> Self {
+    pub fn new(
+        username: String,
+        firstname: String,
+        lastname: String,
+        password: String,
+        email: Option<String>,
+    ) -> Self {
         let role = Role::Teacher;
         NewTeacher {
             username,
@@ -81,11 +92,14 @@ impl NewTeacher {
             lastname,
             password,
             role,
+            email,
         }
     }
 }
 
 #[derive(Queryable, AsChangeset, Clone)]
+// Optional settings like phase dates can be removed on update
+#[changeset_options(treat_none_as_null = "true")]
 pub struct Workshop {
     pub id: u64,
     pub title: String,
@@ -93,6 +107,44 @@ pub struct Workshop {
     pub end: chrono::NaiveDateTime,
     pub anonymous: bool,
     pub reviewtimespan: i64,
+    pub teacherweight: f64,
+    pub aggregation: Aggregation,
+    pub gradescale: GradeScale,
+    pub commentthreshold: Option<f64>,
+    pub draftpolicy: DraftPolicy,
+    pub reviewingweight: f64,
+    pub appealwindow: i64,
+    pub selfassessment: bool,
+    pub submissionstart: Option<chrono::NaiveDateTime>,
+    pub submissionend: Option<chrono::NaiveDateTime>,
+    pub reviewend: Option<chrono::NaiveDateTime>,
+    pub resultsdate: Option<chrono::NaiveDateTime>,
+    pub latepolicy: LatePolicy,
+    pub latepenalty: f64,
+    pub rounds: i32,
+    pub samereviewers: bool,
+    pub roundpolicy: RoundPolicy,
+}
+
+impl Workshop {
+    /// Current phase of the workshop.
+    /// Without explicit phase dates submissions are open until the workshop ends.
+    pub fn phase(&self, now: chrono::NaiveDateTime) -> Phase {
+        if self.submissionstart.map_or(false, |start| now < start) {
+            Phase::Upcoming
+        } else if now <= self.submissionend.unwrap_or(self.end) {
+            Phase::Submission
+        } else if now <= self.reviewend.unwrap_or(self.end) {
+            Phase::Review
+        } else {
+            Phase::Closed
+        }
+    }
+
+    /// Check if students can see points and received reviews.
+    pub fn results_released(&self, now: chrono::NaiveDateTime) -> bool {
+        self.resultsdate.map_or(true, |date| now >= date)
+    }
 }
 
 #[derive(Insertable)]
@@ -103,6 +155,225 @@ pub struct NewWorkshop {
     pub end: chrono::NaiveDateTime,
     pub anonymous: bool,
     pub reviewtimespan: i64,
+    pub teacherweight: f64,
+    pub aggregation: Aggregation,
+    pub gradescale: GradeScale,
+    pub commentthreshold: Option<f64>,
+    pub draftpolicy: DraftPolicy,
+    pub reviewingweight: f64,
+    pub appealwindow: i64,
+    pub selfassessment: bool,
+    pub submissionstart: Option<chrono::NaiveDateTime>,
+    pub submissionend: Option<chrono::NaiveDateTime>,
+    pub reviewend: Option<chrono::NaiveDateTime>,
+    pub resultsdate: Option<chrono::NaiveDateTime>,
+    pub latepolicy: LatePolicy,
+    pub latepenalty: f64,
+    pub rounds: i32,
+    pub samereviewers: bool,
+    pub roundpolicy: RoundPolicy,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Phase {
+    Upcoming,
+    Submission,
+    Review,
+    Closed,
+}
+
+#[derive(DbEnum, Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Aggregation {
+    Mean,
+    Median,
+    Trimmedmean,
+    Weighted,
+}
+
+impl Aggregation {
+    pub fn from(str: &str) -> Result<Self, String> {
+        let input = str.to_lowercase();
+        if input.eq("mean") {
+            Ok(Aggregation::Mean)
+        } else if input.eq("median") {
+            Ok(Aggregation::Median)
+        } else if input.eq("trimmedmean") {
+            Ok(Aggregation::Trimmedmean)
+        } else if input.eq("weighted") {
+            Ok(Aggregation::Weighted)
+        } else {
+            Err(String::new())
+        }
+    }
+}
+
+impl Default for Aggregation {
+    fn default() -> Self {
+        Aggregation::Mean
+    }
+}
+
+#[derive(DbEnum, Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GradeScale {
+    Austrian,
+    German,
+    Swiss,
+}
+
+impl GradeScale {
+    pub fn from(str: &str) -> Result<Self, String> {
+        let input = str.to_lowercase();
+        if input.eq("austrian") {
+            Ok(GradeScale::Austrian)
+        } else if input.eq("german") {
+            Ok(GradeScale::German)
+        } else if input.eq("swiss") {
+            Ok(GradeScale::Swiss)
+        } else {
+            Err(String::new())
+        }
+    }
+}
+
+impl Default for GradeScale {
+    fn default() -> Self {
+        GradeScale::Austrian
+    }
+}
+
+#[derive(DbEnum, Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DraftPolicy {
+    Discard,
+    Acceptcomplete,
+}
+
+impl DraftPolicy {
+    pub fn from(str: &str) -> Result<Self, String> {
+        let input = str.to_lowercase();
+        if input.eq("discard") {
+            Ok(DraftPolicy::Discard)
+        } else if input.eq("acceptcomplete") {
+            Ok(DraftPolicy::Acceptcomplete)
+        } else {
+            Err(String::new())
+        }
+    }
+}
+
+impl Default for DraftPolicy {
+    fn default() -> Self {
+        DraftPolicy::Discard
+    }
+}
+
+#[derive(DbEnum, Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LatePolicy {
+    Block,
+    Accept,
+}
+
+impl LatePolicy {
+    pub fn from(str: &str) -> Result<Self, String> {
+        let input = str.to_lowercase();
+        if input.eq("block") {
+            Ok(LatePolicy::Block)
+        } else if input.eq("accept") {
+            Ok(LatePolicy::Accept)
+        } else {
+            Err(String::new())
+        }
+    }
+}
+
+impl Default for LatePolicy {
+    fn default() -> Self {
+        LatePolicy::Block
+    }
+}
+
+#[derive(DbEnum, Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RoundPolicy {
+    Last,
+    Best,
+}
+
+impl RoundPolicy {
+    pub fn from(str: &str) -> Result<Self, String> {
+        let input = str.to_lowercase();
+        if input.eq("last") {
+            Ok(RoundPolicy::Last)
+        } else if input.eq("best") {
+            Ok(RoundPolicy::Best)
+        } else {
+            Err(String::new())
+        }
+    }
+}
+
+impl Default for RoundPolicy {
+    fn default() -> Self {
+        RoundPolicy::Last
+    }
+}
+
+#[derive(DbEnum, Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EmailKind {
+    Assigned,
+    Deadline,
+    Received,
+    Workshop,
+}
+
+#[derive(DbEnum, Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NotificationKind {
+    Assigned,
+    Graded,
+    Workshop,
+    Appeal,
+}
+
+#[derive(DbEnum, Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub enum WebhookEventKind {
+    #[db_rename = "submission.created"]
+    #[serde(rename = "submission.created")]
+    SubmissionCreated,
+    #[db_rename = "review.completed"]
+    #[serde(rename = "review.completed")]
+    ReviewCompleted,
+    #[db_rename = "submission.graded"]
+    #[serde(rename = "submission.graded")]
+    SubmissionGraded,
+    #[db_rename = "workshop.updated"]
+    #[serde(rename = "workshop.updated")]
+    WorkshopUpdated,
+}
+
+impl WebhookEventKind {
+    pub fn to_string(&self) -> String {
+        match self {
+            WebhookEventKind::SubmissionCreated => String::from("submission.created"),
+            WebhookEventKind::ReviewCompleted => String::from("review.completed"),
+            WebhookEventKind::SubmissionGraded => String::from("submission.graded"),
+            WebhookEventKind::WorkshopUpdated => String::from("workshop.updated"),
+        }
+    }
+}
+
+#[derive(DbEnum, Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AppealStatus {
+    Open,
+    Rejected,
+    Discarded,
+    Adjusted,
 }
 
 #[derive(Insertable, Queryable, Clone)]
@@ -119,6 +390,9 @@ pub enum Kind {
     Grade,
     Percentage,
     Truefalse,
+    Range,
+    Rubric,
+    Text,
 }
 
 impl Kind {
@@ -132,18 +406,20 @@ impl Kind {
             Ok(Kind::Percentage)
         } else if input.eq("truefalse") {
             Ok(Kind::Truefalse)
+        } else if input.eq("range") {
+            Ok(Kind::Range)
+        } else if input.eq("rubric") {
+            Ok(Kind::Rubric)
+        } else if input.eq("text") {
+            Ok(Kind::Text)
         } else {
             Err(String::new())
         }
     }
 
-    pub fn max_points(&self) -> f64 {
-        (match *self {
-            Kind::Point => 10,
-            Kind::Grade => 5,
-            Kind::Percentage => 100,
-            Kind::Truefalse => 1,
-        }) as f64
+    /// Text criteria only hold comments and are not part of the points.
+    pub fn is_scored(&self) -> bool {
+        *self != Kind::Text
     }
 }
 
@@ -155,6 +431,24 @@ pub struct Criterion {
     pub weight: f64,
     #[serde(rename = "type")]
     pub kind: Kind,
+    #[serde(rename = "min")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub minpoints: Option<f64>,
+    #[serde(rename = "max")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub maxpoints: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub step: Option<f64>,
+}
+
+impl Criterion {
+    /// Custom point range of range and rubric criteria.
+    pub fn custom_range(&self) -> Option<(f64, f64)> {
+        match (self.minpoints, self.maxpoints) {
+            (Some(min), Some(max)) => Some((min, max)),
+            _ => None,
+        }
+    }
 }
 
 #[derive(Insertable)]
@@ -164,6 +458,28 @@ pub struct NewCriterion {
     pub content: String,
     pub weight: f64,
     pub kind: Kind,
+    pub minpoints: Option<f64>,
+    pub maxpoints: Option<f64>,
+    pub step: Option<f64>,
+}
+
+#[derive(Queryable, Clone, Serialize)]
+pub struct RubricLevel {
+    pub id: u64,
+    #[serde(skip)]
+    pub criterion: u64,
+    pub title: String,
+    pub content: String,
+    pub points: f64,
+}
+
+#[derive(Insertable)]
+#[table_name = "rubriclevels"]
+pub struct NewRubricLevel {
+    pub criterion: u64,
+    pub title: String,
+    pub content: String,
+    pub points: f64,
 }
 
 #[derive(Insertable, Queryable, Clone)]
@@ -244,6 +560,13 @@ pub struct Submission {
     pub meanpoints: Option<f64>,
     pub maxpoint: Option<f64>,
     pub deadline: chrono::NaiveDateTime,
+    pub computedpoints: Option<f64>,
+    pub justification: Option<String>,
+    pub calibration: bool,
+    pub late: bool,
+    pub team: Option<u64>,
+    pub round: i32,
+    pub previous: Option<u64>,
 }
 
 #[derive(Insertable, Queryable, Clone)]
@@ -258,6 +581,174 @@ pub struct NewSubmission {
     pub locked: bool,
     pub reviewsdone: bool,
     pub error: bool,
+    pub calibration: bool,
+    pub late: bool,
+    pub team: Option<u64>,
+    pub round: i32,
+    pub previous: Option<u64>,
+}
+
+/*
+CREATE TABLE submissionextensions
+(
+    workshop BIGINT UNSIGNED NOT NULL,
+    student  BIGINT UNSIGNED NOT NULL,
+    end      DATETIME        NOT NULL,
+    PRIMARY KEY (workshop, student),
+    FOREIGN KEY (workshop) REFERENCES workshops (id) ON DELETE CASCADE,
+    FOREIGN KEY (student) REFERENCES users (id) ON DELETE CASCADE
+);
+ */
+
+#[derive(Insertable, Queryable, Clone)]
+#[table_name = "submissionextensions"]
+pub struct SubmissionExtension {
+    pub workshop: u64,
+    pub student: u64,
+    pub end: chrono::NaiveDateTime,
+}
+
+/*
+CREATE TABLE reviewextensions
+(
+    review BIGINT UNSIGNED NOT NULL PRIMARY KEY,
+    end    DATETIME        NOT NULL,
+    FOREIGN KEY (review) REFERENCES reviews (id) ON DELETE CASCADE
+);
+
+CREATE TABLE extensionhistory
+(
+    id       SERIAL PRIMARY KEY,
+    workshop BIGINT UNSIGNED NOT NULL,
+    student  BIGINT UNSIGNED NOT NULL,
+    review   BIGINT UNSIGNED,
+    teacher  BIGINT UNSIGNED,
+    end      DATETIME,
+    date     DATETIME        NOT NULL,
+    FOREIGN KEY (workshop) REFERENCES workshops (id) ON DELETE CASCADE,
+    FOREIGN KEY (student) REFERENCES users (id) ON DELETE CASCADE,
+    FOREIGN KEY (review) REFERENCES reviews (id) ON DELETE CASCADE,
+    FOREIGN KEY (teacher) REFERENCES users (id) ON DELETE SET NULL
+);
+ */
+
+#[derive(Insertable, Queryable, Clone)]
+#[table_name = "reviewextensions"]
+pub struct ReviewExtension {
+    pub review: u64,
+    pub end: chrono::NaiveDateTime,
+}
+
+#[derive(Queryable, Clone)]
+pub struct ExtensionHistory {
+    pub id: u64,
+    pub workshop: u64,
+    pub student: u64,
+    pub review: Option<u64>,
+    pub teacher: Option<u64>,
+    pub end: Option<chrono::NaiveDateTime>,
+    pub date: chrono::NaiveDateTime,
+}
+
+#[derive(Insertable)]
+#[table_name = "extensionhistory"]
+pub struct NewExtensionHistory {
+    pub workshop: u64,
+    pub student: u64,
+    pub review: Option<u64>,
+    pub teacher: Option<u64>,
+    pub end: Option<chrono::NaiveDateTime>,
+    pub date: chrono::NaiveDateTime,
+}
+
+/*
+CREATE TABLE teams
+(
+    id       SERIAL PRIMARY KEY,
+    workshop BIGINT UNSIGNED NOT NULL,
+    title    VARCHAR(255)    NOT NULL,
+    FOREIGN KEY (workshop) REFERENCES workshops (id) ON DELETE CASCADE
+);
+
+CREATE TABLE teammembers
+(
+    team    BIGINT UNSIGNED NOT NULL,
+    student BIGINT UNSIGNED NOT NULL,
+    PRIMARY KEY (team, student),
+    FOREIGN KEY (team) REFERENCES teams (id) ON DELETE CASCADE,
+    FOREIGN KEY (student) REFERENCES users (id) ON DELETE CASCADE
+);
+ */
+
+#[derive(Queryable, AsChangeset, Clone)]
+pub struct Team {
+    pub id: u64,
+    pub workshop: u64,
+    pub title: String,
+}
+
+#[derive(Insertable)]
+#[table_name = "teams"]
+pub struct NewTeam {
+    pub workshop: u64,
+    pub title: String,
+}
+
+#[derive(Insertable, Queryable, Clone)]
+#[table_name = "teammembers"]
+pub struct TeamMember {
+    pub team: u64,
+    pub student: u64,
+}
+
+/*
+CREATE TABLE submissionversions
+(
+    id         SERIAL PRIMARY KEY,
+    submission BIGINT UNSIGNED NOT NULL,
+    version    INT             NOT NULL,
+    title      VARCHAR(255)    NOT NULL,
+    comment    TEXT            NOT NULL,
+    date       DATETIME        NOT NULL,
+    UNIQUE (submission, version),
+    FOREIGN KEY (submission) REFERENCES submissions (id) ON DELETE CASCADE
+);
+
+CREATE TABLE versionattachments
+(
+    version    BIGINT UNSIGNED NOT NULL,
+    attachment BIGINT UNSIGNED NOT NULL,
+    PRIMARY KEY (version, attachment),
+    FOREIGN KEY (version) REFERENCES submissionversions (id) ON DELETE CASCADE,
+    FOREIGN KEY (attachment) REFERENCES attachments (id) ON DELETE CASCADE
+);
+ */
+
+#[derive(Queryable, Clone)]
+pub struct SubmissionVersion {
+    pub id: u64,
+    pub submission: u64,
+    pub version: i32,
+    pub title: String,
+    pub comment: String,
+    pub date: chrono::NaiveDateTime,
+}
+
+#[derive(Insertable)]
+#[table_name = "submissionversions"]
+pub struct NewSubmissionVersion {
+    pub submission: u64,
+    pub version: i32,
+    pub title: String,
+    pub comment: String,
+    pub date: chrono::NaiveDateTime,
+}
+
+#[derive(Insertable, Queryable, Clone)]
+#[table_name = "versionattachments"]
+pub struct VersionAttachment {
+    pub version: u64,
+    pub attachment: u64,
 }
 
 #[derive(Insertable, Queryable, Clone)]
@@ -320,6 +811,11 @@ pub struct Review {
     pub done: bool,
     pub locked: bool,
     pub error: bool,
+    pub teacher: bool,
+    pub discarded: bool,
+    pub selfassessment: bool,
+    pub version: Option<u64>,
+    pub reminded: bool,
 }
 
 #[derive(Insertable, Queryable, Clone)]
@@ -333,6 +829,9 @@ pub struct NewReview {
     pub done: bool,
     pub locked: bool,
     pub error: bool,
+    pub teacher: bool,
+    pub discarded: bool,
+    pub selfassessment: bool,
 }
 
 #[derive(Insertable, Queryable, Clone)]
@@ -340,5 +839,325 @@ pub struct NewReview {
 pub struct ReviewPoints {
     pub review: u64,
     pub criterion: u64,
-    pub points: f64,
+    pub points: Option<f64>,
+    pub comment: Option<String>,
+}
+
+/*
+CREATE TABLE reviewratings
+(
+    review      BIGINT UNSIGNED NOT NULL PRIMARY KEY,
+    helpfulness INT             NOT NULL,
+    comment     TEXT,
+    FOREIGN KEY (review) REFERENCES reviews (id) ON DELETE CASCADE
+);
+ */
+
+#[derive(Insertable, Queryable, Clone, Serialize)]
+#[table_name = "reviewratings"]
+pub struct ReviewRating {
+    #[serde(skip)]
+    pub review: u64,
+    pub helpfulness: i32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub comment: Option<String>,
+}
+
+/*
+CREATE TABLE appeals
+(
+    id       SERIAL PRIMARY KEY,
+    review   BIGINT UNSIGNED                                       NOT NULL UNIQUE,
+    student  BIGINT UNSIGNED,
+    reason   TEXT                                                  NOT NULL,
+    status   enum ('open', 'rejected', 'discarded', 'adjusted') NOT NULL DEFAULT 'open',
+    response TEXT,
+    date     DATETIME                                              NOT NULL,
+    FOREIGN KEY (review) REFERENCES reviews (id) ON DELETE CASCADE,
+    FOREIGN KEY (student) REFERENCES users (id) ON DELETE SET NULL
+);
+ */
+
+#[derive(Queryable, AsChangeset, Clone, Serialize)]
+pub struct Appeal {
+    pub id: u64,
+    #[serde(skip)]
+    pub review: u64,
+    #[serde(skip)]
+    pub student: Option<u64>,
+    pub reason: String,
+    pub status: AppealStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub response: Option<String>,
+    pub date: chrono::NaiveDateTime,
+}
+
+#[derive(Insertable)]
+#[table_name = "appeals"]
+pub struct NewAppeal {
+    pub review: u64,
+    pub student: Option<u64>,
+    pub reason: String,
+    pub status: AppealStatus,
+    pub date: chrono::NaiveDateTime,
+}
+
+/*
+CREATE TABLE emailpreferences
+(
+    user     BIGINT UNSIGNED PRIMARY KEY,
+    assigned BOOL NOT NULL DEFAULT TRUE,
+    deadline BOOL NOT NULL DEFAULT TRUE,
+    received BOOL NOT NULL DEFAULT TRUE,
+    workshop BOOL NOT NULL DEFAULT TRUE,
+    FOREIGN KEY (user) REFERENCES users (id) ON DELETE CASCADE
+);
+
+CREATE TABLE emailqueue
+(
+    id          SERIAL PRIMARY KEY,
+    recipient   BIGINT UNSIGNED                                       NOT NULL,
+    address     VARCHAR(255)                                          NOT NULL,
+    kind        enum ('assigned', 'deadline', 'received', 'workshop') NOT NULL,
+    subject     VARCHAR(255)                                          NOT NULL,
+    body        TEXT                                                  NOT NULL,
+    attempts    INT                                                   NOT NULL DEFAULT 0,
+    sent        BOOL                                                  NOT NULL DEFAULT FALSE,
+    failed      BOOL                                                  NOT NULL DEFAULT FALSE,
+    created     DATETIME                                              NOT NULL,
+    nextattempt DATETIME                                              NOT NULL,
+    lasterror   TEXT,
+    FOREIGN KEY (recipient) REFERENCES users (id) ON DELETE CASCADE
+);
+ */
+
+#[derive(Insertable, Queryable, AsChangeset, Clone, Serialize)]
+#[table_name = "emailpreferences"]
+pub struct EmailPreference {
+    #[serde(skip)]
+    pub user: u64,
+    pub assigned: bool,
+    pub deadline: bool,
+    pub received: bool,
+    pub workshop: bool,
+}
+
+impl EmailPreference {
+    /// Users without stored preferences receive all emails.
+    pub fn new(user: u64) -> Self {
+        EmailPreference {
+            user,
+            assigned: true,
+            deadline: true,
+            received: true,
+            workshop: true,
+        }
+    }
+
+    /// Check if the user wants to receive emails of the given kind.
+    pub fn allows(&self, kind: &EmailKind) -> bool {
+        match kind {
+            EmailKind::Assigned => self.assigned,
+            EmailKind::Deadline => self.deadline,
+            EmailKind::Received => self.received,
+            EmailKind::Workshop => self.workshop,
+        }
+    }
+}
+
+#[derive(Queryable, Clone)]
+pub struct QueuedEmail {
+    pub id: u64,
+    pub recipient: u64,
+    pub address: String,
+    pub kind: EmailKind,
+    pub subject: String,
+    pub body: String,
+    pub attempts: i32,
+    pub sent: bool,
+    pub failed: bool,
+    pub created: chrono::NaiveDateTime,
+    pub nextattempt: chrono::NaiveDateTime,
+    pub lasterror: Option<String>,
+}
+
+#[derive(Insertable)]
+#[table_name = "emailqueue"]
+pub struct NewQueuedEmail {
+    pub recipient: u64,
+    pub address: String,
+    pub kind: EmailKind,
+    pub subject: String,
+    pub body: String,
+    pub created: chrono::NaiveDateTime,
+    pub nextattempt: chrono::NaiveDateTime,
+}
+
+/*
+CREATE TABLE notifications
+(
+    id         SERIAL PRIMARY KEY,
+    user       BIGINT UNSIGNED                                   NOT NULL,
+    kind       enum ('assigned', 'graded', 'workshop', 'appeal') NOT NULL,
+    title      VARCHAR(255)                                      NOT NULL,
+    content    TEXT                                              NOT NULL,
+    workshop   BIGINT UNSIGNED,
+    submission BIGINT UNSIGNED,
+    seen       BOOL                                              NOT NULL DEFAULT FALSE,
+    date       DATETIME                                          NOT NULL,
+    FOREIGN KEY (user) REFERENCES users (id) ON DELETE CASCADE,
+    FOREIGN KEY (workshop) REFERENCES workshops (id) ON DELETE CASCADE,
+    FOREIGN KEY (submission) REFERENCES submissions (id) ON DELETE CASCADE
+);
+ */
+
+#[derive(Queryable, Clone, Serialize)]
+pub struct Notification {
+    pub id: u64,
+    #[serde(skip)]
+    pub user: u64,
+    pub kind: NotificationKind,
+    pub title: String,
+    pub content: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub workshop: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub submission: Option<u64>,
+    #[serde(rename(serialize = "read"))]
+    pub seen: bool,
+    pub date: chrono::NaiveDateTime,
+}
+
+#[derive(Insertable)]
+#[table_name = "notifications"]
+pub struct NewNotification {
+    pub user: u64,
+    pub kind: NotificationKind,
+    pub title: String,
+    pub content: String,
+    pub workshop: Option<u64>,
+    pub submission: Option<u64>,
+    pub date: chrono::NaiveDateTime,
+}
+
+/*
+CREATE TABLE webhooks
+(
+    id       SERIAL PRIMARY KEY,
+    owner    BIGINT UNSIGNED NOT NULL,
+    workshop BIGINT UNSIGNED,
+    url      VARCHAR(2048)   NOT NULL,
+    secret   VARCHAR(255)    NOT NULL,
+    active   BOOL            NOT NULL DEFAULT TRUE,
+    created  DATETIME        NOT NULL,
+    FOREIGN KEY (owner) REFERENCES users (id) ON DELETE CASCADE,
+    FOREIGN KEY (workshop) REFERENCES workshops (id) ON DELETE CASCADE
+);
+ */
+
+#[derive(Queryable, Clone, Serialize)]
+pub struct Webhook {
+    pub id: u64,
+    pub owner: u64,
+    pub workshop: Option<u64>,
+    pub url: String,
+    #[serde(skip)]
+    pub secret: String,
+    pub active: bool,
+    pub created: chrono::NaiveDateTime,
+}
+
+#[derive(Insertable)]
+#[table_name = "webhooks"]
+pub struct NewWebhook {
+    pub owner: u64,
+    pub workshop: Option<u64>,
+    pub url: String,
+    pub secret: String,
+    pub active: bool,
+    pub created: chrono::NaiveDateTime,
+}
+
+/*
+CREATE TABLE webhookevents
+(
+    webhook BIGINT UNSIGNED                                                                          NOT NULL,
+    event   enum ('submission.created', 'review.completed', 'submission.graded', 'workshop.updated') NOT NULL,
+    PRIMARY KEY (webhook, event),
+    FOREIGN KEY (webhook) REFERENCES webhooks (id) ON DELETE CASCADE
+);
+ */
+
+#[derive(Insertable, Queryable, Clone)]
+#[table_name = "webhookevents"]
+pub struct WebhookEvent {
+    pub webhook: u64,
+    pub event: WebhookEventKind,
+}
+
+/*
+CREATE TABLE webhookdeliveries
+(
+    id          SERIAL PRIMARY KEY,
+    webhook     BIGINT UNSIGNED                                                                          NOT NULL,
+    event       enum ('submission.created', 'review.completed', 'submission.graded', 'workshop.updated') NOT NULL,
+    payload     TEXT                                                                                     NOT NULL,
+    attempts    INT                                                                                      NOT NULL DEFAULT 0,
+    delivered   BOOL                                                                                     NOT NULL DEFAULT FALSE,
+    failed      BOOL                                                                                     NOT NULL DEFAULT FALSE,
+    status      INT,
+    lasterror   TEXT,
+    created     DATETIME                                                                                 NOT NULL,
+    nextattempt DATETIME                                                                                 NOT NULL,
+    lastattempt DATETIME,
+    FOREIGN KEY (webhook) REFERENCES webhooks (id) ON DELETE CASCADE
+);
+ */
+
+#[derive(Queryable, Clone, Serialize)]
+pub struct WebhookDelivery {
+    pub id: u64,
+    #[serde(skip)]
+    pub webhook: u64,
+    pub event: WebhookEventKind,
+    pub payload: String,
+    pub attempts: i32,
+    pub delivered: bool,
+    pub failed: bool,
+    pub status: Option<i32>,
+    #[serde(rename = "lastError")]
+    pub lasterror: Option<String>,
+    pub created: chrono::NaiveDateTime,
+    #[serde(rename = "nextAttempt")]
+    pub nextattempt: chrono::NaiveDateTime,
+    #[serde(rename = "lastAttempt")]
+    pub lastattempt: Option<chrono::NaiveDateTime>,
+}
+
+#[derive(Insertable)]
+#[table_name = "webhookdeliveries"]
+pub struct NewWebhookDelivery {
+    pub webhook: u64,
+    pub event: WebhookEventKind,
+    pub payload: String,
+    pub created: chrono::NaiveDateTime,
+    pub nextattempt: chrono::NaiveDateTime,
+}
+
+/*
+CREATE TABLE calendartokens
+(
+    user    BIGINT UNSIGNED PRIMARY KEY,
+    token   VARCHAR(64) NOT NULL UNIQUE,
+    created DATETIME    NOT NULL,
+    FOREIGN KEY (user) REFERENCES users (id) ON DELETE CASCADE
+);
+ */
+
+#[derive(Insertable, Queryable, Clone)]
+#[table_name = "calendartokens"]
+pub struct CalendarToken {
+    pub user: u64,
+    pub token: String,
+    pub created: chrono::NaiveDateTime,
 }
@@ -6,6 +6,7 @@ use std::fmt::{Debug, Formatter};
 #[derive(Debug)]
 pub enum RouteErrorKind {
     BadRequest,
+    ExportFailed,
 }
 
 impl fmt::Display for RouteErrorKind {
@@ -1,6 +1,7 @@
 pub mod attachments;
 pub mod error;
 pub mod models;
+pub mod notifications;
 pub mod students;
 pub mod submissions;
 pub mod teachers;
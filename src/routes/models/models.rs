@@ -1,6 +1,9 @@
 //! Structs used throughout routes
 
-use crate::db::models::{Kind, NewCriterion};
+use crate::db::models::{
+    Aggregation, AppealStatus, DraftPolicy, GradeScale, Kind, LatePolicy, NewCriterion,
+    NewRubricLevel, NewWorkshopCriterion, RoundPolicy, WebhookEventKind, WorkshopSettings,
+};
 use crate::routes::validation::SimpleValidation;
 use crate::utils::error::AppError;
 use backend_macro_derive::SimpleValidation;
@@ -48,8 +51,52 @@ pub struct RouteUpdateReview {
 #[derive(Serialize, Deserialize, Validate)]
 pub struct RouteUpdatePoints {
     pub id: u64,
+    // Text criteria have no points
+    #[serde(default)]
     #[validate(range(min = 0.0, max = 100.0))]
     pub points: f64,
+    #[serde(default)]
+    pub comment: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Validate, SimpleValidation)]
+pub struct RouteReviewRating {
+    #[validate(range(min = 1, max = 5))]
+    pub helpfulness: i32,
+    #[serde(default)]
+    pub comment: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Validate, SimpleValidation)]
+pub struct RouteNewAppeal {
+    #[validate(length(min = 1))]
+    pub reason: String,
+}
+
+#[derive(Serialize, Deserialize, Validate, SimpleValidation)]
+#[validate(schema(function = "validate_route_resolve_appeal"))]
+pub struct RouteResolveAppeal {
+    pub status: AppealStatus,
+    #[serde(default)]
+    pub response: Option<String>,
+    // Only used to adjust points
+    #[serde(default)]
+    #[validate]
+    pub points: Vec<RouteUpdatePoints>,
+}
+
+// Appeals can only be resolved, adjusted appeals need the new points
+fn validate_route_resolve_appeal(rra: &RouteResolveAppeal) -> Result<(), ValidationError> {
+    match rra.status {
+        AppealStatus::Open => Err(ValidationError::new("Appeal must be resolved")),
+        AppealStatus::Adjusted if rra.points.len() == 0 => {
+            Err(ValidationError::new("Adjusted appeals need points"))
+        }
+        AppealStatus::Rejected | AppealStatus::Discarded if rra.points.len() > 0 => Err(
+            ValidationError::new("Only adjusted appeals can have points"),
+        ),
+        _ => Ok(()),
+    }
 }
 
 // Student & Teacher
@@ -60,6 +107,7 @@ pub struct RouteWorkshopResponse {
 }
 
 #[derive(Debug, Deserialize, Validate)]
+#[validate(schema(function = "validate_route_criterion"))]
 pub struct RouteCriterion {
     #[validate(length(min = 1))]
     title: String,
@@ -69,6 +117,27 @@ pub struct RouteCriterion {
     weight: f64,
     #[serde(rename = "type")]
     kind: Kind,
+    // Only used by range criteria
+    #[serde(default)]
+    min: Option<f64>,
+    #[serde(default)]
+    max: Option<f64>,
+    #[serde(default)]
+    step: Option<f64>,
+    // Only used by rubric criteria
+    #[serde(default)]
+    #[validate]
+    levels: Vec<RouteRubricLevel>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct RouteRubricLevel {
+    #[validate(length(min = 1))]
+    title: String,
+    #[serde(default)]
+    content: String,
+    #[validate(range(min = 0.0, max = 100.0))]
+    points: f64,
 }
 // Pass default value to serde
 // See: https://stackoverflow.com/a/65973982/12347616
@@ -77,23 +146,95 @@ fn route_criterion_default_weight() -> f64 {
     ROUTE_CRITERION_DEFAULT_WEIGHT
 }
 
-impl From<RouteCriterion> for NewCriterion {
+// Review points of range criteria need to be in the same range as other review points
+fn validate_route_criterion(rc: &RouteCriterion) -> Result<(), ValidationError> {
+    match rc.kind {
+        Kind::Range => {
+            if let (Some(min), Some(max)) = (rc.min, rc.max) {
+                if min < 0.0 || max > 100.0 || min >= max {
+                    return Err(ValidationError::new(
+                        "Range must be between 0 and 100 and min must be lower than max",
+                    ));
+                }
+                if let Some(step) = rc.step {
+                    if step <= 0.0 || step > max - min {
+                        return Err(ValidationError::new(
+                            "Step must be greater than 0 and fit into the range",
+                        ));
+                    }
+                }
+                Ok(())
+            } else {
+                Err(ValidationError::new("Range criteria need min and max"))
+            }
+        }
+        Kind::Rubric => {
+            let max = rc
+                .levels
+                .iter()
+                .map(|level| level.points)
+                .fold(0.0, f64::max);
+            if max > 0.0 {
+                Ok(())
+            } else {
+                Err(ValidationError::new(
+                    "Rubric criteria need levels with points greater than 0",
+                ))
+            }
+        }
+        _ => Ok(()),
+    }
+}
+
+impl From<RouteCriterion> for NewWorkshopCriterion {
     fn from(item: RouteCriterion) -> Self {
-        NewCriterion {
-            title: item.title,
-            content: item.content,
-            weight: item.weight,
-            kind: item.kind,
+        let (minpoints, maxpoints, step) = match item.kind {
+            Kind::Range => (item.min, item.max, item.step),
+            Kind::Rubric => {
+                let max = item
+                    .levels
+                    .iter()
+                    .map(|level| level.points)
+                    .fold(0.0, f64::max);
+                (Some(0.0), Some(max), None)
+            }
+            _ => (None, None, None),
+        };
+        let levels = if item.kind == Kind::Rubric {
+            item.levels
+                .into_iter()
+                .map(|level| NewRubricLevel {
+                    // Assigned on creation
+                    criterion: 0,
+                    title: level.title,
+                    content: level.content,
+                    points: level.points,
+                })
+                .collect()
+        } else {
+            Vec::new()
+        };
+        NewWorkshopCriterion {
+            criterion: NewCriterion {
+                title: item.title,
+                content: item.content,
+                weight: item.weight,
+                kind: item.kind,
+                minpoints,
+                maxpoints,
+                step,
+            },
+            levels,
         }
     }
 }
 
-impl From<RouteCriterionVec> for Vec<NewCriterion> {
+impl From<RouteCriterionVec> for Vec<NewWorkshopCriterion> {
     fn from(items: RouteCriterionVec) -> Self {
         items
             .0
             .into_iter()
-            .map(|item| NewCriterion::from(item))
+            .map(|item| NewWorkshopCriterion::from(item))
             .collect()
     }
 }
@@ -159,6 +300,7 @@ impl Default for NumberVec {
 }
 
 #[derive(FromForm, Deserialize, Validate, SimpleValidation)]
+#[validate(schema(function = "validate_route_new_workshop"))]
 pub struct RouteNewWorkshop {
     #[validate(length(min = 1))]
     pub(crate) title: String,
@@ -170,6 +312,72 @@ pub struct RouteNewWorkshop {
     #[serde(default)]
     #[validate(custom = "validate_review_timespan")]
     pub(crate) review_timespan: Option<i64>,
+    #[serde(rename = "teacherWeight")]
+    #[serde(default = "route_workshop_default_teacher_weight")]
+    #[validate(custom = "validate_teacher_weight")]
+    pub(crate) teacher_weight: f64,
+    #[serde(default)]
+    pub(crate) aggregation: Aggregation,
+    #[serde(rename = "gradeScale")]
+    #[serde(default)]
+    pub(crate) grade_scale: GradeScale,
+    // Comments are required for criteria with a lower fraction of points
+    #[serde(rename = "commentThreshold")]
+    #[serde(default)]
+    #[validate(range(min = 0.0, max = 1.0))]
+    pub(crate) comment_threshold: Option<f64>,
+    // Handling of unsubmitted review drafts on deadline
+    #[serde(rename = "draftPolicy")]
+    #[serde(default)]
+    pub(crate) draft_policy: DraftPolicy,
+    // Share of the helpfulness of written reviews in the final points
+    #[serde(rename = "reviewingWeight")]
+    #[serde(default)]
+    #[validate(range(min = 0.0, max = 1.0))]
+    pub(crate) reviewing_weight: f64,
+    // Minutes after the review deadline in which reviews can be appealed
+    #[serde(rename = "appealWindow")]
+    #[serde(default)]
+    #[validate(range(min = 0))]
+    pub(crate) appeal_window: i64,
+    // Submitters grade their own submission with the workshop criteria
+    #[serde(rename = "selfAssessment")]
+    #[serde(default)]
+    pub(crate) self_assessment: bool,
+    // Optional phase dates, reviews of all submissions end with the review phase
+    #[serde(rename = "submissionStart")]
+    #[serde(default)]
+    pub(crate) submission_start: Option<Date>,
+    #[serde(rename = "submissionEnd")]
+    #[serde(default)]
+    pub(crate) submission_end: Option<Date>,
+    #[serde(rename = "reviewEnd")]
+    #[serde(default)]
+    pub(crate) review_end: Option<Date>,
+    #[serde(rename = "resultsDate")]
+    #[serde(default)]
+    pub(crate) results_date: Option<Date>,
+    // Handling of submissions after the submission phase
+    #[serde(rename = "latePolicy")]
+    #[serde(default)]
+    pub(crate) late_policy: LatePolicy,
+    // Share of the points late submissions lose
+    #[serde(rename = "latePenalty")]
+    #[serde(default)]
+    #[validate(range(min = 0.0, max = 1.0))]
+    pub(crate) late_penalty: f64,
+    // Review rounds, every round after the first is a resubmission
+    #[serde(default = "route_workshop_default_rounds")]
+    #[validate(range(min = 1, max = 10))]
+    pub(crate) rounds: i32,
+    // Resubmissions are reviewed by the reviewers of the previous round
+    #[serde(rename = "sameReviewers")]
+    #[serde(default)]
+    pub(crate) same_reviewers: bool,
+    // Rule that combines the points of all rounds
+    #[serde(rename = "roundPolicy")]
+    #[serde(default)]
+    pub(crate) round_policy: RoundPolicy,
     pub(crate) anonymous: bool,
     pub(crate) teachers: NumberVec,
     pub(crate) students: NumberVec,
@@ -181,6 +389,51 @@ pub struct RouteNewWorkshop {
     pub(crate) attachments: NumberVec,
 }
 
+// Settings of a workshop from the route models of new & updated workshops.
+// The default review timespan is used if none is given.
+macro_rules! workshop_settings {
+    ($workshop:expr, $default_review_timespan:expr) => {
+        WorkshopSettings {
+            title: $workshop.title.clone(),
+            content: $workshop.content.clone(),
+            end: $workshop.end.0,
+            review_timespan: $workshop
+                .review_timespan
+                .unwrap_or($default_review_timespan),
+            teacher_weight: $workshop.teacher_weight,
+            aggregation: $workshop.aggregation.clone(),
+            grade_scale: $workshop.grade_scale.clone(),
+            comment_threshold: $workshop.comment_threshold,
+            draft_policy: $workshop.draft_policy.clone(),
+            reviewing_weight: $workshop.reviewing_weight,
+            appeal_window: $workshop.appeal_window,
+            self_assessment: $workshop.self_assessment,
+            submission_start: $workshop.submission_start.as_ref().map(|date| date.0),
+            submission_end: $workshop.submission_end.as_ref().map(|date| date.0),
+            review_end: $workshop.review_end.as_ref().map(|date| date.0),
+            results_date: $workshop.results_date.as_ref().map(|date| date.0),
+            late_policy: $workshop.late_policy.clone(),
+            late_penalty: $workshop.late_penalty,
+            rounds: $workshop.rounds,
+            same_reviewers: $workshop.same_reviewers,
+            round_policy: $workshop.round_policy.clone(),
+        }
+    };
+}
+
+impl RouteNewWorkshop {
+    /// Settings of the new workshop.
+    /// The default review timespan is used if none is given.
+    pub fn settings(&self, default_review_timespan: i64) -> WorkshopSettings {
+        workshop_settings!(self, default_review_timespan)
+    }
+}
+
+const ROUTE_WORKSHOP_DEFAULT_ROUNDS: i32 = 1;
+fn route_workshop_default_rounds() -> i32 {
+    ROUTE_WORKSHOP_DEFAULT_ROUNDS
+}
+
 fn validate_review_timespan(review_timespan: i64) -> Result<(), ValidationError> {
     if review_timespan > 0 {
         Ok(())
@@ -191,7 +444,56 @@ fn validate_review_timespan(review_timespan: i64) -> Result<(), ValidationError>
     }
 }
 
+fn validate_route_new_workshop(rnw: &RouteNewWorkshop) -> Result<(), ValidationError> {
+    validate_phases(&[
+        &rnw.submission_start,
+        &rnw.submission_end,
+        &rnw.review_end,
+        &rnw.results_date,
+    ])
+}
+
+fn validate_route_update_workshop(ruw: &RouteUpdateWorkshop) -> Result<(), ValidationError> {
+    validate_phases(&[
+        &ruw.submission_start,
+        &ruw.submission_end,
+        &ruw.review_end,
+        &ruw.results_date,
+    ])
+}
+
+// Given phase dates need to follow each other, missing phase dates are skipped
+fn validate_phases(phases: &[&Option<Date>]) -> Result<(), ValidationError> {
+    let dates: Vec<chrono::NaiveDateTime> = phases
+        .iter()
+        .filter_map(|phase| phase.as_ref().map(|date| date.0))
+        .collect();
+    if dates.windows(2).all(|dates| dates[0] < dates[1]) {
+        Ok(())
+    } else {
+        Err(ValidationError::new(
+            "Phase dates must be in chronological order",
+        ))
+    }
+}
+
+const ROUTE_WORKSHOP_DEFAULT_TEACHER_WEIGHT: f64 = 1.0;
+fn route_workshop_default_teacher_weight() -> f64 {
+    ROUTE_WORKSHOP_DEFAULT_TEACHER_WEIGHT
+}
+
+fn validate_teacher_weight(teacher_weight: f64) -> Result<(), ValidationError> {
+    if teacher_weight > 0.0 && teacher_weight <= 100.0 {
+        Ok(())
+    } else {
+        Err(ValidationError::new(
+            "Teacher Weight must be greater than 0 and at most 100",
+        ))
+    }
+}
+
 #[derive(FromForm, Deserialize, Validate, SimpleValidation)]
+#[validate(schema(function = "validate_route_update_workshop"))]
 pub struct RouteUpdateWorkshop {
     #[validate(length(min = 1))]
     pub(crate) title: String,
@@ -202,6 +504,72 @@ pub struct RouteUpdateWorkshop {
     #[serde(default)]
     #[validate(custom = "validate_review_timespan")]
     pub(crate) review_timespan: Option<i64>,
+    #[serde(rename = "teacherWeight")]
+    #[serde(default = "route_workshop_default_teacher_weight")]
+    #[validate(custom = "validate_teacher_weight")]
+    pub(crate) teacher_weight: f64,
+    #[serde(default)]
+    pub(crate) aggregation: Aggregation,
+    #[serde(rename = "gradeScale")]
+    #[serde(default)]
+    pub(crate) grade_scale: GradeScale,
+    // Comments are required for criteria with a lower fraction of points
+    #[serde(rename = "commentThreshold")]
+    #[serde(default)]
+    #[validate(range(min = 0.0, max = 1.0))]
+    pub(crate) comment_threshold: Option<f64>,
+    // Handling of unsubmitted review drafts on deadline
+    #[serde(rename = "draftPolicy")]
+    #[serde(default)]
+    pub(crate) draft_policy: DraftPolicy,
+    // Share of the helpfulness of written reviews in the final points
+    #[serde(rename = "reviewingWeight")]
+    #[serde(default)]
+    #[validate(range(min = 0.0, max = 1.0))]
+    pub(crate) reviewing_weight: f64,
+    // Minutes after the review deadline in which reviews can be appealed
+    #[serde(rename = "appealWindow")]
+    #[serde(default)]
+    #[validate(range(min = 0))]
+    pub(crate) appeal_window: i64,
+    // Submitters grade their own submission with the workshop criteria
+    #[serde(rename = "selfAssessment")]
+    #[serde(default)]
+    pub(crate) self_assessment: bool,
+    // Optional phase dates, reviews of all submissions end with the review phase
+    #[serde(rename = "submissionStart")]
+    #[serde(default)]
+    pub(crate) submission_start: Option<Date>,
+    #[serde(rename = "submissionEnd")]
+    #[serde(default)]
+    pub(crate) submission_end: Option<Date>,
+    #[serde(rename = "reviewEnd")]
+    #[serde(default)]
+    pub(crate) review_end: Option<Date>,
+    #[serde(rename = "resultsDate")]
+    #[serde(default)]
+    pub(crate) results_date: Option<Date>,
+    // Handling of submissions after the submission phase
+    #[serde(rename = "latePolicy")]
+    #[serde(default)]
+    pub(crate) late_policy: LatePolicy,
+    // Share of the points late submissions lose
+    #[serde(rename = "latePenalty")]
+    #[serde(default)]
+    #[validate(range(min = 0.0, max = 1.0))]
+    pub(crate) late_penalty: f64,
+    // Review rounds, every round after the first is a resubmission
+    #[serde(default = "route_workshop_default_rounds")]
+    #[validate(range(min = 1, max = 10))]
+    pub(crate) rounds: i32,
+    // Resubmissions are reviewed by the reviewers of the previous round
+    #[serde(rename = "sameReviewers")]
+    #[serde(default)]
+    pub(crate) same_reviewers: bool,
+    // Rule that combines the points of all rounds
+    #[serde(rename = "roundPolicy")]
+    #[serde(default)]
+    pub(crate) round_policy: RoundPolicy,
     pub(crate) teachers: NumberVec,
     pub(crate) students: NumberVec,
     #[validate]
@@ -210,6 +578,78 @@ pub struct RouteUpdateWorkshop {
     pub(crate) attachments: NumberVec,
 }
 
+impl RouteUpdateWorkshop {
+    /// Settings of the updated workshop.
+    /// The default review timespan is used if none is given.
+    pub fn settings(&self, default_review_timespan: i64) -> WorkshopSettings {
+        workshop_settings!(self, default_review_timespan)
+    }
+}
+
+#[derive(Deserialize, Validate, SimpleValidation)]
+pub struct RouteOverridePoints {
+    #[validate(range(min = 0.0))]
+    pub(crate) points: f64,
+    #[validate(length(min = 1))]
+    pub(crate) justification: String,
+}
+
+#[derive(Deserialize, Validate, SimpleValidation)]
+pub struct RouteGrantExtension {
+    pub(crate) student: u64,
+    #[validate]
+    pub(crate) end: Date,
+}
+
+#[derive(Deserialize, Validate, SimpleValidation)]
+pub struct RouteReviewExtension {
+    #[validate]
+    pub(crate) end: Date,
+}
+
+#[derive(Deserialize, Validate, SimpleValidation)]
+pub struct RouteTeam {
+    #[validate(length(min = 1))]
+    pub(crate) title: String,
+    #[serde(default)]
+    pub(crate) members: NumberVec,
+}
+
+// Webhooks
+#[derive(Deserialize, Validate, SimpleValidation)]
+pub struct RouteNewWebhook {
+    #[serde(default)]
+    pub(crate) workshop: Option<u64>,
+    #[validate(url)]
+    pub(crate) url: String,
+    #[validate(length(min = 8))]
+    pub(crate) secret: String,
+    #[validate(length(min = 1))]
+    pub(crate) events: Vec<WebhookEventKind>,
+}
+
+#[derive(Deserialize, Validate, SimpleValidation)]
+pub struct RouteUpdateWebhook {
+    #[validate(url)]
+    pub(crate) url: String,
+    #[validate(length(min = 8))]
+    #[serde(default)]
+    pub(crate) secret: Option<String>,
+    #[validate(length(min = 1))]
+    pub(crate) events: Vec<WebhookEventKind>,
+    pub(crate) active: bool,
+}
+
+#[derive(Serialize)]
+pub struct RouteWebhookResponse {
+    pub(crate) id: u64,
+    pub(crate) workshop: Option<u64>,
+    pub(crate) url: String,
+    pub(crate) events: Vec<WebhookEventKind>,
+    pub(crate) active: bool,
+    pub(crate) created: chrono::NaiveDateTime,
+}
+
 // Users
 #[derive(FromForm, Deserialize, Validate, SimpleValidation)]
 pub struct RouteCreateStudent {
@@ -223,6 +663,9 @@ pub struct RouteCreateStudent {
     pub(crate) password: String,
     #[serde(rename(deserialize = "group"))]
     pub(crate) unit: String,
+    #[validate(email)]
+    #[serde(default)]
+    pub(crate) email: Option<String>,
 }
 
 #[derive(FromForm, Deserialize, Validate, SimpleValidation)]
@@ -235,6 +678,19 @@ pub struct RouteCreateTeacher {
     pub(crate) lastname: String,
     #[validate(length(min = 1))]
     pub(crate) password: String,
+    #[validate(email)]
+    #[serde(default)]
+    pub(crate) email: Option<String>,
+}
+
+#[derive(Deserialize, Validate, SimpleValidation)]
+pub struct RouteEmailPreferences {
+    #[validate(email)]
+    pub(crate) email: Option<String>,
+    pub(crate) assigned: bool,
+    pub(crate) deadline: bool,
+    pub(crate) received: bool,
+    pub(crate) workshop: bool,
 }
 
 #[derive(FromForm, Deserialize, Validate)]
@@ -430,7 +886,11 @@ mod tests {
 
     #[test]
     fn route_update_points_valid_data_ok() {
-        let rts = RouteUpdatePoints { id: 0, points: 0.0 };
+        let rts = RouteUpdatePoints {
+            id: 0,
+            points: 0.0,
+            comment: None,
+        };
         assert!(rts.validate().is_ok());
     }
 
@@ -439,10 +899,12 @@ mod tests {
         let rts = RouteUpdatePoints {
             id: 0,
             points: -0.1, // Under 0.0
+            comment: None,
         };
         let rts2 = RouteUpdatePoints {
             id: 0,
             points: 100.1, // Above 100.0
+            comment: None,
         };
         assert!(rts.validate().is_err());
         assert!(rts2.validate().is_err());
@@ -462,6 +924,7 @@ mod tests {
         let rts = RouteUpdatePoints {
             id: 0,
             points: -0.1, // Under 0.0
+            comment: None,
         };
         let rur = RouteUpdateReview {
             feedback: "".to_string(),
@@ -477,6 +940,10 @@ mod tests {
             content: "".to_string(),
             weight: 0.0,
             kind: Kind::Point,
+            min: None,
+            max: None,
+            step: None,
+            levels: vec![],
         };
         assert!(rc.validate().is_ok());
     }
@@ -488,24 +955,125 @@ mod tests {
             content: "".to_string(),
             weight: 0.0,
             kind: Kind::Point,
+            min: None,
+            max: None,
+            step: None,
+            levels: vec![],
         };
         let rc2 = RouteCriterion {
             title: "Great Title".to_string(),
             content: "".to_string(),
             weight: -0.1,
             kind: Kind::Point,
+            min: None,
+            max: None,
+            step: None,
+            levels: vec![],
         };
         let rc3 = RouteCriterion {
             title: "Great Title".to_string(),
             content: "".to_string(),
             weight: 100.1,
             kind: Kind::Point,
+            min: None,
+            max: None,
+            step: None,
+            levels: vec![],
         };
         assert!(rc.validate().is_err());
         assert!(rc2.validate().is_err());
         assert!(rc3.validate().is_err());
     }
 
+    #[test]
+    fn route_criterion_range_and_rubric_valid_data_ok() {
+        let range = RouteCriterion {
+            title: "Great Title".to_string(),
+            content: "".to_string(),
+            weight: 1.0,
+            kind: Kind::Range,
+            min: Some(0.0),
+            max: Some(20.0),
+            step: Some(0.5),
+            levels: vec![],
+        };
+        let rubric = RouteCriterion {
+            title: "Great Title".to_string(),
+            content: "".to_string(),
+            weight: 1.0,
+            kind: Kind::Rubric,
+            min: None,
+            max: None,
+            step: None,
+            levels: vec![
+                RouteRubricLevel {
+                    title: "Poor".to_string(),
+                    content: "".to_string(),
+                    points: 0.0,
+                },
+                RouteRubricLevel {
+                    title: "Excellent".to_string(),
+                    content: "".to_string(),
+                    points: 4.0,
+                },
+            ],
+        };
+        assert!(range.validate().is_ok());
+        assert!(rubric.validate().is_ok());
+    }
+
+    #[test]
+    fn route_criterion_invalid_range_and_rubric_not_ok() {
+        let range = RouteCriterion {
+            title: "Great Title".to_string(),
+            content: "".to_string(),
+            weight: 1.0,
+            kind: Kind::Range,
+            min: Some(10.0),
+            max: Some(5.0), // Lower than min
+            step: None,
+            levels: vec![],
+        };
+        let range2 = RouteCriterion {
+            title: "Great Title".to_string(),
+            content: "".to_string(),
+            weight: 1.0,
+            kind: Kind::Range,
+            min: Some(0.0),
+            max: Some(10.0),
+            step: Some(0.0), // Not greater than 0.0
+            levels: vec![],
+        };
+        let rubric = RouteCriterion {
+            title: "Great Title".to_string(),
+            content: "".to_string(),
+            weight: 1.0,
+            kind: Kind::Rubric,
+            min: None,
+            max: None,
+            step: None,
+            levels: vec![], // No levels
+        };
+        let rubric2 = RouteCriterion {
+            title: "Great Title".to_string(),
+            content: "".to_string(),
+            weight: 1.0,
+            kind: Kind::Rubric,
+            min: None,
+            max: None,
+            step: None,
+            levels: vec![RouteRubricLevel {
+                title: "".to_string(), // Empty title
+                content: "".to_string(),
+                points: 1.0,
+            }],
+        };
+        assert!(range.validate().is_err());
+        assert!(range2.validate().is_err());
+        assert!(rubric.validate().is_err());
+        assert!(rubric2.validate().is_err());
+    }
+
     #[test]
     fn route_criterion_vec_valid_data_ok() {
         let rcv = RouteCriterionVec { 0: vec![] };
@@ -514,6 +1082,10 @@ mod tests {
             content: "".to_string(),
             weight: 0.0,
             kind: Kind::Point,
+            min: None,
+            max: None,
+            step: None,
+            levels: vec![],
         };
         let rcv2 = RouteCriterionVec { 0: vec![rc] };
         assert!(rcv.validate().is_ok());
@@ -527,6 +1099,10 @@ mod tests {
             content: "".to_string(),
             weight: -0.1,
             kind: Kind::Point,
+            min: None,
+            max: None,
+            step: None,
+            levels: vec![],
         };
         let rcv = RouteCriterionVec { 0: vec![rc] };
         assert!(rcv.validate().is_err());
@@ -555,6 +1131,10 @@ mod tests {
             content: "".to_string(),
             weight: 0.0,
             kind: Kind::Point,
+            min: None,
+            max: None,
+            step: None,
+            levels: vec![],
         };
         let rcv = RouteCriterionVec { 0: vec![rc] };
         let rnw = RouteNewWorkshop {
@@ -562,6 +1142,23 @@ mod tests {
             content: "".to_string(),
             end: d,
             review_timespan: Some(24 * 60),
+            teacher_weight: 1.0,
+            aggregation: Aggregation::Mean,
+            grade_scale: GradeScale::Austrian,
+            comment_threshold: None,
+            draft_policy: DraftPolicy::Discard,
+            reviewing_weight: 0.0,
+            appeal_window: 0,
+            self_assessment: false,
+            submission_start: None,
+            submission_end: None,
+            review_end: None,
+            results_date: None,
+            late_policy: LatePolicy::Block,
+            late_penalty: 0.0,
+            rounds: 1,
+            same_reviewers: false,
+            round_policy: RoundPolicy::Last,
             anonymous: false,
             teachers: Default::default(),
             students: Default::default(),
@@ -580,6 +1177,10 @@ mod tests {
             content: "".to_string(),
             weight: 0.0,
             kind: Kind::Point,
+            min: None,
+            max: None,
+            step: None,
+            levels: vec![],
         };
         let rcv = RouteCriterionVec { 0: vec![rc] };
         let rnw = RouteNewWorkshop {
@@ -587,6 +1188,23 @@ mod tests {
             content: "".to_string(),
             end: d,
             review_timespan: None,
+            teacher_weight: 1.0,
+            aggregation: Aggregation::Mean,
+            grade_scale: GradeScale::Austrian,
+            comment_threshold: None,
+            draft_policy: DraftPolicy::Discard,
+            reviewing_weight: 0.0,
+            appeal_window: 0,
+            self_assessment: false,
+            submission_start: None,
+            submission_end: None,
+            review_end: None,
+            results_date: None,
+            late_policy: LatePolicy::Block,
+            late_penalty: 0.0,
+            rounds: 1,
+            same_reviewers: false,
+            round_policy: RoundPolicy::Last,
             anonymous: false,
             teachers: Default::default(),
             students: Default::default(),
@@ -600,6 +1218,10 @@ mod tests {
             content: "".to_string(),
             weight: 0.0,
             kind: Kind::Point,
+            min: None,
+            max: None,
+            step: None,
+            levels: vec![],
         };
         let rcv = RouteCriterionVec { 0: vec![rc] };
         let rnw2 = RouteNewWorkshop {
@@ -607,6 +1229,23 @@ mod tests {
             content: "".to_string(),
             end: d,
             review_timespan: None,
+            teacher_weight: 1.0,
+            aggregation: Aggregation::Mean,
+            grade_scale: GradeScale::Austrian,
+            comment_threshold: None,
+            draft_policy: DraftPolicy::Discard,
+            reviewing_weight: 0.0,
+            appeal_window: 0,
+            self_assessment: false,
+            submission_start: None,
+            submission_end: None,
+            review_end: None,
+            results_date: None,
+            late_policy: LatePolicy::Block,
+            late_penalty: 0.0,
+            rounds: 1,
+            same_reviewers: false,
+            round_policy: RoundPolicy::Last,
             anonymous: false,
             teachers: Default::default(),
             students: Default::default(),
@@ -620,6 +1259,10 @@ mod tests {
             content: "".to_string(),
             weight: -0.1,
             kind: Kind::Point,
+            min: None,
+            max: None,
+            step: None,
+            levels: vec![],
         };
         let rcv = RouteCriterionVec { 0: vec![rc] };
         let rnw3 = RouteNewWorkshop {
@@ -627,6 +1270,23 @@ mod tests {
             content: "".to_string(),
             end: d,
             review_timespan: None,
+            teacher_weight: 1.0,
+            aggregation: Aggregation::Mean,
+            grade_scale: GradeScale::Austrian,
+            comment_threshold: None,
+            draft_policy: DraftPolicy::Discard,
+            reviewing_weight: 0.0,
+            appeal_window: 0,
+            self_assessment: false,
+            submission_start: None,
+            submission_end: None,
+            review_end: None,
+            results_date: None,
+            late_policy: LatePolicy::Block,
+            late_penalty: 0.0,
+            rounds: 1,
+            same_reviewers: false,
+            round_policy: RoundPolicy::Last,
             anonymous: false,
             teachers: Default::default(),
             students: Default::default(),
@@ -647,6 +1307,10 @@ mod tests {
             content: "".to_string(),
             weight: 0.0,
             kind: Kind::Point,
+            min: None,
+            max: None,
+            step: None,
+            levels: vec![],
         };
         let rcv = RouteCriterionVec { 0: vec![rc] };
         let rnw = RouteNewWorkshop {
@@ -654,6 +1318,358 @@ mod tests {
             content: "".to_string(),
             end: d,
             review_timespan: Some(-24),
+            teacher_weight: 1.0,
+            aggregation: Aggregation::Mean,
+            grade_scale: GradeScale::Austrian,
+            comment_threshold: None,
+            draft_policy: DraftPolicy::Discard,
+            reviewing_weight: 0.0,
+            appeal_window: 0,
+            self_assessment: false,
+            submission_start: None,
+            submission_end: None,
+            review_end: None,
+            results_date: None,
+            late_policy: LatePolicy::Block,
+            late_penalty: 0.0,
+            rounds: 1,
+            same_reviewers: false,
+            round_policy: RoundPolicy::Last,
+            anonymous: false,
+            teachers: Default::default(),
+            students: Default::default(),
+            criteria: rcv,
+            attachments: Default::default(),
+        };
+        assert!(rnw.validate().is_err());
+    }
+
+    #[test]
+    fn route_new_workshop_teacher_weight_not_ok() {
+        let future_date = Local::now().naive_local() + chrono::Duration::days(1);
+        let d = Date { 0: future_date };
+        let rc = RouteCriterion {
+            title: "Great Title".to_string(),
+            content: "".to_string(),
+            weight: 0.0,
+            kind: Kind::Point,
+            min: None,
+            max: None,
+            step: None,
+            levels: vec![],
+        };
+        let rcv = RouteCriterionVec { 0: vec![rc] };
+        let rnw = RouteNewWorkshop {
+            title: "Great Title".to_string(),
+            content: "".to_string(),
+            end: d,
+            review_timespan: None,
+            teacher_weight: 0.0, // Not greater than 0.0
+            aggregation: Aggregation::Mean,
+            grade_scale: GradeScale::Austrian,
+            comment_threshold: None,
+            draft_policy: DraftPolicy::Discard,
+            reviewing_weight: 0.0,
+            appeal_window: 0,
+            self_assessment: false,
+            submission_start: None,
+            submission_end: None,
+            review_end: None,
+            results_date: None,
+            late_policy: LatePolicy::Block,
+            late_penalty: 0.0,
+            rounds: 1,
+            same_reviewers: false,
+            round_policy: RoundPolicy::Last,
+            anonymous: false,
+            teachers: Default::default(),
+            students: Default::default(),
+            criteria: rcv,
+            attachments: Default::default(),
+        };
+        assert!(rnw.validate().is_err());
+    }
+
+    #[test]
+    fn route_new_workshop_phases_ok() {
+        let now = Local::now().naive_local();
+        let d = Date {
+            0: now + chrono::Duration::days(1),
+        };
+        let rc = RouteCriterion {
+            title: "Great Title".to_string(),
+            content: "".to_string(),
+            weight: 0.0,
+            kind: Kind::Point,
+            min: None,
+            max: None,
+            step: None,
+            levels: vec![],
+        };
+        let rcv = RouteCriterionVec { 0: vec![rc] };
+        let rnw = RouteNewWorkshop {
+            title: "Great Title".to_string(),
+            content: "".to_string(),
+            end: d,
+            review_timespan: Some(24 * 60),
+            teacher_weight: 1.0,
+            aggregation: Aggregation::Mean,
+            grade_scale: GradeScale::Austrian,
+            comment_threshold: None,
+            draft_policy: DraftPolicy::Discard,
+            reviewing_weight: 0.0,
+            appeal_window: 0,
+            self_assessment: false,
+            // Submission start can be in the past, missing phases are skipped
+            submission_start: Some(Date {
+                0: now - chrono::Duration::days(1),
+            }),
+            submission_end: Some(Date {
+                0: now + chrono::Duration::days(2),
+            }),
+            review_end: None,
+            results_date: Some(Date {
+                0: now + chrono::Duration::days(3),
+            }),
+            late_policy: LatePolicy::Block,
+            late_penalty: 0.0,
+            rounds: 1,
+            same_reviewers: false,
+            round_policy: RoundPolicy::Last,
+            anonymous: false,
+            teachers: Default::default(),
+            students: Default::default(),
+            criteria: rcv,
+            attachments: Default::default(),
+        };
+        assert!(rnw.validate().is_ok());
+    }
+
+    #[test]
+    fn route_update_workshop_phases_not_ok() {
+        let now = Local::now().naive_local();
+        let d = Date {
+            0: now + chrono::Duration::days(1),
+        };
+        let rc = RouteCriterion {
+            title: "Great Title".to_string(),
+            content: "".to_string(),
+            weight: 0.0,
+            kind: Kind::Point,
+            min: None,
+            max: None,
+            step: None,
+            levels: vec![],
+        };
+        let rcv = RouteCriterionVec { 0: vec![rc] };
+        let ruw = RouteUpdateWorkshop {
+            title: "Great Title".to_string(),
+            content: "".to_string(),
+            end: d,
+            review_timespan: Some(24 * 60),
+            teacher_weight: 1.0,
+            aggregation: Aggregation::Mean,
+            grade_scale: GradeScale::Austrian,
+            comment_threshold: None,
+            draft_policy: DraftPolicy::Discard,
+            reviewing_weight: 0.0,
+            appeal_window: 0,
+            self_assessment: false,
+            submission_start: None,
+            submission_end: Some(Date {
+                0: now + chrono::Duration::days(2),
+            }),
+            // Review phase ends before submission phase
+            review_end: Some(Date {
+                0: now + chrono::Duration::days(1),
+            }),
+            results_date: None,
+            late_policy: LatePolicy::Block,
+            late_penalty: 0.0,
+            rounds: 1,
+            same_reviewers: false,
+            round_policy: RoundPolicy::Last,
+            teachers: Default::default(),
+            students: Default::default(),
+            criteria: rcv,
+            attachments: Default::default(),
+        };
+        assert!(ruw.validate().is_err());
+    }
+
+    #[test]
+    fn route_new_workshop_comment_threshold_not_ok() {
+        let future_date = Local::now().naive_local() + chrono::Duration::days(1);
+        let d = Date { 0: future_date };
+        let rc = RouteCriterion {
+            title: "Great Title".to_string(),
+            content: "".to_string(),
+            weight: 0.0,
+            kind: Kind::Point,
+            min: None,
+            max: None,
+            step: None,
+            levels: vec![],
+        };
+        let rcv = RouteCriterionVec { 0: vec![rc] };
+        let rnw = RouteNewWorkshop {
+            title: "Great Title".to_string(),
+            content: "".to_string(),
+            end: d,
+            review_timespan: None,
+            teacher_weight: 1.0,
+            aggregation: Aggregation::Mean,
+            grade_scale: GradeScale::Austrian,
+            comment_threshold: Some(1.1), // Above 1.0
+            draft_policy: DraftPolicy::Discard,
+            reviewing_weight: 0.0,
+            appeal_window: 0,
+            self_assessment: false,
+            submission_start: None,
+            submission_end: None,
+            review_end: None,
+            results_date: None,
+            late_policy: LatePolicy::Block,
+            late_penalty: 0.0,
+            rounds: 1,
+            same_reviewers: false,
+            round_policy: RoundPolicy::Last,
+            anonymous: false,
+            teachers: Default::default(),
+            students: Default::default(),
+            criteria: rcv,
+            attachments: Default::default(),
+        };
+        assert!(rnw.validate().is_err());
+    }
+
+    #[test]
+    fn route_new_workshop_settings_ok() {
+        let future_date = Local::now().naive_local() + chrono::Duration::days(1);
+        let d = Date { 0: future_date };
+        let rnw = RouteNewWorkshop {
+            title: "Great Title".to_string(),
+            content: "".to_string(),
+            end: d,
+            review_timespan: None,
+            teacher_weight: 2.0,
+            aggregation: Aggregation::Median,
+            grade_scale: GradeScale::Austrian,
+            comment_threshold: None,
+            draft_policy: DraftPolicy::Discard,
+            reviewing_weight: 0.25,
+            appeal_window: 0,
+            self_assessment: false,
+            submission_start: Some(Date { 0: future_date }),
+            submission_end: None,
+            review_end: None,
+            results_date: None,
+            late_policy: LatePolicy::Accept,
+            late_penalty: 0.1,
+            rounds: 2,
+            same_reviewers: true,
+            round_policy: RoundPolicy::Best,
+            anonymous: false,
+            teachers: Default::default(),
+            students: Default::default(),
+            criteria: RouteCriterionVec { 0: vec![] },
+            attachments: Default::default(),
+        };
+        assert!(rnw.validate().is_ok());
+        let settings = rnw.settings(60);
+        assert_eq!(settings.review_timespan, 60); // Default timespan
+        assert_eq!(settings.end, future_date);
+        assert_eq!(settings.submission_start, Some(future_date));
+        assert_eq!(settings.teacher_weight, 2.0);
+        assert_eq!(settings.reviewing_weight, 0.25);
+        assert_eq!(settings.late_penalty, 0.1);
+        assert_eq!(settings.round_policy, RoundPolicy::Best);
+        assert!(settings.same_reviewers);
+    }
+
+    #[test]
+    fn route_new_workshop_late_penalty_not_ok() {
+        let future_date = Local::now().naive_local() + chrono::Duration::days(1);
+        let d = Date { 0: future_date };
+        let rc = RouteCriterion {
+            title: "Great Title".to_string(),
+            content: "".to_string(),
+            weight: 0.0,
+            kind: Kind::Point,
+            min: None,
+            max: None,
+            step: None,
+            levels: vec![],
+        };
+        let rcv = RouteCriterionVec { 0: vec![rc] };
+        let rnw = RouteNewWorkshop {
+            title: "Great Title".to_string(),
+            content: "".to_string(),
+            end: d,
+            review_timespan: None,
+            teacher_weight: 1.0,
+            aggregation: Aggregation::Mean,
+            grade_scale: GradeScale::Austrian,
+            comment_threshold: None,
+            draft_policy: DraftPolicy::Discard,
+            reviewing_weight: 0.0,
+            appeal_window: 0,
+            self_assessment: false,
+            submission_start: None,
+            submission_end: None,
+            review_end: None,
+            results_date: None,
+            late_policy: LatePolicy::Accept,
+            late_penalty: 1.5, // Above 1.0
+            rounds: 1,
+            same_reviewers: false,
+            round_policy: RoundPolicy::Last,
+            anonymous: false,
+            teachers: Default::default(),
+            students: Default::default(),
+            criteria: rcv,
+            attachments: Default::default(),
+        };
+        assert!(rnw.validate().is_err());
+    }
+
+    #[test]
+    fn route_new_workshop_rounds_not_ok() {
+        let future_date = Local::now().naive_local() + chrono::Duration::days(1);
+        let d = Date { 0: future_date };
+        let rc = RouteCriterion {
+            title: "Great Title".to_string(),
+            content: "".to_string(),
+            weight: 0.0,
+            kind: Kind::Point,
+            min: None,
+            max: None,
+            step: None,
+            levels: vec![],
+        };
+        let rcv = RouteCriterionVec { 0: vec![rc] };
+        let rnw = RouteNewWorkshop {
+            title: "Great Title".to_string(),
+            content: "".to_string(),
+            end: d,
+            review_timespan: None,
+            teacher_weight: 1.0,
+            aggregation: Aggregation::Mean,
+            grade_scale: GradeScale::Austrian,
+            comment_threshold: None,
+            draft_policy: DraftPolicy::Discard,
+            reviewing_weight: 0.0,
+            appeal_window: 0,
+            self_assessment: false,
+            submission_start: None,
+            submission_end: None,
+            review_end: None,
+            results_date: None,
+            late_policy: LatePolicy::Block,
+            late_penalty: 0.0,
+            rounds: 0, // At least one round
+            same_reviewers: false,
+            round_policy: RoundPolicy::Last,
             anonymous: false,
             teachers: Default::default(),
             students: Default::default(),
@@ -672,6 +1688,10 @@ mod tests {
             content: "".to_string(),
             weight: 0.0,
             kind: Kind::Point,
+            min: None,
+            max: None,
+            step: None,
+            levels: vec![],
         };
         let rcv = RouteCriterionVec { 0: vec![rc] };
         let rup = RouteUpdateWorkshop {
@@ -679,6 +1699,23 @@ mod tests {
             content: "".to_string(),
             end: d,
             review_timespan: None,
+            teacher_weight: 1.0,
+            aggregation: Aggregation::Mean,
+            grade_scale: GradeScale::Austrian,
+            comment_threshold: None,
+            draft_policy: DraftPolicy::Discard,
+            reviewing_weight: 0.0,
+            appeal_window: 0,
+            self_assessment: false,
+            submission_start: None,
+            submission_end: None,
+            review_end: None,
+            results_date: None,
+            late_policy: LatePolicy::Block,
+            late_penalty: 0.0,
+            rounds: 1,
+            same_reviewers: false,
+            round_policy: RoundPolicy::Last,
             teachers: Default::default(),
             students: Default::default(),
             criteria: rcv,
@@ -696,6 +1733,10 @@ mod tests {
             content: "".to_string(),
             weight: 0.0,
             kind: Kind::Point,
+            min: None,
+            max: None,
+            step: None,
+            levels: vec![],
         };
         let rcv = RouteCriterionVec { 0: vec![rc] };
         let rup = RouteUpdateWorkshop {
@@ -703,6 +1744,23 @@ mod tests {
             content: "".to_string(),
             end: d,
             review_timespan: None,
+            teacher_weight: 1.0,
+            aggregation: Aggregation::Mean,
+            grade_scale: GradeScale::Austrian,
+            comment_threshold: None,
+            draft_policy: DraftPolicy::Discard,
+            reviewing_weight: 0.0,
+            appeal_window: 0,
+            self_assessment: false,
+            submission_start: None,
+            submission_end: None,
+            review_end: None,
+            results_date: None,
+            late_policy: LatePolicy::Block,
+            late_penalty: 0.0,
+            rounds: 1,
+            same_reviewers: false,
+            round_policy: RoundPolicy::Last,
             teachers: Default::default(),
             students: Default::default(),
             criteria: rcv,
@@ -715,6 +1773,10 @@ mod tests {
             content: "".to_string(),
             weight: 0.0,
             kind: Kind::Point,
+            min: None,
+            max: None,
+            step: None,
+            levels: vec![],
         };
         let rcv = RouteCriterionVec { 0: vec![rc] };
         let rup2 = RouteUpdateWorkshop {
@@ -722,6 +1784,23 @@ mod tests {
             content: "".to_string(),
             end: d,
             review_timespan: None,
+            teacher_weight: 1.0,
+            aggregation: Aggregation::Mean,
+            grade_scale: GradeScale::Austrian,
+            comment_threshold: None,
+            draft_policy: DraftPolicy::Discard,
+            reviewing_weight: 0.0,
+            appeal_window: 0,
+            self_assessment: false,
+            submission_start: None,
+            submission_end: None,
+            review_end: None,
+            results_date: None,
+            late_policy: LatePolicy::Block,
+            late_penalty: 0.0,
+            rounds: 1,
+            same_reviewers: false,
+            round_policy: RoundPolicy::Last,
             teachers: Default::default(),
             students: Default::default(),
             criteria: rcv,
@@ -734,6 +1813,10 @@ mod tests {
             content: "".to_string(),
             weight: -0.1,
             kind: Kind::Point,
+            min: None,
+            max: None,
+            step: None,
+            levels: vec![],
         };
         let rcv = RouteCriterionVec { 0: vec![rc] };
         let rup3 = RouteUpdateWorkshop {
@@ -741,6 +1824,23 @@ mod tests {
             content: "".to_string(),
             end: d,
             review_timespan: None,
+            teacher_weight: 1.0,
+            aggregation: Aggregation::Mean,
+            grade_scale: GradeScale::Austrian,
+            comment_threshold: None,
+            draft_policy: DraftPolicy::Discard,
+            reviewing_weight: 0.0,
+            appeal_window: 0,
+            self_assessment: false,
+            submission_start: None,
+            submission_end: None,
+            review_end: None,
+            results_date: None,
+            late_policy: LatePolicy::Block,
+            late_penalty: 0.0,
+            rounds: 1,
+            same_reviewers: false,
+            round_policy: RoundPolicy::Last,
             teachers: Default::default(),
             students: Default::default(),
             criteria: rcv,
@@ -751,6 +1851,29 @@ mod tests {
         assert!(rup3.validate().is_err());
     }
 
+    #[test]
+    fn route_override_points_valid_data_ok() {
+        let rop = RouteOverridePoints {
+            points: 12.5,
+            justification: "Plagiarism in peer review".to_string(),
+        };
+        assert!(rop.validate().is_ok());
+    }
+
+    #[test]
+    fn route_override_points_invalid_points_and_justification_not_ok() {
+        let rop = RouteOverridePoints {
+            points: -0.1, // Under 0.0
+            justification: "Plagiarism in peer review".to_string(),
+        };
+        let rop2 = RouteOverridePoints {
+            points: 12.5,
+            justification: "".to_string(), // Justification is mandatory
+        };
+        assert!(rop.validate().is_err());
+        assert!(rop2.validate().is_err());
+    }
+
     #[test]
     fn route_create_student_valid_data_ok() {
         let rcs = RouteCreateStudent {
@@ -759,6 +1882,7 @@ mod tests {
             lastname: "Mustermann".to_string(),
             password: "1234".to_string(),
             unit: "5A".to_string(),
+            email: None,
         };
         assert!(rcs.validate().is_ok());
     }
@@ -771,6 +1895,7 @@ mod tests {
             lastname: "Mustermann".to_string(),
             password: "1234".to_string(),
             unit: "5A".to_string(),
+            email: None,
         };
         let rcs2 = RouteCreateStudent {
             username: "User".to_string(),
@@ -778,6 +1903,7 @@ mod tests {
             lastname: "Mustermann".to_string(),
             password: "1234".to_string(),
             unit: "5A".to_string(),
+            email: None,
         };
         let rcs3 = RouteCreateStudent {
             username: "User".to_string(),
@@ -785,6 +1911,7 @@ mod tests {
             lastname: "".to_string(),
             password: "1234".to_string(),
             unit: "5A".to_string(),
+            email: None,
         };
         let rcs4 = RouteCreateStudent {
             username: "User".to_string(),
@@ -792,6 +1919,7 @@ mod tests {
             lastname: "Mustermann".to_string(),
             password: "".to_string(),
             unit: "5A".to_string(),
+            email: None,
         };
         assert!(rcs.validate().is_err());
         assert!(rcs2.validate().is_err());
@@ -799,6 +1927,28 @@ mod tests {
         assert!(rcs4.validate().is_err());
     }
 
+    #[test]
+    fn route_create_student_invalid_email_not_ok() {
+        let rcs = RouteCreateStudent {
+            username: "User".to_string(),
+            firstname: "Max".to_string(),
+            lastname: "Mustermann".to_string(),
+            password: "1234".to_string(),
+            unit: "5A".to_string(),
+            email: Some("max.mustermann".to_string()),
+        };
+        let rcs2 = RouteCreateStudent {
+            username: "User".to_string(),
+            firstname: "Max".to_string(),
+            lastname: "Mustermann".to_string(),
+            password: "1234".to_string(),
+            unit: "5A".to_string(),
+            email: Some("max@mustermann.at".to_string()),
+        };
+        assert!(rcs.validate().is_err());
+        assert!(rcs2.validate().is_ok());
+    }
+
     #[test]
     fn route_create_teacher_valid_data_ok() {
         let rct = RouteCreateTeacher {
@@ -806,6 +1956,7 @@ mod tests {
             firstname: "Max".to_string(),
             lastname: "Mustermann".to_string(),
             password: "1234".to_string(),
+            email: None,
         };
         assert!(rct.validate().is_ok());
     }
@@ -817,24 +1968,28 @@ mod tests {
             firstname: "Max".to_string(),
             lastname: "Mustermann".to_string(),
             password: "1234".to_string(),
+            email: None,
         };
         let rct2 = RouteCreateTeacher {
             username: "User".to_string(),
             firstname: "".to_string(),
             lastname: "Mustermann".to_string(),
             password: "1234".to_string(),
+            email: None,
         };
         let rct3 = RouteCreateTeacher {
             username: "User".to_string(),
             firstname: "Max".to_string(),
             lastname: "".to_string(),
             password: "1234".to_string(),
+            email: None,
         };
         let rct4 = RouteCreateTeacher {
             username: "User".to_string(),
             firstname: "Max".to_string(),
             lastname: "Mustermann".to_string(),
             password: "".to_string(),
+            email: None,
         };
         assert!(rct.validate().is_err());
         assert!(rct2.validate().is_err());
@@ -973,4 +2128,158 @@ mod tests {
         };
         assert!(rss.validate().is_err());
     }
+
+    #[test]
+    fn route_review_rating_valid_data_ok() {
+        let rrr = RouteReviewRating {
+            helpfulness: 4,
+            comment: Some("Very helpful".to_string()),
+        };
+        assert!(rrr.validate().is_ok());
+    }
+
+    #[test]
+    fn route_review_rating_invalid_helpfulness_not_ok() {
+        let rrr = RouteReviewRating {
+            helpfulness: 6, // Above 5
+            comment: None,
+        };
+        assert!(rrr.validate().is_err());
+        let rrr = RouteReviewRating {
+            helpfulness: 0, // Below 1
+            comment: None,
+        };
+        assert!(rrr.validate().is_err());
+    }
+
+    #[test]
+    fn route_new_appeal_empty_reason_not_ok() {
+        let rna = RouteNewAppeal {
+            reason: "".to_string(),
+        };
+        assert!(rna.validate().is_err());
+    }
+
+    #[test]
+    fn route_resolve_appeal_valid_data_ok() {
+        let rra = RouteResolveAppeal {
+            status: AppealStatus::Discarded,
+            response: Some("Review was unfair".to_string()),
+            points: vec![],
+        };
+        assert!(rra.validate().is_ok());
+        let rra = RouteResolveAppeal {
+            status: AppealStatus::Adjusted,
+            response: None,
+            points: vec![RouteUpdatePoints {
+                id: 1,
+                points: 5.0,
+                comment: None,
+            }],
+        };
+        assert!(rra.validate().is_ok());
+    }
+
+    #[test]
+    fn route_resolve_appeal_invalid_status_not_ok() {
+        let rra = RouteResolveAppeal {
+            status: AppealStatus::Open,
+            response: None,
+            points: vec![],
+        };
+        assert!(rra.validate().is_err());
+    }
+
+    #[test]
+    fn route_resolve_appeal_adjusted_without_points_not_ok() {
+        let rra = RouteResolveAppeal {
+            status: AppealStatus::Adjusted,
+            response: None,
+            points: vec![],
+        };
+        assert!(rra.validate().is_err());
+    }
+
+    #[test]
+    fn route_grant_extension_valid_data_ok() {
+        let rge = RouteGrantExtension {
+            student: 1,
+            end: Date {
+                0: Local::now().naive_local() + chrono::Duration::days(1),
+            },
+        };
+        assert!(rge.validate().is_ok());
+    }
+
+    #[test]
+    fn route_grant_extension_past_end_not_ok() {
+        let rge = RouteGrantExtension {
+            student: 1,
+            end: Date {
+                0: Local::now().naive_local() - chrono::Duration::days(1),
+            },
+        };
+        assert!(rge.validate().is_err());
+    }
+
+    #[test]
+    fn route_review_extension_past_end_not_ok() {
+        let rre = RouteReviewExtension {
+            end: Date {
+                0: Local::now().naive_local() - chrono::Duration::hours(1),
+            },
+        };
+        assert!(rre.validate().is_err());
+    }
+
+    #[test]
+    fn route_team_ok() {
+        let rt = RouteTeam {
+            title: "Team 1".to_string(),
+            members: NumberVec(vec![1, 2]),
+        };
+        assert!(rt.validate().is_ok());
+    }
+
+    #[test]
+    fn route_team_empty_title_not_ok() {
+        let rt = RouteTeam {
+            title: "".to_string(),
+            members: NumberVec(vec![1, 2]),
+        };
+        assert!(rt.validate().is_err());
+    }
+
+    #[test]
+    fn route_new_webhook_ok() {
+        let rnw = RouteNewWebhook {
+            workshop: Some(1),
+            url: "http://localhost:8080/hook".to_string(),
+            secret: "supersecret".to_string(),
+            events: vec![WebhookEventKind::SubmissionGraded],
+        };
+        assert!(rnw.validate().is_ok());
+    }
+
+    #[test]
+    fn route_new_webhook_invalid_url_not_ok() {
+        let rnw = RouteNewWebhook {
+            workshop: None,
+            url: "localhost".to_string(), // No scheme!
+            secret: "supersecret".to_string(),
+            events: vec![WebhookEventKind::SubmissionGraded],
+        };
+        assert!(rnw.validate().is_err());
+    }
+
+    #[test]
+    fn route_update_webhook_no_events_not_ok() {
+        let ruw = RouteUpdateWebhook {
+            url: "http://localhost:8080/hook".to_string(),
+            secret: None,
+            events: vec![], // Empty event filter
+            active: true,
+        };
+        assert!(ruw.validate().is_err());
+    }
 }
@@ -0,0 +1,77 @@
+use crate::db::models::*;
+use crate::events::{self, EventStream};
+use crate::routes::models::ApiResponse;
+use crate::utils::error::AppError;
+use crate::{db, IprpDB};
+use rocket_contrib::json::{Json, JsonValue};
+
+/// Get notifications of the current user, latest first.
+/// Only unread notifications are returned if `unread` is true.
+#[get("/notifications?<unread>")]
+pub fn notifications(
+    user: User,
+    conn: IprpDB,
+    unread: Option<bool>,
+) -> Result<Json<JsonValue>, ApiResponse> {
+    let notifications = db::notifications::get_by_user(&*conn, user.id, unread.unwrap_or(false));
+    match notifications {
+        Ok(notifications) => Ok(Json(json!({
+            "ok": true,
+            "notifications": notifications
+        }))),
+        Err(_) => Err(ApiResponse::not_found()),
+    }
+}
+
+/// Count unread notifications of the current user.
+#[get("/notifications/unread")]
+pub fn unread_notifications(user: User, conn: IprpDB) -> Result<Json<JsonValue>, ApiResponse> {
+    let unread = db::notifications::count_unread(&*conn, user.id);
+    match unread {
+        Ok(unread) => Ok(Json(json!({
+            "ok": true,
+            "unread": unread
+        }))),
+        Err(_) => Err(ApiResponse::not_found()),
+    }
+}
+
+/// Mark notification as read.
+#[put("/notification/<notification_id>/read")]
+pub fn read_notification(
+    user: User,
+    conn: IprpDB,
+    notification_id: u64,
+) -> Result<Json<JsonValue>, ApiResponse> {
+    let read = db::notifications::mark_read(&*conn, notification_id, user.id);
+    match read {
+        Ok(_) => Ok(Json(json!({
+            "ok": true
+        }))),
+        Err(err) => {
+            err.print_stacktrace();
+            Err(ApiResponse::not_found_with_error(err))
+        }
+    }
+}
+
+/// Mark all notifications of the current user as read.
+#[put("/notifications/read")]
+pub fn read_notifications(user: User, conn: IprpDB) -> Result<Json<JsonValue>, ApiResponse> {
+    let read = db::notifications::mark_all_read(&*conn, user.id);
+    match read {
+        Ok(_) => Ok(Json(json!({
+            "ok": true
+        }))),
+        Err(err) => {
+            err.print_stacktrace();
+            Err(ApiResponse::conflict_with_error(err))
+        }
+    }
+}
+
+/// Stream live updates of the current user as server-sent events.
+#[get("/events")]
+pub fn event_stream(user: User) -> EventStream {
+    EventStream::new(events::subscribe(user.id))
+}
@@ -1,5 +1,7 @@
 use crate::db::models::*;
-use crate::routes::models::{ApiResponse, RouteNewSubmission, RouteUpdateReview};
+use crate::routes::models::{
+    ApiResponse, RouteNewAppeal, RouteNewSubmission, RouteReviewRating, RouteUpdateReview,
+};
 use crate::utils;
 use crate::{db, IprpDB};
 use chrono::Local;
@@ -75,7 +77,7 @@ pub fn get_submission(
             }
         }
     } else if db::submissions::is_owner(&*conn, submission_id, user.id) {
-        let submission = db::submissions::get_own_submission(&*conn, submission_id);
+        let submission = db::submissions::get_own_submission(&*conn, submission_id, user.id);
         match submission {
             Ok(submission) => {
                 let mut json_response = serde_json::to_value(submission).unwrap();
@@ -122,6 +124,127 @@ pub fn get_submission(
     }
 }
 
+/// Withdraw existing submission.
+#[delete("/submission/<submission_id>")]
+pub fn withdraw_submission(
+    user: User,
+    conn: IprpDB,
+    submission_id: u64,
+) -> Result<Json<JsonValue>, ApiResponse> {
+    if user.role == Role::Teacher {
+        return Err(ApiResponse::forbidden());
+    }
+
+    let withdraw = db::submissions::withdraw(&*conn, submission_id, user.id);
+    match withdraw {
+        Ok(_) => Ok(Json(json!({
+            "ok": true,
+        }))),
+        Err(err) => {
+            err.print_stacktrace();
+            Err(ApiResponse::conflict_with_error(err))
+        }
+    }
+}
+
+/// Resubmit a reviewed submission for the next review round.
+#[post(
+    "/submission/<submission_id>/resubmit",
+    format = "json",
+    data = "<new_submission>"
+)]
+pub fn resubmit_submission(
+    user: User,
+    conn: IprpDB,
+    submission_id: u64,
+    new_submission: RouteNewSubmission,
+) -> Result<Json<JsonValue>, ApiResponse> {
+    if user.role == Role::Teacher {
+        return Err(ApiResponse::forbidden());
+    }
+    let date = Local::now().naive_local();
+
+    let submission = db::submissions::resubmit(
+        &*conn,
+        submission_id,
+        user.id,
+        new_submission.title,
+        new_submission.comment,
+        Vec::from(new_submission.attachments),
+        date,
+    );
+
+    match submission {
+        Ok(submission) => Ok(Json(json!({
+            "ok": true,
+            "id": submission.id,
+            "round": submission.round
+        }))),
+        Err(err) => {
+            err.print_stacktrace();
+            Err(ApiResponse::conflict_with_error(err))
+        }
+    }
+}
+
+/// Get all versions of a submission.
+#[get("/submission/<submission_id>/versions")]
+pub fn get_versions(
+    user: User,
+    conn: IprpDB,
+    submission_id: u64,
+) -> Result<Json<JsonValue>, ApiResponse> {
+    if !can_view_versions(&*conn, &user, submission_id) {
+        return Err(ApiResponse::forbidden());
+    }
+
+    let versions = db::versions::get_versions(&*conn, submission_id);
+    match versions {
+        Ok(versions) => Ok(Json(json!({
+            "ok": true,
+            "versions": versions
+        }))),
+        Err(err) => {
+            err.print_stacktrace();
+            Err(ApiResponse::not_found_with_error(err))
+        }
+    }
+}
+
+/// Get changes between two versions of a submission.
+/// Without `to` the changes up to the latest version are returned.
+#[get("/submission/<submission_id>/diff?<from>&<to>")]
+pub fn get_diff(
+    user: User,
+    conn: IprpDB,
+    submission_id: u64,
+    from: i32,
+    to: Option<i32>,
+) -> Result<Json<JsonValue>, ApiResponse> {
+    if !can_view_versions(&*conn, &user, submission_id) {
+        return Err(ApiResponse::forbidden());
+    }
+
+    let diff = db::versions::get_diff(&*conn, submission_id, from, to);
+    match diff {
+        Ok(diff) => Ok(Json(json!({
+            "ok": true,
+            "diff": diff
+        }))),
+        Err(err) => {
+            err.print_stacktrace();
+            Err(ApiResponse::not_found_with_error(err))
+        }
+    }
+}
+
+// Versions are visible for teachers, owners & reviewers of the submission.
+fn can_view_versions(conn: &diesel::MysqlConnection, user: &User, submission_id: u64) -> bool {
+    user.role == Role::Teacher
+        || db::submissions::is_owner(conn, submission_id, user.id)
+        || db::reviews::is_reviewer(conn, submission_id, user.id)
+}
+
 /// Update existing submission.
 #[put(
     "/submission/<submission_id>",
@@ -158,7 +281,7 @@ pub fn update_submission(
     }
 }
 
-/// Update existing review.
+/// Save draft of existing review.
 #[put("/review/<review_id>", format = "json", data = "<update_review>")]
 pub fn update_review(
     user: User,
@@ -166,10 +289,6 @@ pub fn update_review(
     review_id: u64,
     update_review: RouteUpdateReview,
 ) -> Result<Json<JsonValue>, ApiResponse> {
-    if user.role == Role::Teacher {
-        return Err(ApiResponse::forbidden());
-    }
-
     let res = db::reviews::update(&*conn, update_review, review_id, user.id);
 
     match res {
@@ -183,6 +302,81 @@ pub fn update_review(
     }
 }
 
+/// Submit draft of existing review.
+#[post("/review/<review_id>/submit")]
+pub fn submit_review(
+    user: User,
+    conn: IprpDB,
+    review_id: u64,
+) -> Result<Json<JsonValue>, ApiResponse> {
+    let res = db::reviews::submit(&*conn, review_id, user.id);
+
+    match res {
+        Ok(_) => Ok(Json(json!({
+            "ok": true
+        }))),
+        Err(err) => {
+            err.print_stacktrace();
+            Err(ApiResponse::conflict_with_error(err))
+        }
+    }
+}
+
+/// Rate helpfulness of a received review.
+#[put("/review/<review_id>/rating", format = "json", data = "<rating>")]
+pub fn rate_review(
+    user: User,
+    conn: IprpDB,
+    review_id: u64,
+    rating: RouteReviewRating,
+) -> Result<Json<JsonValue>, ApiResponse> {
+    if user.role != Role::Student {
+        return Err(ApiResponse::forbidden());
+    }
+    let res = db::reviews::rate(
+        &*conn,
+        review_id,
+        user.id,
+        rating.helpfulness,
+        rating.comment,
+    );
+
+    match res {
+        Ok(_) => Ok(Json(json!({
+            "ok": true
+        }))),
+        Err(err) => {
+            err.print_stacktrace();
+            Err(ApiResponse::conflict_with_error(err))
+        }
+    }
+}
+
+/// Appeal a received review.
+#[post("/review/<review_id>/appeal", format = "json", data = "<appeal>")]
+pub fn appeal_review(
+    user: User,
+    conn: IprpDB,
+    review_id: u64,
+    appeal: RouteNewAppeal,
+) -> Result<Json<JsonValue>, ApiResponse> {
+    if user.role != Role::Student {
+        return Err(ApiResponse::forbidden());
+    }
+    let res = db::appeals::create(&*conn, review_id, user.id, appeal.reason);
+
+    match res {
+        Ok(appeal) => Ok(Json(json!({
+            "ok": true,
+            "appeal": appeal
+        }))),
+        Err(err) => {
+            err.print_stacktrace();
+            Err(ApiResponse::conflict_with_error(err))
+        }
+    }
+}
+
 /// Get specific review.
 #[get("/review/<review_id>")]
 pub fn get_review(
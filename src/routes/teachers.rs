@@ -1,15 +1,20 @@
 use crate::db::models::*;
+use crate::export::{ExportFile, ExportFormat};
+use crate::routes::error::{RouteError, RouteErrorKind};
 use crate::routes::models::{
-    ApiResponse, Date, NumberVec, RouteCriterionVec, RouteNewWorkshop, RouteSearchStudent,
-    RouteUpdateWorkshop, RouteWorkshopResponse,
+    ApiResponse, Date, NumberVec, RouteCriterionVec, RouteGrantExtension, RouteNewSubmission,
+    RouteNewWebhook, RouteNewWorkshop, RouteOverridePoints, RouteResolveAppeal,
+    RouteReviewExtension, RouteSearchStudent, RouteTeam, RouteUpdateWebhook, RouteUpdateWorkshop,
+    RouteWebhookResponse, RouteWorkshopResponse,
 };
-use crate::{db, IprpDB};
+use crate::{db, export, IprpDB};
 
 use rocket::http::RawStr;
 use rocket::request::FromFormValue;
 use rocket::State;
 use validator::Validate;
 
+use crate::db::scheduler::Scheduler;
 use crate::db::ReviewTimespan;
 use crate::utils::error::AppError;
 use rocket_contrib::json::{Json, JsonValue};
@@ -59,6 +64,603 @@ pub fn workshop(
     }
 }
 
+/// Get helpfulness of the reviews written by the students of a workshop.
+#[get("/teacher/workshop/<workshop_id>/reviewers")]
+pub fn reviewers(
+    user: User,
+    conn: IprpDB,
+    workshop_id: u64,
+) -> Result<Json<JsonValue>, ApiResponse> {
+    if user.role == Role::Student {
+        return Err(ApiResponse::forbidden());
+    }
+
+    let reviewers = db::reviews::get_reviewer_helpfulness(&*conn, user.id, workshop_id);
+    match reviewers {
+        Ok(reviewers) => Ok(Json(json!({
+            "ok": true,
+            "reviewers": reviewers
+        }))),
+        Err(err) => {
+            err.print_stacktrace();
+            Err(ApiResponse::not_found_with_error(err))
+        }
+    }
+}
+
+/// Get appeals of a workshop.
+/// Only open appeals are returned unless all are requested.
+#[get("/teacher/workshop/<workshop_id>/appeals?<all>")]
+pub fn appeals(
+    user: User,
+    conn: IprpDB,
+    workshop_id: u64,
+    all: Option<bool>,
+) -> Result<Json<JsonValue>, ApiResponse> {
+    if user.role == Role::Student {
+        return Err(ApiResponse::forbidden());
+    }
+
+    let appeals =
+        db::appeals::get_workshop_appeals(&*conn, user.id, workshop_id, all.unwrap_or(false));
+    match appeals {
+        Ok(appeals) => Ok(Json(json!({
+            "ok": true,
+            "appeals": appeals
+        }))),
+        Err(err) => {
+            err.print_stacktrace();
+            Err(ApiResponse::not_found_with_error(err))
+        }
+    }
+}
+
+/// Resolve an open appeal.
+#[put(
+    "/teacher/appeal/<appeal_id>",
+    format = "json",
+    data = "<resolve_appeal>"
+)]
+pub fn resolve_appeal(
+    user: User,
+    conn: IprpDB,
+    appeal_id: u64,
+    resolve_appeal: RouteResolveAppeal,
+) -> Result<Json<JsonValue>, ApiResponse> {
+    if user.role == Role::Student {
+        return Err(ApiResponse::forbidden());
+    }
+
+    let res = db::appeals::resolve(
+        &*conn,
+        user.id,
+        appeal_id,
+        resolve_appeal.status,
+        resolve_appeal.response,
+        resolve_appeal.points,
+    );
+    match res {
+        Ok(_) => Ok(Json(json!({
+            "ok": true,
+        }))),
+        Err(err) => {
+            err.print_stacktrace();
+            Err(ApiResponse::conflict_with_error(err))
+        }
+    }
+}
+
+/// Create calibration submission with its reference review.
+#[post(
+    "/teacher/workshop/<workshop_id>/calibration",
+    format = "json",
+    data = "<new_submission>"
+)]
+pub fn create_calibration(
+    user: User,
+    conn: IprpDB,
+    workshop_id: u64,
+    new_submission: RouteNewSubmission,
+) -> Result<Json<JsonValue>, ApiResponse> {
+    if user.role == Role::Student {
+        return Err(ApiResponse::forbidden());
+    }
+
+    let calibration = db::calibration::create(
+        &*conn,
+        new_submission.title,
+        new_submission.comment,
+        Vec::from(new_submission.attachments),
+        user.id,
+        workshop_id,
+    );
+    match calibration {
+        Ok((submission, review)) => Ok(Json(json!({
+            "ok": true,
+            "id": submission.id,
+            "review": review.id
+        }))),
+        Err(err) => {
+            err.print_stacktrace();
+            Err(ApiResponse::conflict_with_error(err))
+        }
+    }
+}
+
+/// Get calibration submissions of a workshop and the accuracy of its reviewers.
+#[get("/teacher/workshop/<workshop_id>/calibration")]
+pub fn calibration(
+    user: User,
+    conn: IprpDB,
+    workshop_id: u64,
+) -> Result<Json<JsonValue>, ApiResponse> {
+    if user.role == Role::Student {
+        return Err(ApiResponse::forbidden());
+    }
+
+    let calibrations = db::calibration::get_workshop_calibrations(&*conn, user.id, workshop_id);
+    let reviewers = db::calibration::get_reviewer_accuracies(&*conn, user.id, workshop_id);
+    match (calibrations, reviewers) {
+        (Ok(calibrations), Ok(reviewers)) => Ok(Json(json!({
+            "ok": true,
+            "calibrations": calibrations,
+            "reviewers": reviewers
+        }))),
+        (Err(err), _) | (_, Err(err)) => {
+            err.print_stacktrace();
+            Err(ApiResponse::not_found_with_error(err))
+        }
+    }
+}
+
+/// Get the deviation of the self-assessments of a workshop from the peer reviews.
+#[get("/teacher/workshop/<workshop_id>/selfassessments")]
+pub fn self_assessments(
+    user: User,
+    conn: IprpDB,
+    workshop_id: u64,
+) -> Result<Json<JsonValue>, ApiResponse> {
+    if user.role == Role::Student {
+        return Err(ApiResponse::forbidden());
+    }
+
+    let deviations = db::reviews::get_self_assessment_deviations(&*conn, user.id, workshop_id);
+    match deviations {
+        Ok(deviations) => Ok(Json(json!({
+            "ok": true,
+            "selfAssessments": deviations
+        }))),
+        Err(err) => {
+            err.print_stacktrace();
+            Err(ApiResponse::not_found_with_error(err))
+        }
+    }
+}
+
+/// Get the agreement of reviewers and outlier reviews of a workshop.
+#[get("/teacher/workshop/<workshop_id>/statistics")]
+pub fn statistics(
+    user: User,
+    conn: IprpDB,
+    workshop_id: u64,
+) -> Result<Json<JsonValue>, ApiResponse> {
+    if user.role == Role::Student {
+        return Err(ApiResponse::forbidden());
+    }
+
+    let statistics = db::reviews::get_workshop_statistics(&*conn, user.id, workshop_id);
+    match statistics {
+        Ok(statistics) => Ok(Json(json!({
+            "ok": true,
+            "statistics": statistics
+        }))),
+        Err(err) => {
+            err.print_stacktrace();
+            Err(ApiResponse::not_found_with_error(err))
+        }
+    }
+}
+
+/// Export the grades of all students from a workshop.
+/// The file is a CSV table unless XLSX is requested.
+#[get("/teacher/workshop/<workshop_id>/export?<format>")]
+pub fn export_grades(
+    user: User,
+    conn: IprpDB,
+    workshop_id: u64,
+    format: Option<ExportFormat>,
+) -> Result<ExportFile, ApiResponse> {
+    if user.role == Role::Student {
+        return Err(ApiResponse::forbidden());
+    }
+
+    let grades = db::workshops::get_grade_export(&*conn, user.id, workshop_id);
+    if let Err(err) = grades {
+        err.print_stacktrace();
+        return Err(ApiResponse::not_found_with_error(err));
+    }
+    match export::render(&grades.unwrap(), format.unwrap_or_default()) {
+        Ok(file) => Ok(file),
+        Err(e) => {
+            let err = RouteError::new(
+                RouteErrorKind::ExportFailed,
+                format!("Export of Workshop {} failed: {}", workshop_id, e),
+            );
+            err.print_stacktrace();
+            Err(ApiResponse::bad_request_with_error(err))
+        }
+    }
+}
+
+/// Get the status of the last scheduler run.
+#[get("/teacher/scheduler")]
+pub fn scheduler(user: User, scheduler: State<Scheduler>) -> Result<Json<JsonValue>, ApiResponse> {
+    if user.role == Role::Student {
+        return Err(ApiResponse::forbidden());
+    }
+
+    Ok(Json(json!({
+        "ok": true,
+        "scheduler": scheduler.status()
+    })))
+}
+
+/// Get the running submission & review extensions of a workshop.
+#[get("/teacher/workshop/<workshop_id>/extensions")]
+pub fn extensions(
+    user: User,
+    conn: IprpDB,
+    workshop_id: u64,
+) -> Result<Json<JsonValue>, ApiResponse> {
+    if user.role == Role::Student {
+        return Err(ApiResponse::forbidden());
+    }
+
+    let submission_extensions =
+        db::extensions::get_submission_extensions(&*conn, user.id, workshop_id);
+    let review_extensions = db::extensions::get_review_extensions(&*conn, user.id, workshop_id);
+    match (submission_extensions, review_extensions) {
+        (Ok(submission_extensions), Ok(review_extensions)) => Ok(Json(json!({
+            "ok": true,
+            "submissionExtensions": submission_extensions,
+            "reviewExtensions": review_extensions
+        }))),
+        (Err(err), _) | (_, Err(err)) => {
+            err.print_stacktrace();
+            Err(ApiResponse::not_found_with_error(err))
+        }
+    }
+}
+
+/// Extend the submission phase of a workshop for a student.
+#[put(
+    "/teacher/workshop/<workshop_id>/extension",
+    format = "json",
+    data = "<grant_extension>"
+)]
+pub fn grant_submission_extension(
+    user: User,
+    conn: IprpDB,
+    workshop_id: u64,
+    grant_extension: RouteGrantExtension,
+) -> Result<Json<JsonValue>, ApiResponse> {
+    if user.role == Role::Student {
+        return Err(ApiResponse::forbidden());
+    }
+
+    let res = db::extensions::grant_submission_extension(
+        &*conn,
+        user.id,
+        workshop_id,
+        grant_extension.student,
+        grant_extension.end.0,
+    );
+    match res {
+        Ok(_) => Ok(Json(json!({
+            "ok": true,
+        }))),
+        Err(err) => {
+            err.print_stacktrace();
+            Err(ApiResponse::conflict_with_error(err))
+        }
+    }
+}
+
+/// Revoke the submission extension of a student.
+#[delete("/teacher/workshop/<workshop_id>/extension/<student_id>")]
+pub fn revoke_submission_extension(
+    user: User,
+    conn: IprpDB,
+    workshop_id: u64,
+    student_id: u64,
+) -> Result<Json<JsonValue>, ApiResponse> {
+    if user.role == Role::Student {
+        return Err(ApiResponse::forbidden());
+    }
+
+    let res = db::extensions::revoke_submission_extension(&*conn, user.id, workshop_id, student_id);
+    match res {
+        Ok(_) => Ok(Json(json!({
+            "ok": true,
+        }))),
+        Err(err) => {
+            err.print_stacktrace();
+            Err(ApiResponse::not_found_with_error(err))
+        }
+    }
+}
+
+/// Extend the deadline of a review for its reviewer.
+#[put(
+    "/teacher/review/<review_id>/extension",
+    format = "json",
+    data = "<review_extension>"
+)]
+pub fn grant_review_extension(
+    user: User,
+    conn: IprpDB,
+    review_id: u64,
+    review_extension: RouteReviewExtension,
+) -> Result<Json<JsonValue>, ApiResponse> {
+    if user.role == Role::Student {
+        return Err(ApiResponse::forbidden());
+    }
+
+    let res =
+        db::extensions::grant_review_extension(&*conn, user.id, review_id, review_extension.end.0);
+    match res {
+        Ok(_) => Ok(Json(json!({
+            "ok": true,
+        }))),
+        Err(err) => {
+            err.print_stacktrace();
+            Err(ApiResponse::conflict_with_error(err))
+        }
+    }
+}
+
+/// Revoke the extension of a review.
+#[delete("/teacher/review/<review_id>/extension")]
+pub fn revoke_review_extension(
+    user: User,
+    conn: IprpDB,
+    review_id: u64,
+) -> Result<Json<JsonValue>, ApiResponse> {
+    if user.role == Role::Student {
+        return Err(ApiResponse::forbidden());
+    }
+
+    let res = db::extensions::revoke_review_extension(&*conn, user.id, review_id);
+    match res {
+        Ok(_) => Ok(Json(json!({
+            "ok": true,
+        }))),
+        Err(err) => {
+            err.print_stacktrace();
+            Err(ApiResponse::not_found_with_error(err))
+        }
+    }
+}
+
+/// Create a team in a workshop.
+#[post(
+    "/teacher/workshop/<workshop_id>/team",
+    format = "json",
+    data = "<team>"
+)]
+pub fn create_team(
+    user: User,
+    conn: IprpDB,
+    workshop_id: u64,
+    team: RouteTeam,
+) -> Result<Json<JsonValue>, ApiResponse> {
+    if user.role == Role::Student {
+        return Err(ApiResponse::forbidden());
+    }
+
+    let res = db::teams::create(
+        &*conn,
+        user.id,
+        workshop_id,
+        team.title,
+        team.members.into(),
+    );
+    match res {
+        Ok(team) => Ok(Json(json!({
+            "ok": true,
+            "id": team.id,
+        }))),
+        Err(err) => {
+            err.print_stacktrace();
+            Err(ApiResponse::conflict_with_error(err))
+        }
+    }
+}
+
+/// Update title & members of a team.
+#[put("/teacher/team/<team_id>", format = "json", data = "<team>")]
+pub fn update_team(
+    user: User,
+    conn: IprpDB,
+    team_id: u64,
+    team: RouteTeam,
+) -> Result<Json<JsonValue>, ApiResponse> {
+    if user.role == Role::Student {
+        return Err(ApiResponse::forbidden());
+    }
+
+    let res = db::teams::update(&*conn, user.id, team_id, team.title, team.members.into());
+    match res {
+        Ok(_) => Ok(Json(json!({
+            "ok": true,
+        }))),
+        Err(err) => {
+            err.print_stacktrace();
+            Err(ApiResponse::conflict_with_error(err))
+        }
+    }
+}
+
+/// Delete a team.
+#[delete("/teacher/team/<team_id>")]
+pub fn delete_team(user: User, conn: IprpDB, team_id: u64) -> Result<Json<JsonValue>, ApiResponse> {
+    if user.role == Role::Student {
+        return Err(ApiResponse::forbidden());
+    }
+
+    let res = db::teams::delete(&*conn, user.id, team_id);
+    match res {
+        Ok(_) => Ok(Json(json!({
+            "ok": true,
+        }))),
+        Err(err) => {
+            err.print_stacktrace();
+            Err(ApiResponse::not_found_with_error(err))
+        }
+    }
+}
+
+/// Get webhooks with their event filter.
+/// Teachers get the webhooks of their workshops, the "admin" account all webhooks.
+#[get("/teacher/webhooks")]
+pub fn webhooks(user: User, conn: IprpDB) -> Result<Json<JsonValue>, ApiResponse> {
+    if user.role == Role::Student {
+        return Err(ApiResponse::forbidden());
+    }
+
+    let webhooks = db::webhooks::get_by_user(&*conn, &user);
+    if webhooks.is_err() {
+        return Err(ApiResponse::not_found());
+    }
+    let webhooks = webhooks
+        .unwrap()
+        .into_iter()
+        .map(|webhook| RouteWebhookResponse {
+            id: webhook.id,
+            workshop: webhook.workshop,
+            url: webhook.url,
+            events: db::webhooks::get_events(&*conn, webhook.id).unwrap_or_default(),
+            active: webhook.active,
+            created: webhook.created,
+        })
+        .collect::<Vec<RouteWebhookResponse>>();
+    Ok(Json(json!({
+        "ok": true,
+        "webhooks": webhooks
+    })))
+}
+
+/// Register a webhook for a workshop.
+/// Global webhooks without workshop can only be registered by the "admin" account.
+#[post("/teacher/webhook", format = "json", data = "<webhook>")]
+pub fn create_webhook(
+    user: User,
+    conn: IprpDB,
+    webhook: RouteNewWebhook,
+) -> Result<Json<JsonValue>, ApiResponse> {
+    if user.role == Role::Student {
+        return Err(ApiResponse::forbidden());
+    }
+
+    let res = db::webhooks::create(
+        &*conn,
+        &user,
+        webhook.workshop,
+        webhook.url,
+        webhook.secret,
+        webhook.events,
+    );
+    match res {
+        Ok(webhook) => Ok(Json(json!({
+            "ok": true,
+            "id": webhook.id,
+        }))),
+        Err(err) => {
+            err.print_stacktrace();
+            Err(ApiResponse::conflict_with_error(err))
+        }
+    }
+}
+
+/// Update url, secret, event filter & state of a webhook.
+#[put("/teacher/webhook/<webhook_id>", format = "json", data = "<webhook>")]
+pub fn update_webhook(
+    user: User,
+    conn: IprpDB,
+    webhook_id: u64,
+    webhook: RouteUpdateWebhook,
+) -> Result<Json<JsonValue>, ApiResponse> {
+    if user.role == Role::Student {
+        return Err(ApiResponse::forbidden());
+    }
+
+    let res = db::webhooks::update(
+        &*conn,
+        &user,
+        webhook_id,
+        webhook.url,
+        webhook.secret,
+        webhook.events,
+        webhook.active,
+    );
+    match res {
+        Ok(_) => Ok(Json(json!({
+            "ok": true,
+        }))),
+        Err(err) => {
+            err.print_stacktrace();
+            Err(ApiResponse::conflict_with_error(err))
+        }
+    }
+}
+
+/// Delete a webhook.
+#[delete("/teacher/webhook/<webhook_id>")]
+pub fn delete_webhook(
+    user: User,
+    conn: IprpDB,
+    webhook_id: u64,
+) -> Result<Json<JsonValue>, ApiResponse> {
+    if user.role == Role::Student {
+        return Err(ApiResponse::forbidden());
+    }
+
+    let res = db::webhooks::delete(&*conn, &user, webhook_id);
+    match res {
+        Ok(_) => Ok(Json(json!({
+            "ok": true,
+        }))),
+        Err(err) => {
+            err.print_stacktrace();
+            Err(ApiResponse::not_found_with_error(err))
+        }
+    }
+}
+
+/// Get the delivery log of a webhook, latest first.
+#[get("/teacher/webhook/<webhook_id>/deliveries")]
+pub fn webhook_deliveries(
+    user: User,
+    conn: IprpDB,
+    webhook_id: u64,
+) -> Result<Json<JsonValue>, ApiResponse> {
+    if user.role == Role::Student {
+        return Err(ApiResponse::forbidden());
+    }
+
+    let res = db::webhooks::get_deliveries(&*conn, &user, webhook_id);
+    match res {
+        Ok(deliveries) => Ok(Json(json!({
+            "ok": true,
+            "deliveries": deliveries,
+        }))),
+        Err(err) => {
+            err.print_stacktrace();
+            Err(ApiResponse::not_found_with_error(err))
+        }
+    }
+}
+
 /// Create new workshop.
 #[post("/teacher/workshop", format = "json", data = "<new_workshop>")]
 pub fn create_workshop(
@@ -77,19 +679,10 @@ pub fn create_workshop(
         new_workshop.teachers.0.push(user.id);
     }
 
-    // Check if proper timespan was already given
-    // If not, use default value
-    if new_workshop.review_timespan.is_none() {
-        new_workshop.review_timespan = Some(review_timespan.inner().in_minutes())
-    }
-
     let workshop = db::workshops::create(
         &*conn,
         user.id,
-        new_workshop.title,
-        new_workshop.content,
-        new_workshop.end.0,
-        new_workshop.review_timespan.unwrap(),
+        new_workshop.settings(review_timespan.inner().in_minutes()),
         new_workshop.anonymous,
         Vec::from(new_workshop.teachers),
         Vec::from(new_workshop.students),
@@ -131,20 +724,11 @@ pub fn update_workshop(
         update_workshop.teachers.0.push(user.id);
     }
 
-    // Check if proper timespan was already given
-    // If not, use default value
-    if update_workshop.review_timespan.is_none() {
-        update_workshop.review_timespan = Some(review_timespan.inner().in_minutes())
-    }
-
     let workshop = db::workshops::update(
         &*conn,
         user.id,
         workshop_id,
-        update_workshop.title,
-        update_workshop.content,
-        update_workshop.end.0,
-        update_workshop.review_timespan.unwrap(),
+        update_workshop.settings(review_timespan.inner().in_minutes()),
         Vec::from(update_workshop.teachers),
         Vec::from(update_workshop.students),
         Vec::from(update_workshop.criteria),
@@ -175,6 +759,88 @@ pub fn delete_workshop(user: User, conn: IprpDB, id: u64) -> Result<Json<JsonVal
     }
 }
 
+/// Create review for a submission.
+/// Returns the existing review if the teacher already reviews the submission.
+#[post("/teacher/submission/<submission_id>/review")]
+pub fn create_review(
+    user: User,
+    conn: IprpDB,
+    submission_id: u64,
+) -> Result<Json<JsonValue>, ApiResponse> {
+    if user.role == Role::Student {
+        return Err(ApiResponse::forbidden());
+    }
+
+    let review = db::reviews::create_teacher_review(&*conn, submission_id, user.id);
+    match review {
+        Ok(review) => Ok(Json(json!({
+            "ok": true,
+            "id": review.id
+        }))),
+        Err(err) => {
+            err.print_stacktrace();
+            Err(ApiResponse::conflict_with_error(err))
+        }
+    }
+}
+
+/// Override points of a submission.
+#[put(
+    "/teacher/submission/<submission_id>/points",
+    format = "json",
+    data = "<override_points>"
+)]
+pub fn override_points(
+    user: User,
+    conn: IprpDB,
+    submission_id: u64,
+    override_points: RouteOverridePoints,
+) -> Result<Json<JsonValue>, ApiResponse> {
+    if user.role == Role::Student {
+        return Err(ApiResponse::forbidden());
+    }
+
+    let update = db::submissions::override_points(
+        &*conn,
+        submission_id,
+        user.id,
+        override_points.points,
+        override_points.justification,
+    );
+    match update {
+        Ok(_) => Ok(Json(json!({
+            "ok": true,
+        }))),
+        Err(err) => {
+            err.print_stacktrace();
+            Err(ApiResponse::conflict_with_error(err))
+        }
+    }
+}
+
+/// Revert overridden points of a submission.
+#[delete("/teacher/submission/<submission_id>/points")]
+pub fn reset_points(
+    user: User,
+    conn: IprpDB,
+    submission_id: u64,
+) -> Result<Json<JsonValue>, ApiResponse> {
+    if user.role == Role::Student {
+        return Err(ApiResponse::forbidden());
+    }
+
+    let update = db::submissions::reset_points(&*conn, submission_id, user.id);
+    match update {
+        Ok(_) => Ok(Json(json!({
+            "ok": true,
+        }))),
+        Err(err) => {
+            err.print_stacktrace();
+            Err(ApiResponse::not_found_with_error(err))
+        }
+    }
+}
+
 /// Search students.
 /// Different Query Parameter yield different results.
 #[get("/teacher/search/student?<all>&<id>&<firstname>&<lastname>&<group>")]
@@ -281,6 +947,54 @@ impl<'v> FromFormValue<'v> for Date {
     }*/
 }
 
+impl<'v> FromFormValue<'v> for Aggregation {
+    type Error = &'v RawStr;
+
+    fn from_form_value(form_value: &'v RawStr) -> Result<Self, Self::Error> {
+        Aggregation::from(form_value.as_str()).map_err(|_| form_value)
+    }
+}
+
+impl<'v> FromFormValue<'v> for ExportFormat {
+    type Error = &'v RawStr;
+
+    fn from_form_value(form_value: &'v RawStr) -> Result<Self, Self::Error> {
+        ExportFormat::from(form_value.as_str()).map_err(|_| form_value)
+    }
+}
+
+impl<'v> FromFormValue<'v> for GradeScale {
+    type Error = &'v RawStr;
+
+    fn from_form_value(form_value: &'v RawStr) -> Result<Self, Self::Error> {
+        GradeScale::from(form_value.as_str()).map_err(|_| form_value)
+    }
+}
+
+impl<'v> FromFormValue<'v> for DraftPolicy {
+    type Error = &'v RawStr;
+
+    fn from_form_value(form_value: &'v RawStr) -> Result<Self, Self::Error> {
+        DraftPolicy::from(form_value.as_str()).map_err(|_| form_value)
+    }
+}
+
+impl<'v> FromFormValue<'v> for LatePolicy {
+    type Error = &'v RawStr;
+
+    fn from_form_value(form_value: &'v RawStr) -> Result<Self, Self::Error> {
+        LatePolicy::from(form_value.as_str()).map_err(|_| form_value)
+    }
+}
+
+impl<'v> FromFormValue<'v> for RoundPolicy {
+    type Error = &'v RawStr;
+
+    fn from_form_value(form_value: &'v RawStr) -> Result<Self, Self::Error> {
+        RoundPolicy::from(form_value.as_str()).map_err(|_| form_value)
+    }
+}
+
 impl<'v> FromFormValue<'v> for NumberVec {
     type Error = &'v RawStr;
 
@@ -1,8 +1,12 @@
 use crate::db::models::*;
+use crate::utils::error::AppError;
 use crate::{db, IprpDB};
-use rocket::http::{Cookie, Cookies, Status};
+use rocket::http::{ContentType, Cookie, Cookies, Status};
+use rocket::response::content::Content;
 
-use crate::routes::models::{RouteCreateStudent, RouteCreateTeacher};
+use crate::routes::models::{
+    ApiResponse, RouteCreateStudent, RouteCreateTeacher, RouteEmailPreferences,
+};
 use rocket_contrib::json;
 use rocket_contrib::json::{Json, JsonValue};
 
@@ -41,6 +45,7 @@ pub fn create_student(
         create_info.lastname,
         hashed_password,
         create_info.unit,
+        create_info.email,
     );
     return match user {
         Ok(user) => Ok(json::Json(user.id)),
@@ -66,6 +71,7 @@ pub fn create_teacher(
         create_info.firstname,
         create_info.lastname,
         hashed_password,
+        create_info.email,
     );
     return match user {
         Ok(user) => Ok(json::Json(user.id)),
@@ -73,6 +79,107 @@ pub fn create_teacher(
     };
 }
 
+/// Get email address & notification preferences of the current user.
+#[get("/user/notifications")]
+pub fn email_preferences(user: User, conn: IprpDB) -> Json<JsonValue> {
+    let preference = db::emails::get_preference(&*conn, user.id);
+    Json(json!({
+        "ok": true,
+        "email": user.email,
+        "preferences": preference
+    }))
+}
+
+/// Update email address & notification preferences of the current user.
+#[put("/user/notifications", format = "json", data = "<preferences>")]
+pub fn update_email_preferences(
+    user: User,
+    conn: IprpDB,
+    preferences: RouteEmailPreferences,
+) -> Result<Json<JsonValue>, ApiResponse> {
+    let preference = EmailPreference {
+        user: user.id,
+        assigned: preferences.assigned,
+        deadline: preferences.deadline,
+        received: preferences.received,
+        workshop: preferences.workshop,
+    };
+    let update = db::emails::update_preference(&*conn, user.id, preferences.email, preference);
+
+    match update {
+        Ok(_) => Ok(Json(json!({
+            "ok": true
+        }))),
+        Err(err) => {
+            err.print_stacktrace();
+            Err(ApiResponse::conflict_with_error(err))
+        }
+    }
+}
+
+/// Get the calendar feed url of the current user.
+/// The token is created on first access.
+#[get("/user/calendar")]
+pub fn calendar(user: User, conn: IprpDB) -> Result<Json<JsonValue>, ApiResponse> {
+    let token = db::calendar::get_token(&*conn, user.id);
+
+    match token {
+        Ok(token) => Ok(Json(json!({
+            "ok": true,
+            "token": token,
+            "url": format!("/calendar/{}.ics", token)
+        }))),
+        Err(err) => {
+            err.print_stacktrace();
+            Err(ApiResponse::not_found_with_error(err))
+        }
+    }
+}
+
+/// Replace the calendar token of the current user.
+/// Feed urls with the old token stop working.
+#[post("/user/calendar/reset")]
+pub fn reset_calendar(user: User, conn: IprpDB) -> Result<Json<JsonValue>, ApiResponse> {
+    let token = db::calendar::reset_token(&*conn, user.id);
+
+    match token {
+        Ok(token) => Ok(Json(json!({
+            "ok": true,
+            "token": token,
+            "url": format!("/calendar/{}.ics", token)
+        }))),
+        Err(err) => {
+            err.print_stacktrace();
+            Err(ApiResponse::conflict_with_error(err))
+        }
+    }
+}
+
+/// Get iCalendar feed with the deadlines of an user.
+/// No login needed, calendar applications are authenticated through the token.
+#[get("/calendar/<token>")]
+pub fn calendar_feed(conn: IprpDB, token: String) -> Result<Content<String>, Status> {
+    let token = token.trim_end_matches(".ics");
+    let user = db::calendar::get_user_by_token(&*conn, token);
+    if user.is_err() {
+        return Err(Status::NotFound);
+    }
+    let user = user.unwrap();
+
+    let events = db::calendar::get_events(&*conn, &user);
+    match events {
+        Ok(events) => {
+            let stamp = chrono::Utc::now().naive_utc();
+            let feed = crate::utils::calendar::render("IPRP", &events, &stamp);
+            Ok(Content(ContentType::Calendar, feed))
+        }
+        Err(err) => {
+            err.print_stacktrace();
+            Err(Status::InternalServerError)
+        }
+    }
+}
+
 /*// See: https://github.com/Keats/validator
 use crate::routes::validation::SimpleValidation;
 use validator::{Validate, ValidationError, ValidationErrors};
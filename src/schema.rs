@@ -2,6 +2,21 @@
 // the use crate::models::* import, some do not
 #![allow(unused_imports)]
 
+table! {
+    use diesel::sql_types::*;
+    use crate::models::*;
+
+    appeals (id) {
+        id -> Unsigned<Bigint>,
+        review -> Unsigned<Bigint>,
+        student -> Nullable<Unsigned<Bigint>>,
+        reason -> Text,
+        status -> AppealStatusMapping,
+        response -> Nullable<Text>,
+        date -> Datetime,
+    }
+}
+
 table! {
     use diesel::sql_types::*;
     use crate::models::*;
@@ -13,6 +28,17 @@ table! {
     }
 }
 
+table! {
+    use diesel::sql_types::*;
+    use crate::models::*;
+
+    calendartokens (user) {
+        user -> Unsigned<Bigint>,
+        token -> Varchar,
+        created -> Datetime,
+    }
+}
+
 table! {
     use diesel::sql_types::*;
     use crate::models::*;
@@ -33,6 +59,84 @@ table! {
         content -> Text,
         weight -> Double,
         kind -> KindMapping,
+        minpoints -> Nullable<Double>,
+        maxpoints -> Nullable<Double>,
+        step -> Nullable<Double>,
+    }
+}
+
+table! {
+    use diesel::sql_types::*;
+    use crate::models::*;
+
+    emailpreferences (user) {
+        user -> Unsigned<Bigint>,
+        assigned -> Bool,
+        deadline -> Bool,
+        received -> Bool,
+        workshop -> Bool,
+    }
+}
+
+table! {
+    use diesel::sql_types::*;
+    use crate::models::*;
+
+    emailqueue (id) {
+        id -> Unsigned<Bigint>,
+        recipient -> Unsigned<Bigint>,
+        address -> Varchar,
+        kind -> EmailKindMapping,
+        subject -> Varchar,
+        body -> Text,
+        attempts -> Integer,
+        sent -> Bool,
+        failed -> Bool,
+        created -> Datetime,
+        nextattempt -> Datetime,
+        lasterror -> Nullable<Text>,
+    }
+}
+
+table! {
+    use diesel::sql_types::*;
+    use crate::models::*;
+
+    extensionhistory (id) {
+        id -> Unsigned<Bigint>,
+        workshop -> Unsigned<Bigint>,
+        student -> Unsigned<Bigint>,
+        review -> Nullable<Unsigned<Bigint>>,
+        teacher -> Nullable<Unsigned<Bigint>>,
+        end -> Nullable<Datetime>,
+        date -> Datetime,
+    }
+}
+
+table! {
+    use diesel::sql_types::*;
+    use crate::models::*;
+
+    notifications (id) {
+        id -> Unsigned<Bigint>,
+        user -> Unsigned<Bigint>,
+        kind -> NotificationKindMapping,
+        title -> Varchar,
+        content -> Text,
+        workshop -> Nullable<Unsigned<Bigint>>,
+        submission -> Nullable<Unsigned<Bigint>>,
+        seen -> Bool,
+        date -> Datetime,
+    }
+}
+
+table! {
+    use diesel::sql_types::*;
+    use crate::models::*;
+
+    reviewextensions (review) {
+        review -> Unsigned<Bigint>,
+        end -> Datetime,
     }
 }
 
@@ -44,6 +148,18 @@ table! {
         review -> Unsigned<Bigint>,
         criterion -> Unsigned<Bigint>,
         points -> Nullable<Double>,
+        comment -> Nullable<Text>,
+    }
+}
+
+table! {
+    use diesel::sql_types::*;
+    use crate::models::*;
+
+    reviewratings (review) {
+        review -> Unsigned<Bigint>,
+        helpfulness -> Integer,
+        comment -> Nullable<Text>,
     }
 }
 
@@ -61,6 +177,24 @@ table! {
         done -> Bool,
         locked -> Bool,
         error -> Bool,
+        teacher -> Bool,
+        discarded -> Bool,
+        selfassessment -> Bool,
+        version -> Nullable<Unsigned<Bigint>>,
+        reminded -> Bool,
+    }
+}
+
+table! {
+    use diesel::sql_types::*;
+    use crate::models::*;
+
+    rubriclevels (id) {
+        id -> Unsigned<Bigint>,
+        criterion -> Unsigned<Bigint>,
+        title -> Varchar,
+        content -> Text,
+        points -> Double,
     }
 }
 
@@ -84,6 +218,17 @@ table! {
     }
 }
 
+table! {
+    use diesel::sql_types::*;
+    use crate::models::*;
+
+    submissionextensions (workshop, student) {
+        workshop -> Unsigned<Bigint>,
+        student -> Unsigned<Bigint>,
+        end -> Datetime,
+    }
+}
+
 table! {
     use diesel::sql_types::*;
     use crate::models::*;
@@ -101,6 +246,48 @@ table! {
         meanpoints -> Nullable<Double>,
         maxpoint -> Nullable<Double>,
         deadline -> Datetime,
+        computedpoints -> Nullable<Double>,
+        justification -> Nullable<Text>,
+        calibration -> Bool,
+        late -> Bool,
+        team -> Nullable<Unsigned<Bigint>>,
+        round -> Integer,
+        previous -> Nullable<Unsigned<Bigint>>,
+    }
+}
+
+table! {
+    use diesel::sql_types::*;
+    use crate::models::*;
+
+    submissionversions (id) {
+        id -> Unsigned<Bigint>,
+        submission -> Unsigned<Bigint>,
+        version -> Integer,
+        title -> Varchar,
+        comment -> Text,
+        date -> Datetime,
+    }
+}
+
+table! {
+    use diesel::sql_types::*;
+    use crate::models::*;
+
+    teammembers (team, student) {
+        team -> Unsigned<Bigint>,
+        student -> Unsigned<Bigint>,
+    }
+}
+
+table! {
+    use diesel::sql_types::*;
+    use crate::models::*;
+
+    teams (id) {
+        id -> Unsigned<Bigint>,
+        workshop -> Unsigned<Bigint>,
+        title -> Varchar,
     }
 }
 
@@ -116,6 +303,62 @@ table! {
         password -> Varchar,
         role -> RoleMapping,
         unit -> Nullable<Varchar>,
+        email -> Nullable<Varchar>,
+    }
+}
+
+table! {
+    use diesel::sql_types::*;
+    use crate::models::*;
+
+    versionattachments (version, attachment) {
+        version -> Unsigned<Bigint>,
+        attachment -> Unsigned<Bigint>,
+    }
+}
+
+table! {
+    use diesel::sql_types::*;
+    use crate::models::*;
+
+    webhookdeliveries (id) {
+        id -> Unsigned<Bigint>,
+        webhook -> Unsigned<Bigint>,
+        event -> WebhookEventKindMapping,
+        payload -> Text,
+        attempts -> Integer,
+        delivered -> Bool,
+        failed -> Bool,
+        status -> Nullable<Integer>,
+        lasterror -> Nullable<Text>,
+        created -> Datetime,
+        nextattempt -> Datetime,
+        lastattempt -> Nullable<Datetime>,
+    }
+}
+
+table! {
+    use diesel::sql_types::*;
+    use crate::models::*;
+
+    webhookevents (webhook, event) {
+        webhook -> Unsigned<Bigint>,
+        event -> WebhookEventKindMapping,
+    }
+}
+
+table! {
+    use diesel::sql_types::*;
+    use crate::models::*;
+
+    webhooks (id) {
+        id -> Unsigned<Bigint>,
+        owner -> Unsigned<Bigint>,
+        workshop -> Nullable<Unsigned<Bigint>>,
+        url -> Varchar,
+        secret -> Varchar,
+        active -> Bool,
+        created -> Datetime,
     }
 }
 
@@ -151,38 +394,99 @@ table! {
         end -> Datetime,
         anonymous -> Bool,
         reviewtimespan -> Bigint,
+        teacherweight -> Double,
+        aggregation -> AggregationMapping,
+        gradescale -> GradeScaleMapping,
+        commentthreshold -> Nullable<Double>,
+        draftpolicy -> DraftPolicyMapping,
+        reviewingweight -> Double,
+        appealwindow -> Bigint,
+        selfassessment -> Bool,
+        submissionstart -> Nullable<Datetime>,
+        submissionend -> Nullable<Datetime>,
+        reviewend -> Nullable<Datetime>,
+        resultsdate -> Nullable<Datetime>,
+        latepolicy -> LatePolicyMapping,
+        latepenalty -> Double,
+        rounds -> Integer,
+        samereviewers -> Bool,
+        roundpolicy -> RoundPolicyMapping,
     }
 }
 
+joinable!(appeals -> reviews (review));
+joinable!(appeals -> users (student));
 joinable!(attachments -> users (owner));
 joinable!(criteria -> criterion (criterion));
 joinable!(criteria -> workshops (workshop));
+joinable!(calendartokens -> users (user));
+joinable!(emailpreferences -> users (user));
+joinable!(emailqueue -> users (recipient));
+joinable!(extensionhistory -> reviews (review));
+joinable!(extensionhistory -> workshops (workshop));
+joinable!(notifications -> submissions (submission));
+joinable!(notifications -> users (user));
+joinable!(notifications -> workshops (workshop));
+joinable!(reviewextensions -> reviews (review));
 joinable!(reviewpoints -> criterion (criterion));
 joinable!(reviewpoints -> submissions (review));
+joinable!(reviewratings -> reviews (review));
 joinable!(reviews -> submissions (submission));
+joinable!(reviews -> submissionversions (version));
 joinable!(reviews -> users (reviewer));
 joinable!(reviews -> workshops (workshop));
+joinable!(rubriclevels -> criterion (criterion));
 joinable!(submissionattachments -> attachments (attachment));
 joinable!(submissionattachments -> submissions (submission));
 joinable!(submissioncriteria -> criterion (criterion));
 joinable!(submissioncriteria -> submissions (submission));
+joinable!(submissionextensions -> users (student));
+joinable!(submissionextensions -> workshops (workshop));
+joinable!(submissions -> teams (team));
 joinable!(submissions -> users (student));
 joinable!(submissions -> workshops (workshop));
+joinable!(submissionversions -> submissions (submission));
+joinable!(teammembers -> teams (team));
+joinable!(teammembers -> users (student));
+joinable!(teams -> workshops (workshop));
+joinable!(versionattachments -> attachments (attachment));
+joinable!(versionattachments -> submissionversions (version));
+joinable!(webhookdeliveries -> webhooks (webhook));
+joinable!(webhookevents -> webhooks (webhook));
+joinable!(webhooks -> users (owner));
+joinable!(webhooks -> workshops (workshop));
 joinable!(workshopattachments -> attachments (attachment));
 joinable!(workshopattachments -> workshops (workshop));
 joinable!(workshoplist -> users (user));
 joinable!(workshoplist -> workshops (workshop));
 
 allow_tables_to_appear_in_same_query!(
+    appeals,
     attachments,
+    calendartokens,
     criteria,
     criterion,
+    emailpreferences,
+    emailqueue,
+    extensionhistory,
+    notifications,
+    reviewextensions,
     reviewpoints,
+    reviewratings,
     reviews,
+    rubriclevels,
     submissionattachments,
     submissioncriteria,
+    submissionextensions,
     submissions,
+    submissionversions,
+    teammembers,
+    teams,
     users,
+    versionattachments,
+    webhookdeliveries,
+    webhookevents,
+    webhooks,
     workshopattachments,
     workshoplist,
     workshops,
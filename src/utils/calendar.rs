@@ -0,0 +1,116 @@
+//! Rendering of iCalendar feeds.
+//! See: https://datatracker.ietf.org/doc/html/rfc5545
+
+const DATE_TIME_FORMAT: &str = "%Y%m%dT%H%M%S";
+// Maximal length of a content line in octets, longer lines are folded
+const LINE_LIMIT: usize = 75;
+
+/// Event of an iCalendar feed.
+/// Times are local server times without time zone.
+#[derive(Debug, PartialEq)]
+pub struct CalendarEvent {
+    pub uid: String,
+    pub summary: String,
+    pub description: String,
+    pub start: chrono::NaiveDateTime,
+    pub end: Option<chrono::NaiveDateTime>,
+}
+
+/// Render events as iCalendar feed.
+/// `stamp` is the UTC time the feed was created.
+pub fn render(name: &str, events: &[CalendarEvent], stamp: &chrono::NaiveDateTime) -> String {
+    let mut lines = vec![
+        String::from("BEGIN:VCALENDAR"),
+        String::from("VERSION:2.0"),
+        String::from("PRODID:-//IPRP//Deadlines//EN"),
+        String::from("CALSCALE:GREGORIAN"),
+        String::from("METHOD:PUBLISH"),
+        format!("X-WR-CALNAME:{}", escape(name)),
+    ];
+    for event in events {
+        lines.push(String::from("BEGIN:VEVENT"));
+        lines.push(format!("UID:{}", escape(&event.uid)));
+        lines.push(format!("DTSTAMP:{}Z", stamp.format(DATE_TIME_FORMAT)));
+        lines.push(format!("DTSTART:{}", event.start.format(DATE_TIME_FORMAT)));
+        if let Some(end) = event.end {
+            lines.push(format!("DTEND:{}", end.format(DATE_TIME_FORMAT)));
+        }
+        lines.push(format!("SUMMARY:{}", escape(&event.summary)));
+        lines.push(format!("DESCRIPTION:{}", escape(&event.description)));
+        lines.push(String::from("END:VEVENT"));
+    }
+    lines.push(String::from("END:VCALENDAR"));
+
+    let mut feed = lines
+        .iter()
+        .map(|line| fold(line))
+        .collect::<Vec<String>>()
+        .join("\r\n");
+    feed.push_str("\r\n");
+    feed
+}
+
+// Escape special characters of text values.
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\\n")
+        .replace('\n', "\\n")
+}
+
+// Fold content line after 75 octets, continuation lines start with a space.
+// Multi-byte characters are never split.
+fn fold(line: &str) -> String {
+    let mut folded = String::with_capacity(line.len());
+    let mut length = 0;
+    for c in line.chars() {
+        if length + c.len_utf8() > LINE_LIMIT {
+            folded.push_str("\r\n ");
+            length = 1;
+        }
+        folded.push(c);
+        length += c.len_utf8();
+    }
+    folded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    #[test]
+    fn render_event_ok() {
+        let stamp = NaiveDate::from_ymd(2022, 3, 1).and_hms(8, 0, 0);
+        let events = vec![CalendarEvent {
+            uid: "review-1@iprp".to_string(),
+            summary: "Review deadline: Essay".to_string(),
+            description: "Workshop WS".to_string(),
+            start: NaiveDate::from_ymd(2022, 3, 14).and_hms(9, 30, 0),
+            end: None,
+        }];
+        let feed = render("IPRP", &events, &stamp);
+        assert!(feed.starts_with("BEGIN:VCALENDAR\r\nVERSION:2.0\r\n"));
+        assert!(feed.contains("\r\nUID:review-1@iprp\r\nDTSTAMP:20220301T080000Z\r\n"));
+        assert!(feed.contains("\r\nDTSTART:20220314T093000\r\nSUMMARY:"));
+        assert!(!feed.contains("DTEND"));
+        assert!(feed.ends_with("END:VEVENT\r\nEND:VCALENDAR\r\n"));
+    }
+
+    #[test]
+    fn escape_ok() {
+        assert_eq!(escape("a,b;c\\d\ne"), "a\\,b\\;c\\\\d\\ne");
+    }
+
+    #[test]
+    fn fold_long_line_ok() {
+        let line = format!("SUMMARY:{}", "ä".repeat(40));
+        let folded = fold(&line);
+        let parts: Vec<&str> = folded.split("\r\n").collect();
+        assert_eq!(parts.len(), 2);
+        assert!(parts.iter().all(|part| part.len() <= LINE_LIMIT));
+        assert!(parts[1].starts_with(' '));
+        assert_eq!(folded.replace("\r\n ", ""), line);
+    }
+}
@@ -0,0 +1,100 @@
+//! Line based text diff.
+//! Lines that are part of the longest common subsequence of both texts are kept,
+//! all other lines are deleted from the old or inserted from the new text.
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DiffKind {
+    Equal,
+    Insert,
+    Delete,
+}
+
+/// Single line of a diff.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct DiffLine {
+    pub kind: DiffKind,
+    pub line: String,
+}
+
+/// Diff the lines of two texts.
+pub fn diff_lines(old: &str, new: &str) -> Vec<DiffLine> {
+    let old: Vec<&str> = old.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+    let (n, m) = (old.len(), new.len());
+
+    // lcs[i][j] is the length of the longest common subsequence of old[i..] and new[j..]
+    let mut lcs = vec![vec![0usize; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lcs[i][j] = if old[i] == new[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let line = |kind: DiffKind, line: &str| DiffLine {
+        kind,
+        line: line.to_string(),
+    };
+    let mut diff = Vec::with_capacity(n.max(m));
+    let (mut i, mut j) = (0, 0);
+    while i < n && j < m {
+        if old[i] == new[j] {
+            diff.push(line(DiffKind::Equal, old[i]));
+            i += 1;
+            j += 1;
+        } else if lcs[i + 1][j] >= lcs[i][j + 1] {
+            diff.push(line(DiffKind::Delete, old[i]));
+            i += 1;
+        } else {
+            diff.push(line(DiffKind::Insert, new[j]));
+            j += 1;
+        }
+    }
+    diff.extend(old[i..].iter().map(|l| line(DiffKind::Delete, l)));
+    diff.extend(new[j..].iter().map(|l| line(DiffKind::Insert, l)));
+    diff
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kinds(diff: &[DiffLine]) -> Vec<(DiffKind, &str)> {
+        diff.iter().map(|l| (l.kind, l.line.as_str())).collect()
+    }
+
+    #[test]
+    fn diff_lines_equal_ok() {
+        let diff = diff_lines("a\nb", "a\nb");
+        assert_eq!(
+            kinds(&diff),
+            vec![(DiffKind::Equal, "a"), (DiffKind::Equal, "b")]
+        );
+    }
+
+    #[test]
+    fn diff_lines_changed_ok() {
+        let diff = diff_lines("a\nb\nc", "a\nx\nc\nd");
+        assert_eq!(
+            kinds(&diff),
+            vec![
+                (DiffKind::Equal, "a"),
+                (DiffKind::Delete, "b"),
+                (DiffKind::Insert, "x"),
+                (DiffKind::Equal, "c"),
+                (DiffKind::Insert, "d"),
+            ]
+        );
+    }
+
+    #[test]
+    fn diff_lines_empty_ok() {
+        assert!(diff_lines("", "").is_empty());
+        assert_eq!(kinds(&diff_lines("", "a")), vec![(DiffKind::Insert, "a")]);
+        assert_eq!(kinds(&diff_lines("a", "")), vec![(DiffKind::Delete, "a")]);
+    }
+}
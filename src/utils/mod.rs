@@ -1,5 +1,8 @@
+pub mod calendar;
+pub mod diff;
 pub mod error;
 pub mod json;
 mod path;
+pub mod queue;
 
 pub use path::*;
@@ -0,0 +1,107 @@
+//! Background queues in the database, used for emails and webhook deliveries.
+//! Every entry is locked while it is processed so multiple instances never process it twice,
+//! failed entries are retried with exponential backoff.
+
+use crate::db::error::{DbError, DbErrorKind};
+use crate::utils::error::AppError;
+use diesel::prelude::*;
+use diesel::result::Error;
+use rocket::logger::error;
+use std::thread;
+use std::time::Duration;
+
+/// Delay of the next attempt after the given number of failed attempts.
+/// Doubles with every attempt, starting at 2 minutes and capped at about 17 hours.
+pub fn backoff(attempts: i32) -> chrono::Duration {
+    chrono::Duration::minutes(2i64.pow(attempts.max(0).min(10) as u32))
+}
+
+/// Process all pending entries of a queue, every entry in its own transaction.
+/// `lock` locks a pending entry and fails with `NotFound` when it was already processed,
+/// `handle` processes the locked entry and returns if it succeeded.
+/// Returns the number of successfully processed entries.
+pub fn process<T, L, P>(
+    conn: &MysqlConnection,
+    name: &str,
+    pending: Result<Vec<u64>, Error>,
+    lock: L,
+    mut handle: P,
+) -> Result<usize, DbError>
+where
+    L: Fn(&MysqlConnection, u64) -> Result<T, Error>,
+    P: FnMut(&T) -> Result<bool, DbError>,
+{
+    if pending.is_err() {
+        return Err(DbError::new(
+            DbErrorKind::ReadFailed,
+            format!("Could not query {} queue", name),
+        ));
+    }
+
+    let mut processed = 0;
+    for entry_id in pending.unwrap() {
+        let mut t_error: Result<(), DbError> = Ok(());
+        let transaction = conn.transaction::<bool, Error, _>(|| {
+            let entry = match lock(conn, entry_id) {
+                Ok(entry) => entry,
+                // Already processed by another instance
+                Err(Error::NotFound) => return Ok(false),
+                Err(_) => {
+                    return DbError::assign_and_rollback(
+                        &mut t_error,
+                        DbError::new(
+                            DbErrorKind::ReadFailed,
+                            format!("Could not lock {} {}", name, entry_id),
+                        ),
+                    )
+                }
+            };
+            match handle(&entry) {
+                Ok(success) => Ok(success),
+                Err(err) => DbError::assign_and_rollback(&mut t_error, err),
+            }
+        });
+        match transaction {
+            Ok(true) => processed += 1,
+            Ok(false) => {}
+            Err(_) => {
+                if let Err(err) = t_error {
+                    err.print_stacktrace();
+                }
+            }
+        }
+    }
+    Ok(processed)
+}
+
+/// Run a job periodically in a background thread with its own database connection.
+pub fn spawn<F>(name: &'static str, url: String, interval: u64, job: F)
+where
+    F: Fn(&MysqlConnection) -> Result<usize, DbError> + Send + 'static,
+{
+    thread::spawn(move || loop {
+        match MysqlConnection::establish(&url) {
+            Ok(conn) => {
+                if let Err(err) = job(&conn) {
+                    err.print_stacktrace();
+                }
+            }
+            Err(e) => error(&format!("{} could not connect to database: {}", name, e)),
+        }
+        thread::sleep(Duration::from_secs(interval));
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backoff_ok() {
+        assert_eq!(backoff(1), chrono::Duration::minutes(2));
+        assert_eq!(backoff(3), chrono::Duration::minutes(8));
+        assert_eq!(backoff(10), chrono::Duration::minutes(1024));
+        assert_eq!(backoff(25), chrono::Duration::minutes(1024));
+        assert_eq!(backoff(0), chrono::Duration::minutes(1));
+    }
+}
@@ -0,0 +1,234 @@
+use super::signature_header;
+use crate::db;
+use crate::db::error::{DbError, DbErrorKind};
+use crate::db::models::{Webhook, WebhookDelivery};
+use crate::utils::queue;
+use diesel::prelude::*;
+use rocket::logger::error;
+use rocket::Rocket;
+use rocket_contrib::databases::database_config;
+use std::time::Duration;
+use ureq::{Agent, AgentBuilder};
+
+/// Delivery settings of webhooks.
+pub struct WebhookConfig {
+    pub max_attempts: i32,
+}
+
+/// Deliver the payload to the receiver of a webhook.
+/// Returns the status of the response, or the status and error of a failed delivery.
+pub fn deliver(
+    agent: &Agent,
+    webhook: &Webhook,
+    delivery: &WebhookDelivery,
+) -> Result<i32, (Option<i32>, String)> {
+    let response = agent
+        .post(&webhook.url)
+        .set("Content-Type", "application/json")
+        .set("User-Agent", "IPRP-Webhook")
+        .set("X-IPRP-Event", &delivery.event.to_string())
+        .set("X-IPRP-Delivery", &delivery.id.to_string())
+        .set(
+            "X-IPRP-Signature",
+            &signature_header(&webhook.secret, &delivery.payload),
+        )
+        .send_string(&delivery.payload);
+    match response {
+        Ok(response) => Ok(response.status() as i32),
+        Err(ureq::Error::Status(status, _)) => Err((
+            Some(status as i32),
+            format!("Receiver responded with status {}", status),
+        )),
+        Err(e) => Err((None, e.to_string())),
+    }
+}
+
+/// Send all webhook deliveries that are due.
+/// Returns the number of successful deliveries.
+pub fn process_deliveries(
+    conn: &MysqlConnection,
+    config: &WebhookConfig,
+    agent: &Agent,
+) -> Result<usize, DbError> {
+    queue::process(
+        conn,
+        "Webhook Delivery",
+        db::webhooks::get_pending_ids(conn),
+        db::webhooks::lock_pending,
+        |delivery| {
+            let webhook = db::webhooks::get_by_id(conn, delivery.webhook);
+            if webhook.is_err() {
+                return Err(DbError::new(
+                    DbErrorKind::NotFound,
+                    format!("Webhook {} not found", delivery.webhook),
+                ));
+            }
+            let result = deliver(agent, &webhook.unwrap(), delivery);
+            let success = result.is_ok();
+            let update = match result {
+                Ok(status) => db::webhooks::mark_delivered(conn, delivery, status),
+                Err((status, e)) => {
+                    db::webhooks::mark_failed(conn, delivery, status, e, config.max_attempts)
+                }
+            };
+            update.map(|_| success)
+        },
+    )
+}
+
+/// Setup webhook dispatcher from `Rocket.toml` configuration file.
+/// Queued deliveries are sent periodically in the background.
+pub fn setup_webhooks(rocket: Rocket) -> Result<Rocket, Rocket> {
+    let enabled = rocket.config().get_bool("webhooks_enabled").unwrap_or(true);
+    if !enabled {
+        return Ok(rocket);
+    }
+    let interval = rocket
+        .config()
+        .get_int("webhook_interval_seconds")
+        .unwrap_or(10)
+        .max(1) as u64;
+    let timeout = rocket
+        .config()
+        .get_int("webhook_timeout_seconds")
+        .unwrap_or(10)
+        .max(1) as u64;
+    let config = WebhookConfig {
+        max_attempts: rocket.config().get_int("webhook_max_attempts").unwrap_or(5) as i32,
+    };
+    let agent = AgentBuilder::new()
+        .timeout(Duration::from_secs(timeout))
+        .build();
+    let url = match database_config("iprp_db", rocket.config()) {
+        Ok(config) => config.url.to_string(),
+        Err(e) => {
+            error(&format!("Failed to read webhook database config: {:?}", e));
+            return Err(rocket);
+        }
+    };
+
+    queue::spawn("Webhook dispatcher", url, interval, move |conn| {
+        process_deliveries(conn, &config, &agent)
+    });
+    Ok(rocket)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::models::WebhookEventKind;
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+    use std::thread;
+
+    // Minimal HTTP receiver, answers a single request with the given status.
+    // Returns the headers (lowercase names) and the body of the request.
+    fn receive(
+        listener: TcpListener,
+        status: &'static str,
+    ) -> thread::JoinHandle<(Vec<(String, String)>, String)> {
+        thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut headers = Vec::new();
+            let mut line = String::new();
+            reader.read_line(&mut line).unwrap();
+            loop {
+                line.clear();
+                reader.read_line(&mut line).unwrap();
+                let header = line.trim_end();
+                if header.is_empty() {
+                    break;
+                }
+                let (name, value) = header.split_at(header.find(':').unwrap());
+                headers.push((name.to_lowercase(), value[1..].trim().to_string()));
+            }
+            let length = headers
+                .iter()
+                .find(|(name, _)| name == "content-length")
+                .map(|(_, value)| value.parse::<usize>().unwrap())
+                .unwrap_or(0);
+            let mut body = vec![0u8; length];
+            reader.read_exact(&mut body).unwrap();
+            let mut writer = stream;
+            writer
+                .write_all(
+                    format!(
+                        "HTTP/1.1 {}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+                        status
+                    )
+                    .as_bytes(),
+                )
+                .unwrap();
+            (headers, String::from_utf8(body).unwrap())
+        })
+    }
+
+    fn webhook(url: String) -> Webhook {
+        Webhook {
+            id: 1,
+            owner: 1,
+            workshop: None,
+            url,
+            secret: "secret".to_string(),
+            active: true,
+            created: chrono::Local::now().naive_local(),
+        }
+    }
+
+    fn delivery() -> WebhookDelivery {
+        let now = chrono::Local::now().naive_local();
+        WebhookDelivery {
+            id: 7,
+            webhook: 1,
+            event: WebhookEventKind::SubmissionCreated,
+            payload: "{\"submission\":3}".to_string(),
+            attempts: 0,
+            delivered: false,
+            failed: false,
+            status: None,
+            lasterror: None,
+            created: now,
+            nextattempt: now,
+            lastattempt: None,
+        }
+    }
+
+    fn header(headers: &[(String, String)], name: &str) -> String {
+        headers
+            .iter()
+            .find(|(header, _)| header == name)
+            .map(|(_, value)| value.clone())
+            .unwrap_or_default()
+    }
+
+    #[test]
+    fn deliver_ok() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/hook", listener.local_addr().unwrap());
+        let receiver = receive(listener, "204 No Content");
+        let result = deliver(&AgentBuilder::new().build(), &webhook(url), &delivery());
+        assert_eq!(result, Ok(204));
+        let (headers, body) = receiver.join().unwrap();
+        assert_eq!(body, "{\"submission\":3}");
+        assert_eq!(header(&headers, "x-iprp-event"), "submission.created");
+        assert_eq!(header(&headers, "x-iprp-delivery"), "7");
+        assert_eq!(
+            header(&headers, "x-iprp-signature"),
+            signature_header("secret", &body)
+        );
+    }
+
+    #[test]
+    fn deliver_status_err() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/hook", listener.local_addr().unwrap());
+        let receiver = receive(listener, "500 Internal Server Error");
+        let result = deliver(&AgentBuilder::new().build(), &webhook(url), &delivery());
+        assert_eq!(
+            result,
+            Err((Some(500), "Receiver responded with status 500".to_string()))
+        );
+        receiver.join().unwrap();
+    }
+}
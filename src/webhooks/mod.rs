@@ -0,0 +1,53 @@
+//! Outgoing webhooks.
+//! Payloads are signed with HMAC-SHA256 of the shared secret, receivers verify the
+//! `X-IPRP-Signature` header against the raw request body.
+
+mod dispatcher;
+
+pub use dispatcher::*;
+
+use crypto::hmac::Hmac;
+use crypto::mac::Mac;
+use crypto::sha2::Sha256;
+
+/// Prefix of the signature header value.
+pub const SIGNATURE_PREFIX: &str = "sha256=";
+
+/// Sign a payload with the secret of a webhook.
+/// Returns the hex encoded HMAC-SHA256.
+pub fn sign(secret: &str, payload: &str) -> String {
+    let mut hmac = Hmac::new(Sha256::new(), secret.as_bytes());
+    hmac.input(payload.as_bytes());
+    hmac.result()
+        .code()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+/// Value of the signature header of a payload.
+pub fn signature_header(secret: &str, payload: &str) -> String {
+    format!("{}{}", SIGNATURE_PREFIX, sign(secret, payload))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sign_rfc4231_ok() {
+        // Test case 2 of RFC 4231
+        assert_eq!(
+            sign("Jefe", "what do ya want for nothing?"),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+
+    #[test]
+    fn signature_header_ok() {
+        let header = signature_header("secret", "{}");
+        assert!(header.starts_with("sha256="));
+        assert_eq!(header.len(), SIGNATURE_PREFIX.len() + 64);
+        assert_ne!(header, signature_header("other", "{}"));
+    }
+}